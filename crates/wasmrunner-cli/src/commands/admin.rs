@@ -0,0 +1,139 @@
+use anyhow::Result;
+use tracing::info;
+use prettytable::{Table, Row, Cell};
+use wasmrunner_core::config::Config;
+use wasmrunner_store::models::ModerationAction;
+use wasmrunner_store::{PageRequest, StoreClient, StoreError};
+
+/// Apply a moderation action to `target`, an app slug or for yanks `slug@version`
+pub async fn moderate(
+    config: &Config,
+    action: ModerationAction,
+    target: String,
+    reason: Option<String>,
+) -> Result<()> {
+    let (slug, version) = match target.split_once('@') {
+        Some((slug, version)) => (slug.to_string(), Some(version.to_string())),
+        None => (target, None),
+    };
+    if version.is_some() && !matches!(action, ModerationAction::Yank | ModerationAction::Unyank) {
+        return Err(anyhow::anyhow!("Only yank applies to a single version; pass the app slug"));
+    }
+    
+    let client = moderator_client(config).await?;
+    let app = client.app_by_slug(&slug).await?;
+    
+    info!("Moderating {}: {}", slug, action.as_str());
+    let entry = match client
+        .moderate(&app.id, action, version.as_deref(), reason.as_deref())
+        .await
+    {
+        Err(StoreError::NotFound(_)) => {
+            let subject = version.map(|v| format!("{}@{}", slug, v)).unwrap_or(slug);
+            println!("Nothing to {}: {} is unchanged", action.as_str(), subject);
+            return Ok(());
+        }
+        result => result?,
+    };
+    
+    let subject = match &entry.version {
+        Some(version) => format!("{}@{}", slug, version),
+        None if matches!(action, ModerationAction::Yank | ModerationAction::Unyank) => {
+            format!("every version of {}", slug)
+        }
+        None => slug,
+    };
+    let done = match action {
+        ModerationAction::Verify => "Verified",
+        ModerationAction::Unverify => "Removed verification from",
+        ModerationAction::Feature => "Featured",
+        ModerationAction::Unfeature => "Stopped featuring",
+        ModerationAction::Unpublish => "Unpublished",
+        ModerationAction::Publish => "Republished",
+        ModerationAction::Yank => "Yanked",
+        ModerationAction::Unyank => "Restored",
+    };
+    println!("🛡️  {} {}", done, subject);
+    println!("Logged as {}", entry.id);
+    
+    Ok(())
+}
+
+/// Show the moderation log, newest first
+pub async fn log(config: &Config, app: Option<String>, limit: u64) -> Result<()> {
+    let client = moderator_client(config).await?;
+    let app_id = match &app {
+        Some(slug) => Some(client.app_by_slug(slug).await?.id),
+        None => None,
+    };
+    
+    let entries = client
+        .moderation_log(app_id.as_deref(), PageRequest::new(0, limit.max(1)))
+        .await?;
+    
+    if entries.items.is_empty() {
+        println!("No moderation actions recorded");
+        return Ok(());
+    }
+    
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("TIME"),
+        Cell::new("MODERATOR"),
+        Cell::new("ACTION"),
+        Cell::new("APP"),
+        Cell::new("VERSION"),
+        Cell::new("REASON"),
+    ]));
+    
+    for entry in &entries.items {
+        let moderator = entry
+            .actor
+            .as_ref()
+            .map(|actor| actor.username.clone().unwrap_or_else(|| actor.email.clone()))
+            .unwrap_or_else(|| entry.actor_id.clone());
+        let app = entry
+            .app
+            .as_ref()
+            .map(|app| app.slug.clone())
+            .or_else(|| entry.app_id.clone())
+            .unwrap_or_else(|| "(deleted)".to_string());
+        
+        table.add_row(Row::new(vec![
+            Cell::new(&entry.created_at.map(|d| d.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()),
+            Cell::new(&moderator),
+            Cell::new(entry.action.as_str()),
+            Cell::new(&app),
+            Cell::new(entry.version.as_deref().unwrap_or("")),
+            Cell::new(entry.reason.as_deref().unwrap_or("")),
+        ]));
+    }
+    
+    table.printstd();
+    if entries.has_more() {
+        println!("Showing the latest {}; use --limit for more", entries.items.len());
+    }
+    Ok(())
+}
+
+/// A client for the logged-in user, refusing early if they are not a moderator
+///
+/// The server enforces the role on every action; this only gives a clearer
+/// error than a rejected request.
+async fn moderator_client(config: &Config) -> Result<StoreClient> {
+    if std::env::var(super::store::TOKEN_ENV).is_ok_and(|t| !t.trim().is_empty()) {
+        return Err(anyhow::anyhow!(
+            "Moderation requires a password login; unset {} and run `wasmrunner login`",
+            super::store::TOKEN_ENV
+        ));
+    }
+    
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let user = client.current_user().await?;
+    let profile = client.profile(&user.id).await?;
+    if profile.role != "moderator" && profile.role != "admin" {
+        return Err(anyhow::anyhow!("This command requires the moderator or admin role"));
+    }
+    
+    Ok(client)
+}
@@ -0,0 +1,40 @@
+
+use anyhow::Result;
+use tracing::{info, error};
+use wasmrunner_core::registry::{LocalRegistry, ATTACH_SOCKET};
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+
+pub async fn execute(container: String) -> Result<()> {
+    let registry = LocalRegistry::new()?;
+    let container = registry.find_container(&container).await?;
+    
+    if !container.is_running() {
+        error!("Container {} is not running", container.config.name);
+        return Err(anyhow::anyhow!("Cannot attach to a stopped container"));
+    }
+    
+    let socket = registry.container_dir(&container.id).join(ATTACH_SOCKET);
+    let stream = UnixStream::connect(&socket)?;
+    info!("Attached to {}", container.config.name);
+    
+    let interactive = container.config.interactive;
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        if interactive {
+            let mut input = stream.try_clone()?;
+            
+            // Closing host stdin sends EOF to the guest
+            std::thread::spawn(move || {
+                let _ = std::io::copy(&mut std::io::stdin().lock(), &mut input);
+                let _ = input.shutdown(Shutdown::Write);
+            });
+        }
+        
+        // Output ends when the container exits
+        let mut output = stream;
+        std::io::copy(&mut output, &mut std::io::stdout().lock())?;
+        Ok(())
+    }).await??;
+    
+    Ok(())
+}
@@ -2,10 +2,19 @@
 use anyhow::Result;
 use tracing::{info, error};
 use std::path::Path;
+use wasmrunner_core::config::Config;
 
-pub async fn execute(tag: String, context: String, file: String) -> Result<()> {
+pub async fn execute(_config: &Config, tag: String, context: String, file: String, key: Option<String>) -> Result<()> {
     info!("Building WASM container: {} from {}", tag, context);
     
+    // Nothing is stored yet, so a key would sign whatever image was stored under this tag before
+    if let Some(publisher) = key {
+        return Err(anyhow::anyhow!(
+            "Cannot sign as {}: build does not store images yet; sign when publishing with `wasmrunner publish --key`",
+            publisher
+        ));
+    }
+    
     let context_path = Path::new(&context);
     let dockerfile_path = context_path.join(&file);
     
@@ -0,0 +1,46 @@
+
+use anyhow::Result;
+use tracing::{info, error};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::image::ImageStore;
+use wasmrunner_core::trust::TrustStore;
+use wasmrunner_plugins::{CapabilitySet, PluginHost};
+use wasmrunner_runtime::RuntimeManager;
+use std::sync::Arc;
+
+pub async fn execute(config: &Config, image: String, export: String, args: Vec<String>) -> Result<()> {
+    info!("Calling {} in {}", export, image);
+    
+    let image_store = ImageStore::new()?;
+    let trust = TrustStore::from_config(&config.plugins)?;
+    image_store.verify(&image, &trust, config.security.image_policy)?;
+    let wasm_bytes = image_store.load_wasm(&image)?;
+    let imports = image_store.plugin_imports(&image)?;
+    
+    let plugins = Arc::new(PluginHost::from_config(&config.plugins)?);
+    let capabilities = CapabilitySet::from_security(&config.security);
+    let mut runtime_manager = RuntimeManager::with_plugins(plugins, capabilities, imports)?;
+    let runtime = runtime_manager.get_runtime(None)?;
+    runtime.set_fuel_metering(config.runtime.fuel_metering)?;
+    let mut module = runtime.load_module(&wasm_bytes)?;
+    
+    // Look up the export first so argument mistakes are reported against its signature
+    let signature = module.signature(&export)?;
+    info!("{}: {}", export, signature);
+    
+    if signature.params.len() != args.len() {
+        error!("{} expects {} arguments: {}", export, signature.params.len(), signature);
+        return Err(anyhow::anyhow!(
+            "Expected {} arguments, got {}",
+            signature.params.len(),
+            args.len()
+        ));
+    }
+    
+    let results = module.call(&export, &args)?;
+    for result in results {
+        println!("{}", result);
+    }
+    
+    Ok(())
+}
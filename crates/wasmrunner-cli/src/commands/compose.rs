@@ -0,0 +1,372 @@
+use anyhow::Result;
+use tracing::{info, warn};
+use prettytable::{Table, Row, Cell};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use wasmrunner_core::compose::{self, ComposeFile, COMPOSE_FILE, PROJECT_LABEL, SERVICE_LABEL};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::container::{Container, ContainerState, RestartPolicy};
+use wasmrunner_core::events::{EventBus, EventKind};
+use wasmrunner_core::registry::{LocalRegistry, LOG_FILE};
+use wasmrunner_plugins::PluginHost;
+
+/// How long `up` waits for a dependency to start before giving up
+const DEPENDENCY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often container state and log files are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The compose file and project selected by `--file` and `--project-name`
+pub struct ProjectOptions {
+    pub file: Option<PathBuf>,
+    pub project_name: Option<String>,
+}
+
+impl ProjectOptions {
+    /// The compose file: `--file`, else `wasmrunner.compose.toml` in the current directory
+    fn compose_file(&self) -> Result<Option<ComposeFile>> {
+        match &self.file {
+            Some(path) => ComposeFile::load(path).map(Some),
+            None if Path::new(COMPOSE_FILE).is_file() => ComposeFile::load(Path::new(COMPOSE_FILE)).map(Some),
+            None => Ok(None),
+        }
+    }
+    
+    fn require_compose_file(&self) -> Result<ComposeFile> {
+        self.compose_file()?.ok_or_else(|| {
+            anyhow::anyhow!("No {} in the current directory; pass one with --file", COMPOSE_FILE)
+        })
+    }
+    
+    /// `--project-name`, else the compose file's project, else the current directory's name
+    fn project(&self, compose: Option<&ComposeFile>) -> Result<String> {
+        if let Some(name) = &self.project_name {
+            return Ok(compose::project_name(name));
+        }
+        if let Some(compose) = compose {
+            return Ok(compose.project_name());
+        }
+        
+        let dir = std::env::current_dir()?;
+        Ok(compose::project_name(&dir.file_name().unwrap_or_default().to_string_lossy()))
+    }
+}
+
+/// Start the project's services, dependencies first, then follow their logs unless `detach`
+pub async fn up(config: &Config, options: ProjectOptions, services: Vec<String>, detach: bool) -> Result<()> {
+    let compose = options.require_compose_file()?;
+    let project = options.project(Some(&compose))?;
+    let order = compose.start_order(&services)?;
+    info!("Starting project {}: {}", project, order.join(", "));
+    
+    let registry = LocalRegistry::new()?;
+    let existing = registry.containers_labelled(PROJECT_LABEL, &project).await?;
+    
+    // Services already running are left alone; exited ones are replaced
+    let mut containers: HashMap<String, Container> = HashMap::new();
+    let mut stale = Vec::new();
+    for container in existing {
+        match service_of(&container) {
+            Some(service) if order.iter().any(|s| s == service) => {
+                if is_alive(&container) {
+                    containers.insert(service.to_string(), container);
+                } else {
+                    stale.push(container);
+                }
+            }
+            _ => {}
+        }
+    }
+    remove_all(config, &registry, stale).await?;
+    
+    for service in &order {
+        if containers.contains_key(service) {
+            println!("✔ {} is already running", service);
+            continue;
+        }
+        
+        let spec = &compose.services[service];
+        for dependency in &spec.depends_on {
+            wait_for_start(&registry, dependency, &containers[dependency]).await?;
+        }
+        
+        let container_config = spec.container_config(&project, service, &compose.dir)?;
+        let container = super::run::start_detached(config, container_config).await?;
+        println!("🚀 Started {} ({})", service, &container.id[..12]);
+        containers.insert(service.clone(), container);
+    }
+    
+    if detach {
+        return Ok(());
+    }
+    
+    let started: Vec<Container> = order.iter().filter_map(|service| containers.remove(service)).collect();
+    println!("Following logs; press Ctrl-C to stop the services");
+    tokio::select! {
+        result = follow_logs(&registry, &started, true) => result,
+        _ = tokio::signal::ctrl_c() => stop_all(config, &registry, &started).await,
+    }
+}
+
+/// Stop and remove every container of the project, in reverse start order
+pub async fn down(config: &Config, options: ProjectOptions) -> Result<()> {
+    let compose = options.compose_file()?;
+    let project = options.project(compose.as_ref())?;
+    
+    let registry = LocalRegistry::new()?;
+    let mut containers = registry.containers_labelled(PROJECT_LABEL, &project).await?;
+    if containers.is_empty() {
+        println!("No containers for project {}", project);
+        return Ok(());
+    }
+    
+    // Dependencies last, when the compose file still says what they are
+    if let Some(order) = compose.as_ref().and_then(|compose| compose.start_order(&[]).ok()) {
+        let position = |c: &Container| service_of(c).and_then(|s| order.iter().position(|o| o == s));
+        containers.sort_by_key(|c| position(c).unwrap_or(usize::MAX));
+    }
+    
+    stop_all(config, &registry, &containers).await?;
+    remove_all(config, &registry, containers).await
+}
+
+/// List the project's containers
+pub async fn ps(options: ProjectOptions) -> Result<()> {
+    let compose = options.compose_file()?;
+    let project = options.project(compose.as_ref())?;
+    
+    let registry = LocalRegistry::new()?;
+    let containers = registry.containers_labelled(PROJECT_LABEL, &project).await?;
+    if containers.is_empty() {
+        println!("No containers for project {}", project);
+        return Ok(());
+    }
+    
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("SERVICE"),
+        Cell::new("CONTAINER ID"),
+        Cell::new("IMAGE"),
+        Cell::new("STATUS"),
+        Cell::new("PORTS"),
+    ]));
+    
+    for container in &containers {
+        let status = if container.is_running() && !is_alive(container) {
+            "Dead".to_string()
+        } else {
+            container.status()
+        };
+        let ports: Vec<String> = container.config.ports.iter().map(|p| p.to_string()).collect();
+        
+        table.add_row(Row::new(vec![
+            Cell::new(service_of(container).unwrap_or("-")),
+            Cell::new(&container.id[..12]),
+            Cell::new(&container.config.image),
+            Cell::new(&status),
+            Cell::new(&ports.join(", ")),
+        ]));
+    }
+    
+    table.printstd();
+    Ok(())
+}
+
+/// Print the output of the project's services, each line prefixed with its service
+pub async fn logs(options: ProjectOptions, services: Vec<String>, follow: bool) -> Result<()> {
+    let compose = options.compose_file()?;
+    let project = options.project(compose.as_ref())?;
+    
+    let registry = LocalRegistry::new()?;
+    let containers: Vec<Container> = registry
+        .containers_labelled(PROJECT_LABEL, &project)
+        .await?
+        .into_iter()
+        .filter(|c| services.is_empty() || service_of(c).is_some_and(|s| services.iter().any(|x| x == s)))
+        .collect();
+    if containers.is_empty() {
+        println!("No containers for project {}", project);
+        return Ok(());
+    }
+    
+    tokio::select! {
+        result = follow_logs(&registry, &containers, follow) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+fn service_of(container: &Container) -> Option<&str> {
+    container.config.labels.get(SERVICE_LABEL).map(String::as_str)
+}
+
+/// Whether a container is running and its process still exists
+fn is_alive(container: &Container) -> bool {
+    match container.pid {
+        // Safety: signal 0 only checks that the process exists
+        Some(pid) => container.is_running() && unsafe { libc::kill(pid as libc::pid_t, 0) } == 0,
+        None => container.is_running(),
+    }
+}
+
+/// Wait until a dependency's guest has started, failing if it could not start
+async fn wait_for_start(registry: &LocalRegistry, service: &str, container: &Container) -> Result<()> {
+    let deadline = Instant::now() + DEPENDENCY_TIMEOUT;
+    loop {
+        let container = registry.find_container(&container.id).await?;
+        match &container.state {
+            // A dependency that will be restarted gets until the deadline to come up
+            ContainerState::Error(message) if container.config.restart == RestartPolicy::No => {
+                return Err(anyhow::anyhow!("Dependency {} failed to start: {}", service, message));
+            }
+            _ if container.started_at.is_some() => return Ok(()),
+            _ => {}
+        }
+        
+        if Instant::now() >= deadline {
+            return Err(anyhow::anyhow!("Timed out waiting for dependency {} to start", service));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Stop running containers, last first
+async fn stop_all(config: &Config, registry: &LocalRegistry, containers: &[Container]) -> Result<()> {
+    let plugins = PluginHost::from_config(&config.plugins)?;
+    let events = EventBus::new();
+    let handlers = plugins.subscribe_events(&events)?;
+    
+    for container in containers.iter().rev() {
+        let mut container = registry.find_container(&container.id).await?.with_events(events.clone());
+        if !container.is_running() {
+            continue;
+        }
+        
+        // The shim goes down with the guest, so the exit is recorded here
+        if is_alive(&container) {
+            super::remove::kill(&container, libc::SIGTERM);
+            container.mark_exited(128 + libc::SIGTERM).await;
+        } else {
+            container.mark_trapped("Container process exited unexpectedly".to_string()).await;
+        }
+        registry.save_container(&container).await?;
+        println!("🛑 Stopped {}", container.config.name);
+    }
+    
+    events.close();
+    handlers.join();
+    Ok(())
+}
+
+/// Delete containers, killing any that are still running
+async fn remove_all(config: &Config, registry: &LocalRegistry, containers: Vec<Container>) -> Result<()> {
+    if containers.is_empty() {
+        return Ok(());
+    }
+    
+    let plugins = PluginHost::from_config(&config.plugins)?;
+    let events = EventBus::new();
+    let handlers = plugins.subscribe_events(&events)?;
+    
+    for container in containers {
+        let container = container.with_events(events.clone());
+        if is_alive(&container) {
+            super::remove::kill(&container, libc::SIGKILL);
+        }
+        
+        registry.remove_container(&container.id).await?;
+        container.emit(EventKind::Removed).await;
+        println!("🗑️  Removed {}", container.config.name);
+    }
+    
+    events.close();
+    handlers.join();
+    Ok(())
+}
+
+/// Output of one container read so far
+struct LogTail {
+    container_id: String,
+    prefix: String,
+    path: PathBuf,
+    offset: u64,
+    partial: String,
+}
+
+impl LogTail {
+    /// Print complete lines written since the last call; returns whether anything was read
+    fn print_new(&mut self, width: usize) -> Result<bool> {
+        let mut file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+        file.seek(SeekFrom::Start(self.offset))?;
+        
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        if data.is_empty() {
+            return Ok(false);
+        }
+        self.offset += data.len() as u64;
+        
+        self.partial.push_str(&String::from_utf8_lossy(&data));
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            println!("{:width$} | {}", self.prefix, line.trim_end_matches(['\r', '\n']), width = width);
+        }
+        Ok(true)
+    }
+    
+    /// Print an unterminated last line
+    fn flush(&mut self, width: usize) {
+        if !self.partial.is_empty() {
+            println!("{:width$} | {}", self.prefix, self.partial, width = width);
+            self.partial.clear();
+        }
+    }
+}
+
+/// Print containers' output prefixed with their service, until they have all exited when `follow`
+async fn follow_logs(registry: &LocalRegistry, containers: &[Container], follow: bool) -> Result<()> {
+    let mut tails: Vec<LogTail> = containers
+        .iter()
+        .map(|container| LogTail {
+            container_id: container.id.clone(),
+            prefix: service_of(container).unwrap_or(&container.config.name).to_string(),
+            path: registry.container_dir(&container.id).join(LOG_FILE),
+            offset: 0,
+            partial: String::new(),
+        })
+        .collect();
+    let width = tails.iter().map(|tail| tail.prefix.len()).max().unwrap_or(0);
+    
+    loop {
+        // Checked before reading, so output written just before exiting is still printed
+        let mut running = false;
+        for tail in &tails {
+            match registry.find_container(&tail.container_id).await {
+                Ok(container) => running |= is_alive(&container),
+                Err(e) => warn!("{}", e),
+            }
+        }
+        
+        let mut progressed = false;
+        for tail in &mut tails {
+            progressed |= tail.print_new(width)?;
+        }
+        
+        if !follow || (!running && !progressed) {
+            break;
+        }
+        if !progressed {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+    
+    for tail in &mut tails {
+        tail.flush(width);
+    }
+    Ok(())
+}
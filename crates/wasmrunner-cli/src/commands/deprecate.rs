@@ -0,0 +1,40 @@
+use anyhow::Result;
+use tracing::info;
+use wasmrunner_core::config::Config;
+
+/// Attach a deprecation notice to `slug` or `slug@version`, or clear it with `undo`
+pub async fn execute(config: &Config, target: String, message: Option<String>, undo: bool) -> Result<()> {
+    let message = match (message, undo) {
+        (_, true) => None,
+        (Some(message), false) => Some(message),
+        (None, false) => return Err(anyhow::anyhow!("--message is required to deprecate")),
+    };
+    let (slug, version) = match target.split_once('@') {
+        Some((slug, version)) => (slug, Some(version)),
+        None => (target.as_str(), None),
+    };
+    
+    let (client, app) = super::yank::authored_app(config, slug).await?;
+    let subject = match version {
+        Some(version) => {
+            let release = client
+                .app_version(&app.id, version)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("{} has no version {}", slug, version))?;
+            info!("Updating deprecation of {}@{}", slug, version);
+            client.deprecate_version(&release.id, message.as_deref()).await?;
+            format!("{}@{}", slug, version)
+        }
+        None => {
+            info!("Updating deprecation of {}", slug);
+            client.deprecate_app(&app.id, message.as_deref()).await?;
+            slug.to_string()
+        }
+    };
+    
+    match message {
+        Some(_) => println!("⚠️  Deprecated {}; install and run will warn about it", subject),
+        None => println!("✅ {} is no longer deprecated", subject),
+    }
+    Ok(())
+}
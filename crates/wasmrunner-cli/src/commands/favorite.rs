@@ -1,76 +1,78 @@
 
 use anyhow::Result;
-use tracing::{info, error};
-use serde_json::json;
+use tracing::info;
+use prettytable::{Table, Row, Cell};
+use wasmrunner_core::config::Config;
+use wasmrunner_store::{PageRequest, StoreError};
 
-pub async fn execute(app: String, remove: bool) -> Result<()> {
-    let action = if remove { "Removing" } else { "Adding" };
-    info!("{} {} to/from favorites", action, app);
-    
-    // Load auth token
-    let config_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".wasmrunner");
-    
-    let auth_file = config_dir.join("auth.json");
-    if !auth_file.exists() {
-        error!("Not logged in. Run `wasmrunner login` first.");
-        return Err(anyhow::anyhow!("Authentication required"));
-    }
+pub async fn execute(config: &Config, app: Option<String>, remove: bool, list: bool) -> Result<()> {
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let user = client.current_user().await?;
     
-    let auth_data: serde_json::Value = serde_json::from_str(
-        &std::fs::read_to_string(auth_file)?
-    )?;
+    let slug = match app {
+        Some(slug) if !list => slug,
+        _ => return list_favorites(&client, &user.id).await,
+    };
     
-    let access_token = auth_data.get("access_token")
-        .and_then(|t| t.as_str())
-        .ok_or_else(|| anyhow::anyhow!("Invalid auth token"))?;
-    
-    let supabase_url = auth_data.get("supabase_url")
-        .and_then(|u| u.as_str())
-        .unwrap_or("https://your-project.supabase.co");
+    let action = if remove { "Removing" } else { "Adding" };
+    info!("{} {} to/from favorites", action, slug);
     
-    let client = reqwest::Client::new();
+    let app = client.app_by_slug(&slug).await?;
     
     if remove {
-        // Remove favorite
-        let response = client
-            .delete(&format!("{}/rest/v1/user_favorites", supabase_url))
-            .header("apikey", std::env::var("SUPABASE_ANON_KEY").unwrap_or_default())
-            .header("Authorization", format!("Bearer {}", access_token))
-            .header("Content-Type", "application/json")
-            .query(&[("app_id", &format!("eq.{}", app))])
-            .send()
-            .await?;
-        
-        if response.status().is_success() {
-            println!("💔 Removed {} from favorites", app);
-        } else {
-            error!("Failed to remove favorite");
-            return Err(anyhow::anyhow!("Failed to remove favorite"));
+        match client.remove_favorite(&user.id, &app.id).await {
+            Err(StoreError::NotFound(_)) => println!("{} is not in your favorites", slug),
+            result => {
+                result?;
+                println!("💔 Removed {} from favorites", slug);
+            }
         }
     } else {
-        // Add favorite
-        let favorite_data = json!({
-            "app_id": app
-        });
-        
-        let response = client
-            .post(&format!("{}/rest/v1/user_favorites", supabase_url))
-            .header("apikey", std::env::var("SUPABASE_ANON_KEY").unwrap_or_default())
-            .header("Authorization", format!("Bearer {}", access_token))
-            .header("Content-Type", "application/json")
-            .json(&favorite_data)
-            .send()
-            .await?;
-        
-        if response.status().is_success() {
-            println!("⭐ Added {} to favorites", app);
-        } else {
-            error!("Failed to add favorite");
-            return Err(anyhow::anyhow!("Failed to add favorite"));
+        match client.add_favorite(&user.id, &app.id).await {
+            Err(StoreError::Conflict(_)) => println!("{} is already in your favorites", slug),
+            result => {
+                result?;
+                println!("⭐ Added {} to favorites", slug);
+            }
         }
     }
     
     Ok(())
 }
+
+async fn list_favorites(client: &wasmrunner_store::StoreClient, user_id: &str) -> Result<()> {
+    let mut favorites = Vec::new();
+    let mut request = Some(PageRequest::default());
+    while let Some(page_request) = request {
+        let page = client.list_favorite_apps(user_id, page_request).await?;
+        request = page.next_request(page_request.limit);
+        favorites.extend(page.items);
+    }
+    
+    if favorites.is_empty() {
+        println!("No favorites yet. Add one with `wasmrunner favorite <app>`");
+        return Ok(());
+    }
+    
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("NAME"),
+        Cell::new("VERSION"),
+        Cell::new("DOWNLOADS"),
+        Cell::new("STARS"),
+        Cell::new("ADDED"),
+    ]));
+    
+    for favorite in favorites {
+        table.add_row(Row::new(vec![
+            Cell::new(&favorite.app.slug),
+            Cell::new(&favorite.app.version),
+            Cell::new(&favorite.app.download_count.to_string()),
+            Cell::new(&favorite.app.star_count.to_string()),
+            Cell::new(&favorite.created_at.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()),
+        ]));
+    }
+    
+    table.printstd();
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use tracing::{debug, info, warn};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::container::{Container, ContainerState, HealthCheck, HealthTest};
+use wasmrunner_core::events::EventBus;
+use wasmrunner_core::image::ImageStore;
+use wasmrunner_core::registry::LocalRegistry;
+use wasmrunner_plugins::{CapabilitySet, PluginHost};
+use wasmrunner_runtime::{GuestEnvironment, RuntimeManager};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Probe a detached container until its shim exits, recording Running and Unhealthy transitions
+///
+/// The guest runs in the same process and saves its own state transitions,
+/// so each verdict is checked against the saved state before it is written.
+pub(crate) async fn monitor(
+    config: Config,
+    plugins: Arc<PluginHost>,
+    events: EventBus,
+    container_id: String,
+    check: HealthCheck,
+) {
+    let registry = match LocalRegistry::new() {
+        Ok(registry) => registry,
+        Err(e) => {
+            warn!("Health checks disabled: {}", e);
+            return;
+        }
+    };
+    let interval = Duration::from_secs(check.interval_secs.max(1));
+    let timeout = Duration::from_secs(check.timeout_secs.max(1));
+    let start_period = chrono::Duration::seconds(check.start_period_secs as i64);
+    
+    let mut failures = 0;
+    let mut run_started = None;
+    loop {
+        tokio::time::sleep(interval).await;
+        
+        let container = match registry.load_container(&container_id).await {
+            Ok(container) => container,
+            // Removed
+            Err(_) => return,
+        };
+        if !matches!(container.state, ContainerState::Running | ContainerState::Unhealthy) {
+            continue;
+        }
+        
+        // Every restart gets a fresh start period and failure count
+        if container.started_at != run_started {
+            run_started = container.started_at;
+            failures = 0;
+        }
+        let starting = run_started.is_some_and(|started| chrono::Utc::now() - started < start_period);
+        
+        let probe = probe(&config, plugins.clone(), &container, &check.test, timeout);
+        let healthy = match tokio::time::timeout(timeout, probe).await {
+            Ok(Ok(())) => true,
+            Ok(Err(e)) => {
+                debug!("Health check of {} failed: {}", container.config.name, e);
+                false
+            }
+            Err(_) => {
+                debug!("Health check of {} timed out", container.config.name);
+                false
+            }
+        };
+        
+        if healthy {
+            failures = 0;
+        } else if !starting {
+            failures += 1;
+        }
+        
+        let verdict = failures < check.retries.max(1);
+        if let Err(e) = record_health(&registry, &events, &container, verdict).await {
+            warn!("Failed to record health of {}: {}", container.config.name, e);
+        }
+    }
+}
+
+/// Save the verdict of a probe of `probed`, unless its guest stopped or restarted meanwhile
+async fn record_health(registry: &LocalRegistry, events: &EventBus, probed: &Container, healthy: bool) -> Result<()> {
+    // Held from the re-read to the write, so a transition the shim saves in between is never overwritten
+    let lock = registry.lock_container(&probed.id).await?;
+    let mut container = registry.load_container(&probed.id).await?.with_events(events.clone());
+    if container.started_at != probed.started_at
+        || !matches!(container.state, ContainerState::Running | ContainerState::Unhealthy)
+    {
+        return Ok(());
+    }
+    
+    if container.mark_health(healthy).await {
+        info!("Container {} is {}", container.config.name, if healthy { "healthy" } else { "unhealthy" });
+        registry.save_locked(&container, &lock).await?;
+    }
+    Ok(())
+}
+
+async fn probe(
+    config: &Config,
+    plugins: Arc<PluginHost>,
+    container: &Container,
+    test: &HealthTest,
+    timeout: Duration,
+) -> Result<()> {
+    match test {
+        HealthTest::Export(export) => {
+            let config = config.clone();
+            let container = container.clone();
+            let export = export.clone();
+            tokio::task::spawn_blocking(move || call_export(&config, plugins, &container, &export, timeout)).await?
+        }
+        HealthTest::Http(path) => {
+            let port = container
+                .config
+                .ports
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("HTTP health checks need a published port"))?;
+            let url = format!("http://{}{}", probe_address(port.host), path);
+            
+            let status = reqwest::get(&url).await?.status();
+            if status.as_u16() < 400 {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("GET {} returned {}", url, status))
+            }
+        }
+    }
+}
+
+/// Where to connect to reach a listener bound to `host`
+fn probe_address(host: SocketAddr) -> SocketAddr {
+    match host.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => SocketAddr::new(Ipv4Addr::LOCALHOST.into(), host.port()),
+        IpAddr::V6(ip) if ip.is_unspecified() => SocketAddr::new(Ipv6Addr::LOCALHOST.into(), host.port()),
+        _ => host,
+    }
+}
+
+/// Call `export` on a fresh instance of the container's module, with its environment and volumes
+///
+/// The instance traps once `timeout` passes, so a probe stuck in a loop
+/// does not keep its blocking thread after the check has timed out.
+fn call_export(
+    config: &Config,
+    plugins: Arc<PluginHost>,
+    container: &Container,
+    export: &str,
+    timeout: Duration,
+) -> Result<()> {
+    let image_store = ImageStore::new()?;
+    let wasm_bytes = image_store.load_wasm(&container.config.image)?;
+    let imports = image_store.plugin_imports(&container.config.image)?;
+    
+    let mut capabilities = CapabilitySet::from_security(&config.security);
+    for capability in &container.config.capabilities {
+        capabilities.grant(capability);
+    }
+    
+    let mut runtime_manager = RuntimeManager::with_plugins(plugins, capabilities, imports)?;
+    let runtime = runtime_manager.get_runtime(None)?;
+    runtime.set_compile_cache(config.registry.cache_dir.join(super::run::COMPILE_CACHE_DIR));
+    runtime.set_fuel_metering(config.runtime.fuel_metering)?;
+    let mut module = runtime.load_module(&wasm_bytes)?;
+    module.set_memory_limit(container.config.resource_limits.memory_mb * 1024 * 1024);
+    module.set_timeout(timeout);
+    
+    // The guest owns the published sockets, so the probe instance gets none
+    let mut variables: Vec<_> = container.config.environment.clone().into_iter().collect();
+    variables.sort();
+    module.set_environment(GuestEnvironment {
+        variables,
+        volumes: container
+            .config
+            .volumes
+            .iter()
+            .map(|volume| (volume.host.clone(), volume.guest.clone()))
+            .collect(),
+        listeners: Vec::new(),
+    });
+    
+    let results = module.call(export, &[])?;
+    match results.first().map(String::as_str) {
+        None | Some("0") => Ok(()),
+        Some(result) => Err(anyhow::anyhow!("{} returned {}", export, result)),
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use tracing::info;
+use prettytable::{Table, Row, Cell};
+use wasmrunner_core::config::Config;
+use wasmrunner_store::PageRequest;
+
+use super::review::stars;
+
+/// Number of reviews shown under the app details
+const RECENT_REVIEWS: u64 = 5;
+
+pub async fn execute(config: &Config, app: String) -> Result<()> {
+    info!("Fetching details for {}", app);
+    
+    let client = super::store::client(config)?;
+    let store_app = client.app_by_slug(&app).await?;
+    let versions = client.all_versions(&store_app.id).await?;
+    let reviews = client
+        .list_reviews(&store_app.id, PageRequest::new(0, RECENT_REVIEWS))
+        .await?;
+    
+    println!("{} {}", store_app.name, store_app.version);
+    if let Some(description) = &store_app.description {
+        println!("{}", description);
+    }
+    if let Some(message) = &store_app.deprecation_message {
+        println!("⚠️  Deprecated: {}", message);
+    }
+    println!();
+    
+    println!("Slug:       {}", store_app.slug);
+    if let Some(category) = &store_app.category {
+        println!("Category:   {}", category);
+    }
+    if !store_app.tags.is_empty() {
+        println!("Tags:       {}", store_app.tags.join(", "));
+    }
+    println!("License:    {}", store_app.license.as_deref().unwrap_or("unspecified"));
+    if let Some(homepage) = &store_app.homepage_url {
+        println!("Homepage:   {}", homepage);
+    }
+    if let Some(repository) = &store_app.repository_url {
+        println!("Repository: {}", repository);
+    }
+    println!("Downloads:  {}", store_app.download_count);
+    println!("Stars:      {}", store_app.star_count);
+    match store_app.rating_average {
+        Some(average) => println!(
+            "Rating:     {} {:.1}/5 from {} review(s)",
+            stars(average.round() as i32),
+            average,
+            store_app.rating_count
+        ),
+        None => println!("Rating:     no reviews yet"),
+    }
+    if store_app.is_verified {
+        println!("Verified:   ✓");
+    }
+    
+    if !versions.is_empty() {
+        println!();
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![
+            Cell::new("VERSION"),
+            Cell::new("PUBLISHED"),
+            Cell::new("SIZE"),
+            Cell::new("SIGNED"),
+            Cell::new(""),
+        ]));
+        
+        for version in &versions {
+            let label = if version.is_yanked() {
+                "yanked"
+            } else if version.deprecation_message.is_some() {
+                "deprecated"
+            } else if version.is_latest {
+                "latest"
+            } else if version.is_prerelease {
+                "prerelease"
+            } else {
+                ""
+            };
+            table.add_row(Row::new(vec![
+                Cell::new(&version.version),
+                Cell::new(&version.created_at.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()),
+                Cell::new(&format!("{:.1} KB", version.size_bytes as f64 / 1024.0)),
+                Cell::new(version.signature.as_ref().map(|s| s.publisher.as_str()).unwrap_or("")),
+                Cell::new(label),
+            ]));
+        }
+        
+        table.printstd();
+    }
+    
+    if !reviews.items.is_empty() {
+        println!();
+        println!("Recent reviews:");
+        for review in &reviews.items {
+            let date = review.created_at.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default();
+            println!(
+                "  {} {}  {}",
+                stars(review.rating),
+                review.title.as_deref().unwrap_or(""),
+                date
+            );
+            if let Some(content) = &review.content {
+                println!("    {}", content);
+            }
+        }
+        if reviews.has_more() {
+            println!("  ...and {} more", reviews.total.unwrap_or(0).saturating_sub(RECENT_REVIEWS));
+        }
+    }
+    
+    Ok(())
+}
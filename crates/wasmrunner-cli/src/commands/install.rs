@@ -0,0 +1,247 @@
+
+use anyhow::Result;
+use tracing::{info, warn};
+use indicatif::{ProgressBar, ProgressStyle};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::image::{ImageOrigin, ImageStore};
+use wasmrunner_core::reference::ImageReference;
+use wasmrunner_core::signing::{sha256_hex, verify_image, Verification};
+use wasmrunner_core::trust::TrustStore;
+use wasmrunner_store::models::{App, AppVersion, NewDownload};
+use wasmrunner_store::{StoreClient, StoreError};
+
+pub async fn execute(config: &Config, name: String, version: Option<String>) -> Result<()> {
+    let reference = ImageReference::parse(&name)?;
+    if reference.registry.is_some() || reference.namespace.is_some() {
+        return Err(anyhow::anyhow!(
+            "{} is not an app slug; use `wasmrunner pull` for images on a registry",
+            name
+        ));
+    }
+    
+    // `slug:version` is shorthand for `slug --version version`
+    let version = match (reference.tag, version) {
+        (Some(_), Some(_)) => {
+            return Err(anyhow::anyhow!("Give the version either in the name or with --version, not both"));
+        }
+        (tag, version) => tag.or(version),
+    };
+    let slug = reference.name;
+    info!("Installing {} from WasmRunner app store", slug);
+    
+    // Module binaries are only served to signed-in users, so fail before resolving anything
+    let (client, _) = match super::store::authenticated_client(config).await {
+        Err(e) if matches!(e.downcast_ref::<StoreError>(), Some(StoreError::NotLoggedIn)) => {
+            return Err(anyhow::anyhow!(
+                "Installing apps requires signing in: run `wasmrunner login` or set {}",
+                super::store::TOKEN_ENV
+            ));
+        }
+        result => result?,
+    };
+    let app = client.app_by_slug(&slug).await?;
+    let release = match &reference.digest {
+        Some(digest) => release_with_digest(&client, &app, version.as_deref(), digest).await?,
+        None => resolve_version(&client, &app, version.as_deref()).await?,
+    };
+    // The version names the image directory, so a malformed one from the server must not reach the disk
+    semver::Version::parse(&release.version)
+        .map_err(|e| anyhow::anyhow!("{} has an invalid version {:?}: {}", slug, release.version, e))?;
+    if let Some(notice) = super::store::yank_notice(&app, &release) {
+        warn!("{}; installing it because it was pinned exactly", notice);
+    }
+    let deprecation = super::store::deprecation_notice(&app, &release);
+    if let Some(notice) = &deprecation {
+        warn!("{}", notice);
+    }
+    
+    let wasm_bytes = download(&client, &release.wasm_url, &format!("{}.wasm", release.version)).await?;
+    verify_digest(&release, &wasm_bytes)?;
+    
+    let manifest = client.download(&release.manifest_url, |_, _| {}).await?;
+    serde_json::from_slice::<serde_json::Value>(&manifest)
+        .map_err(|e| anyhow::anyhow!("Manifest for {} v{} is not valid JSON: {}", slug, release.version, e))?;
+    
+    let reference = format!("{}:{}", app.slug, release.version);
+    let trust = TrustStore::from_config(&config.plugins)?;
+    let verification = verify_image(
+        &reference,
+        release.signature.as_ref(),
+        &wasm_bytes,
+        Some(&manifest),
+        &trust,
+        config.security.image_policy,
+    )?;
+    
+    let image = ImageStore::new()?.import(&app.slug, &release.version, &wasm_bytes, Some(&manifest))?;
+    if let Some(signature) = &release.signature {
+        image.save_signature(signature)?;
+    }
+    image.save_origin(&ImageOrigin {
+        store_url: client.base_url().to_string(),
+        app: app.slug.clone(),
+        version: release.version.clone(),
+        deprecation,
+    })?;
+    
+    // Counting is best effort; the image is already installed
+    let user_id = client.current_user().await.ok().map(|user| user.id);
+    let download = NewDownload {
+        app_id: app.id.clone(),
+        version: release.version.clone(),
+        user_id,
+        user_agent: Some(format!("wasmrunner/{}", env!("CARGO_PKG_VERSION"))),
+        platform: Some(format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)),
+    };
+    if let Err(e) = client.record_download(&download).await {
+        warn!("Failed to record download: {}", e);
+    }
+    
+    println!("✅ Installed {}:{}", image.name, image.tag);
+    if let Verification::Trusted(publisher) = &verification {
+        println!("🔏 Verified signature from {}", publisher);
+    }
+    println!("🚀 Run: wasmrunner run {}:{}", image.name, image.tag);
+    
+    Ok(())
+}
+
+/// Pick the version to install for an optional version or semver requirement
+///
+/// Without one, the app's latest stable version is used. An exact version
+/// such as `1.2.0` must exist and is used even if it was yanked; a
+/// requirement such as `^1.2` resolves to the highest matching version that
+/// has not been yanked, which only includes prereleases the requirement
+/// names explicitly.
+async fn resolve_version(client: &StoreClient, app: &App, requested: Option<&str>) -> Result<AppVersion> {
+    let requested = match requested {
+        Some(requested) if requested != "latest" => requested,
+        _ => return latest_release(client, app).await,
+    };
+    
+    if semver::Version::parse(requested).is_ok() {
+        return client
+            .app_version(&app.id, requested)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("{} has no version {}", app.slug, requested));
+    }
+    
+    let requirement = semver::VersionReq::parse(requested)
+        .map_err(|e| anyhow::anyhow!("Invalid version requirement {}: {}", requested, e))?;
+    
+    highest(client.all_versions(&app.id).await?, |version| requirement.matches(version))
+        .ok_or_else(|| anyhow::anyhow!("No installable version of {} matches {}", app.slug, requested))
+}
+
+/// The release published with module digest `digest`, for `slug@sha256:<digest>`
+///
+/// Like an exact version, a digest pins the release even if it was yanked.
+async fn release_with_digest(
+    client: &StoreClient,
+    app: &App,
+    requested: Option<&str>,
+    digest: &str,
+) -> Result<AppVersion> {
+    let has_digest = |release: &AppVersion| {
+        release.sha256.as_deref().is_some_and(|sha256| sha256.eq_ignore_ascii_case(digest))
+    };
+    
+    if requested.is_some() {
+        let release = resolve_version(client, app, requested).await?;
+        if !has_digest(&release) {
+            return Err(anyhow::anyhow!(
+                "{} v{} does not have digest sha256:{}",
+                app.slug, release.version, digest
+            ));
+        }
+        return Ok(release);
+    }
+    
+    client
+        .all_versions(&app.id)
+        .await?
+        .into_iter()
+        .find(|release| has_digest(release))
+        .ok_or_else(|| anyhow::anyhow!("{} has no version with digest sha256:{}", app.slug, digest))
+}
+
+/// The version marked latest, or the highest stable version if that was yanked
+async fn latest_release(client: &StoreClient, app: &App) -> Result<AppVersion> {
+    if let Some(latest) = client.latest_version(&app.id).await? {
+        if !latest.is_yanked() {
+            return Ok(latest);
+        }
+    }
+    
+    let versions = client.all_versions(&app.id).await?;
+    if versions.is_empty() {
+        // Apps published before version history was recorded
+        return client
+            .app_version(&app.id, &app.version)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("{} has no published versions", app.slug));
+    }
+    
+    highest(versions, |version| version.pre.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Every stable version of {} has been yanked", app.slug))
+}
+
+/// Highest version that is not yanked and satisfies `accept`
+fn highest(versions: Vec<AppVersion>, accept: impl Fn(&semver::Version) -> bool) -> Option<AppVersion> {
+    versions
+        .into_iter()
+        .filter(|release| !release.is_yanked())
+        .filter_map(|release| {
+            let version = semver::Version::parse(&release.version).ok()?;
+            accept(&version).then_some((version, release))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+}
+
+fn verify_digest(release: &AppVersion, wasm_bytes: &[u8]) -> Result<()> {
+    if release.size_bytes > 0 && release.size_bytes != wasm_bytes.len() as i64 {
+        return Err(anyhow::anyhow!(
+            "Size mismatch for v{}: expected {} bytes, got {}",
+            release.version, release.size_bytes, wasm_bytes.len()
+        ));
+    }
+    
+    let actual = sha256_hex(wasm_bytes);
+    match &release.sha256 {
+        Some(expected) if expected.eq_ignore_ascii_case(&actual) => Ok(()),
+        Some(expected) => Err(anyhow::anyhow!(
+            "Digest mismatch for v{}: expected sha256:{}, got sha256:{}",
+            release.version, expected, actual
+        )),
+        None => {
+            warn!("v{} was published without a digest; downloaded sha256:{}", release.version, actual);
+            Ok(())
+        }
+    }
+}
+
+async fn download(client: &StoreClient, url: &str, label: &str) -> Result<Vec<u8>> {
+    let progress = ProgressBar::new(0);
+    progress.set_style(
+        ProgressStyle::with_template("{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec})")?
+            .progress_chars("=> "),
+    );
+    progress.set_message(format!("Downloading {}", label));
+    
+    let result = client
+        .download(url, |received, total| {
+            if let Some(total) = total {
+                progress.set_length(total);
+            }
+            progress.set_position(received);
+        })
+        .await;
+    
+    match result {
+        Ok(_) => progress.finish(),
+        Err(_) => progress.abandon(),
+    }
+    
+    Ok(result?)
+}
@@ -0,0 +1,52 @@
+
+use anyhow::Result;
+use tracing::info;
+use wasmrunner_core::config::Config;
+use wasmrunner_core::signing::KeyStore;
+use wasmrunner_core::trust::{check_publisher, keys_dir, TrustStore};
+
+pub async fn generate(config: &Config, publisher: Option<String>) -> Result<()> {
+    let publisher = publisher
+        .or_else(|| config.security.signing_key.clone())
+        .ok_or_else(|| anyhow::anyhow!("Give a publisher name or set security.signing_key"))?;
+    info!("Generating signing key for {}", publisher);
+    
+    let keys = KeyStore::new(&config.security.signing_keys_dir);
+    let public_key = hex::encode(keys.generate(&publisher)?.to_bytes());
+    
+    // Images signed on this machine should verify on this machine
+    install_public_key(config, &publisher, &public_key)?;
+    
+    println!("🔑 Generated signing key for {}", publisher);
+    println!("Public key: {}", public_key);
+    print_trust_hint(config, &publisher);
+    
+    Ok(())
+}
+
+pub async fn trust(config: &Config, publisher: String, public_key: String) -> Result<()> {
+    // Validate before writing anything
+    let mut store = TrustStore::default();
+    store.add_hex(&publisher, public_key.trim())?;
+    
+    install_public_key(config, &publisher, public_key.trim())?;
+    
+    println!("✅ Added public key for {}", publisher);
+    print_trust_hint(config, &publisher);
+    
+    Ok(())
+}
+
+fn install_public_key(config: &Config, publisher: &str, public_key: &str) -> Result<()> {
+    check_publisher(publisher)?;
+    let dir = keys_dir(&config.plugins);
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(format!("{}.pub", publisher)), public_key)?;
+    Ok(())
+}
+
+fn print_trust_hint(config: &Config, publisher: &str) {
+    if !config.plugins.trusted_publishers.iter().any(|p| p == publisher) {
+        println!("ℹ️  Add \"{}\" to plugins.trusted_publishers to trust its signatures", publisher);
+    }
+}
@@ -0,0 +1,30 @@
+
+use anyhow::Result;
+use tracing::{info, warn};
+use wasmrunner_core::config::Config;
+use wasmrunner_store::{Credentials, StoreError};
+
+pub async fn execute(config: &Config) -> Result<()> {
+    let path = super::store::credentials_path(config);
+    
+    let credentials = match Credentials::load(&path) {
+        Ok(credentials) => credentials,
+        Err(StoreError::NotLoggedIn) => {
+            println!("Not logged in");
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    info!("Logging out {}", credentials.email);
+    
+    // Revoking is best effort; the local session is removed regardless
+    let client = super::store::client(config)?.with_access_token(&credentials.access_token);
+    if let Err(e) = client.sign_out().await {
+        warn!("Failed to revoke session on the server: {}", e);
+    }
+    
+    Credentials::remove(&path)?;
+    println!("👋 Logged out {}", credentials.email);
+    
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use tracing::error;
+use wasmrunner_core::config::Config;
+use wasmrunner_core::container::{Container, ContainerState};
+use wasmrunner_core::metrics::{Counters, MetricsStore, START_LATENCY_BUCKETS};
+use wasmrunner_core::registry::LocalRegistry;
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Label values of `wasmrunner_containers`, every one reported even when zero
+const STATES: [&str; 6] = ["created", "running", "restarting", "unhealthy", "exited", "error"];
+
+/// Serve this machine's runner metrics for Prometheus on `/metrics`
+pub async fn serve(config: &Config, listen: Option<String>) -> Result<()> {
+    let listen = listen.unwrap_or_else(|| config.telemetry.metrics_listen.clone());
+    let listener = tokio::net::TcpListener::bind(&listen).await?;
+    println!("📈 Serving metrics on http://{}/metrics", listener.local_addr()?);
+    
+    let router = Router::new().route("/metrics", get(scrape));
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+async fn scrape() -> Response {
+    match gather().await {
+        Ok(body) => ([(header::CONTENT_TYPE, CONTENT_TYPE)], body).into_response(),
+        Err(e) => {
+            error!("Failed to gather metrics: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+async fn gather() -> Result<String> {
+    // Loading waits on the lock writers hold
+    let counters = tokio::task::spawn_blocking(|| MetricsStore::new()?.load()).await??;
+    let containers = LocalRegistry::new()?.containers().await?;
+    Ok(render(&counters, &containers))
+}
+
+fn state_label(container: &Container) -> &'static str {
+    match container.state {
+        ContainerState::Created => "created",
+        ContainerState::Running => "running",
+        ContainerState::Restarting => "restarting",
+        ContainerState::Unhealthy => "unhealthy",
+        ContainerState::Stopped => "exited",
+        ContainerState::Error(_) => "error",
+    }
+}
+
+/// The Prometheus text exposition of `counters` and the containers' states
+fn render(counters: &Counters, containers: &[Container]) -> String {
+    let mut by_state: BTreeMap<&str, u64> = STATES.iter().map(|state| (*state, 0)).collect();
+    for container in containers {
+        *by_state.entry(state_label(container)).or_default() += 1;
+    }
+    
+    // Writing to a String cannot fail
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP wasmrunner_containers Containers on this machine by state.");
+    let _ = writeln!(out, "# TYPE wasmrunner_containers gauge");
+    for state in STATES {
+        let _ = writeln!(out, "wasmrunner_containers{{state=\"{}\"}} {}", state, by_state[state]);
+    }
+    
+    counter(
+        &mut out,
+        "wasmrunner_container_starts_total",
+        "Guests started, restarts included.",
+        counters.containers_started,
+    );
+    counter(
+        &mut out,
+        "wasmrunner_container_restarts_total",
+        "Guests started again under a restart policy.",
+        counters.restarts,
+    );
+    counter(&mut out, "wasmrunner_traps_total", "Guests that ended in a WebAssembly trap.", counters.traps);
+    
+    let _ = writeln!(out, "# HELP wasmrunner_limit_kills_total Guests killed for exceeding a resource limit.");
+    let _ = writeln!(out, "# TYPE wasmrunner_limit_kills_total counter");
+    let _ = writeln!(out, "wasmrunner_limit_kills_total{{limit=\"memory\"}} {}", counters.oom_kills);
+    
+    let _ = writeln!(
+        out,
+        "# HELP wasmrunner_compile_cache_lookups_total Module loads by whether compiled code was reused."
+    );
+    let _ = writeln!(out, "# TYPE wasmrunner_compile_cache_lookups_total counter");
+    for (result, count) in [("hit", counters.compile_cache_hits), ("miss", counters.compile_cache_misses)] {
+        let _ = writeln!(out, "wasmrunner_compile_cache_lookups_total{{result=\"{}\"}} {}", result, count);
+    }
+    
+    let _ = writeln!(
+        out,
+        "# HELP wasmrunner_start_latency_seconds Time from starting a container to its guest running."
+    );
+    let _ = writeln!(out, "# TYPE wasmrunner_start_latency_seconds histogram");
+    let mut cumulative = 0;
+    for (i, bound) in START_LATENCY_BUCKETS.iter().enumerate() {
+        cumulative += counters.start_latency_buckets.get(i).copied().unwrap_or(0);
+        let _ = writeln!(out, "wasmrunner_start_latency_seconds_bucket{{le=\"{}\"}} {}", bound, cumulative);
+    }
+    let _ = writeln!(out, "wasmrunner_start_latency_seconds_bucket{{le=\"+Inf\"}} {}", counters.start_latency_count);
+    let _ = writeln!(out, "wasmrunner_start_latency_seconds_sum {}", counters.start_latency_sum_secs);
+    let _ = writeln!(out, "wasmrunner_start_latency_seconds_count {}", counters.start_latency_count);
+    
+    out
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
@@ -1,15 +1,35 @@
 
 pub mod run;
+pub mod call;
+pub mod attach;
+pub mod shim;
 pub mod build;
+pub mod key;
 pub mod push;
 pub mod pull;
+pub mod registry;
 pub mod list;
+pub mod top;
+pub mod metrics;
 pub mod logs;
 pub mod stop;
 pub mod remove;
+pub mod compose;
 pub mod search;
 pub mod install;
 pub mod login;
+pub mod logout;
+pub mod whoami;
+pub mod token;
 pub mod register;
 pub mod publish;
 pub mod favorite;
+pub mod review;
+pub mod info;
+pub mod stats;
+pub mod admin;
+pub mod yank;
+pub mod deprecate;
+
+mod health;
+mod store;
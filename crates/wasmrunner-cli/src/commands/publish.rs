@@ -1,47 +1,47 @@
 
 use anyhow::Result;
-use tracing::{info, error};
-use std::path::Path;
+use tracing::{info, warn};
+use indicatif::{ProgressBar, ProgressStyle};
 use serde_json::json;
+use wasmrunner_core::config::Config;
+use wasmrunner_core::image::{Image, ImageStore};
+use wasmrunner_core::signing::{sha256_hex, ImageSignature, KeyStore};
+use wasmrunner_store::models::{App, AppUpdate, NewApp, NewAppVersion};
+use wasmrunner_store::storage::{MANIFEST_BUCKET, WASM_BUCKET};
+use wasmrunner_store::{StoreClient, StoreError};
 
 pub async fn execute(
+    config: &Config,
     image: String,
     manifest_path: Option<String>,
     description: Option<String>,
     category: Option<String>,
-    tags: Vec<String>
+    tags: Vec<String>,
+    changelog: Option<String>,
+    prerelease: bool,
+    key: Option<String>,
 ) -> Result<()> {
     info!("Publishing {} to WasmRunner app store", image);
     
-    // Load auth token
-    let config_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".wasmrunner");
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let user = client.current_user().await?;
     
-    let auth_file = config_dir.join("auth.json");
-    if !auth_file.exists() {
-        error!("Not logged in. Run `wasmrunner login` first.");
-        return Err(anyhow::anyhow!("Authentication required"));
-    }
-    
-    let auth_data: serde_json::Value = serde_json::from_str(
-        &std::fs::read_to_string(auth_file)?
-    )?;
-    
-    let access_token = auth_data.get("access_token")
-        .and_then(|t| t.as_str())
-        .ok_or_else(|| anyhow::anyhow!("Invalid auth token"))?;
+    // Publish exactly what `run` would execute
+    let image = ImageStore::new()?.resolve(&image)?;
+    let manifest = image.manifest()?;
+    let name = image.name.clone();
     
-    let supabase_url = auth_data.get("supabase_url")
-        .and_then(|u| u.as_str())
-        .unwrap_or("https://your-project.supabase.co");
-    
-    // Parse image name and tag
-    let (name, version) = if let Some((n, v)) = image.split_once(':') {
-        (n.to_string(), v.to_string())
-    } else {
-        (image.clone(), "latest".to_string())
+    // `latest` is a local alias; the store only knows semantic versions
+    let version = match (image.tag.as_str(), &manifest) {
+        ("latest", Some(manifest)) => manifest.version.clone(),
+        (tag, _) => tag.to_string(),
     };
+    let parsed = semver::Version::parse(&version).map_err(|e| {
+        anyhow::anyhow!("Version {} is not a valid semantic version (e.g. 1.2.0): {}", version, e)
+    })?;
+    let prerelease = prerelease || !parsed.pre.is_empty();
+    
+    let wasm_bytes = image.read_wasm()?;
     
     // Create slug from name
     let slug = name.to_lowercase()
@@ -49,58 +49,172 @@ pub async fn execute(
         .map(|c| if c.is_alphanumeric() { c } else { '-' })
         .collect::<String>();
     
-    // Read manifest if provided
+    let existing = match client.app_by_slug(&slug).await {
+        Ok(app) => Some(app),
+        Err(StoreError::NotFound(_)) => None,
+        Err(e) => return Err(e.into()),
+    };
+    
+    if let Some(app) = &existing {
+        if app.author_id != user.id {
+            return Err(anyhow::anyhow!("App {} is owned by another publisher", slug));
+        }
+        if client.app_version(&app.id, &version).await?.is_some() {
+            return Err(anyhow::anyhow!(
+                "{} v{} is already published; bump the version to publish again",
+                slug, version
+            ));
+        }
+    }
+    
+    // Prefer an explicit manifest, then the image's own, then a generated one
     let manifest_content = if let Some(path) = manifest_path {
         std::fs::read_to_string(path)?
+    } else if image.manifest_path().is_file() {
+        std::fs::read_to_string(image.manifest_path())?
     } else {
-        // Generate basic manifest
         json!({
             "name": name,
             "version": version,
-            "description": description.unwrap_or_else(|| format!("WASM application: {}", name)),
+            "description": description.clone().unwrap_or_else(|| format!("WASM application: {}", name)),
             "runtime": "wasmtime",
             "memory_limit": 128,
             "cpu_limit": 100
         }).to_string()
     };
     
-    // TODO: Upload WASM file to storage and get URL
-    let wasm_url = format!("https://storage.supabase.co/wasm/{}/{}.wasm", slug, version);
-    let manifest_url = format!("https://storage.supabase.co/manifests/{}/{}.json", slug, version);
-    
-    // Create app record
-    let client = reqwest::Client::new();
-    let app_data = json!({
-        "name": name,
-        "slug": slug,
-        "description": description.unwrap_or_else(|| format!("WASM application: {}", name)),
-        "version": version,
-        "category": category.unwrap_or_else(|| "utility".to_string()),
-        "tags": tags,
-        "manifest_url": manifest_url,
-        "wasm_url": wasm_url,
-        "is_published": true
-    });
+    let sha256 = sha256_hex(&wasm_bytes);
+    let signature = sign(config, &image, key, &wasm_bytes, manifest_content.as_bytes())?;
     
-    let response = client
-        .post(&format!("{}/rest/v1/apps", supabase_url))
-        .header("apikey", std::env::var("SUPABASE_ANON_KEY").unwrap_or_default())
-        .header("Authorization", format!("Bearer {}", access_token))
-        .header("Content-Type", "application/json")
-        .header("Prefer", "return=representation")
-        .json(&app_data)
-        .send()
-        .await?;
-    
-    if response.status().is_success() {
-        println!("✅ Successfully published {} v{}", name, version);
-        println!("🔍 Search: wasmrunner search {}", name);
-        println!("📦 Install: wasmrunner install {}", slug);
-    } else {
-        let error_text = response.text().await?;
-        error!("Publish failed: {}", error_text);
-        return Err(anyhow::anyhow!("Publish failed: {}", error_text));
+    // Storage policies only allow writes under the uploader's own folder
+    let wasm_object = format!("{}/{}/{}.wasm", user.id, slug, version);
+    let manifest_object = format!("{}/{}/{}.json", user.id, slug, version);
+    
+    upload(&client, WASM_BUCKET, &wasm_object, "application/wasm", &wasm_bytes).await?;
+    upload(&client, MANIFEST_BUCKET, &manifest_object, "application/json", manifest_content.as_bytes()).await?;
+    
+    let wasm_url = client.object_url(WASM_BUCKET, &wasm_object);
+    let manifest_url = client.public_object_url(MANIFEST_BUCKET, &manifest_object);
+    let size_bytes = wasm_bytes.len() as i64;
+    
+    let is_latest = match &existing {
+        Some(app) => !prerelease && is_newer_than_latest(&client, app, &parsed).await?,
+        None => !prerelease,
+    };
+    let release = NewAppVersion {
+        app_id: existing.as_ref().map(|app| app.id.clone()).unwrap_or_default(),
+        version: version.clone(),
+        changelog,
+        manifest_url: manifest_url.clone(),
+        wasm_url: wasm_url.clone(),
+        size_bytes,
+        sha256: Some(sha256.clone()),
+        signature: signature.clone(),
+        is_prerelease: prerelease,
+        is_latest,
+    };
+    
+    // A new app is created along with its first version, so a failed insert leaves neither behind
+    let published = match &existing {
+        Some(_) => client.create_version(&release).await?,
+        None => client.create_app_with_version(&NewApp {
+            name: name.clone(),
+            slug: slug.clone(),
+            version: version.clone(),
+            author_id: user.id.clone(),
+            manifest_url: manifest_url.clone(),
+            wasm_url: wasm_url.clone(),
+            description: Some(description.clone().unwrap_or_else(|| format!("WASM application: {}", name))),
+            category: Some(category.unwrap_or_else(|| "utility".to_string())),
+            tags,
+            size_bytes,
+            sha256: Some(sha256.clone()),
+            is_published: true,
+        }, &release).await?,
+    };
+    let app_id = published.app_id.clone();
+    
+    // The app row mirrors its latest stable version
+    if is_latest {
+        client.demote_versions(&app_id, &published.id).await?;
+        client.update_app(&app_id, &AppUpdate {
+            version: Some(version.clone()),
+            description,
+            manifest_url: Some(manifest_url),
+            wasm_url: Some(wasm_url),
+            size_bytes: Some(size_bytes),
+            sha256: Some(sha256.clone()),
+            updated_at: Some(chrono::Utc::now()),
+        }).await?;
     }
     
+    let label = if prerelease { " (prerelease)" } else { "" };
+    println!("✅ Successfully published {} v{}{}", name, version, label);
+    println!("🔒 sha256:{}", sha256);
+    if let Some(signature) = &signature {
+        println!("🔏 Signed by {}", signature.publisher);
+    }
+    println!("🔍 Search: wasmrunner search {}", name);
+    println!("📦 Install: wasmrunner install {}", slug);
+    
     Ok(())
 }
+
+/// Signature covering the artifacts being uploaded
+///
+/// With a key, the artifacts are signed now. Otherwise a signature made at
+/// build time is reused if it covers the same WASM and manifest.
+fn sign(
+    config: &Config,
+    image: &Image,
+    key: Option<String>,
+    wasm: &[u8],
+    manifest: &[u8],
+) -> Result<Option<ImageSignature>> {
+    if let Some(publisher) = key.or_else(|| config.security.signing_key.clone()) {
+        let signing_key = KeyStore::new(&config.security.signing_keys_dir).load(&publisher)?;
+        return Ok(Some(ImageSignature::sign(&publisher, &signing_key, wasm, Some(manifest))));
+    }
+    
+    let manifest_sha256 = sha256_hex(manifest);
+    let existing = image.signature()?.filter(|signature| {
+        signature.wasm_sha256 == sha256_hex(wasm)
+            && signature.manifest_sha256.as_deref() == Some(manifest_sha256.as_str())
+    });
+    if existing.is_none() {
+        warn!("Publishing unsigned; installs will be refused where unsigned images are not allowed");
+    }
+    
+    Ok(existing)
+}
+
+/// Whether `version` should replace the app's current latest stable version
+async fn is_newer_than_latest(client: &StoreClient, app: &App, version: &semver::Version) -> Result<bool> {
+    let latest = match client.latest_version(&app.id).await? {
+        Some(latest) => latest,
+        None => return Ok(true),
+    };
+    
+    // Treat an unparseable legacy version as older than any valid one
+    Ok(semver::Version::parse(&latest.version).ok().is_none_or(|latest| *version > latest))
+}
+
+async fn upload(client: &StoreClient, bucket: &str, object: &str, content_type: &str, data: &[u8]) -> Result<()> {
+    let progress = ProgressBar::new(data.len() as u64);
+    progress.set_style(
+        ProgressStyle::with_template("{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec})")?
+            .progress_chars("=> "),
+    );
+    progress.set_message(format!("Uploading {}", object.rsplit('/').next().unwrap_or(object)));
+    
+    let result = client
+        .upload(bucket, object, content_type, data, |uploaded| progress.set_position(uploaded))
+        .await;
+    
+    match result {
+        Ok(()) => progress.finish(),
+        Err(_) => progress.abandon(),
+    }
+    
+    Ok(result?)
+}
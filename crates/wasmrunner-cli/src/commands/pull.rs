@@ -0,0 +1,48 @@
+use anyhow::Result;
+use tracing::info;
+use wasmrunner_core::config::Config;
+use wasmrunner_core::image::ImageStore;
+use wasmrunner_core::signing::{verify_image, Verification};
+use wasmrunner_core::trust::TrustStore;
+
+use super::registry::RemoteImage;
+
+#[tracing::instrument(name = "pull", skip(config))]
+pub async fn execute(config: &Config, image: String) -> Result<()> {
+    let remote = RemoteImage::parse(&image, config)?;
+    info!("Pulling {}:{} from {}", remote.name, remote.tag(), remote.registry);
+    
+    let pulled = super::registry::pull(config, &remote).await?;
+    
+    // A digest pull without a tag keeps the tag the image was cached under
+    let tag = match &remote.explicit_tag {
+        Some(tag) => tag.clone(),
+        None if remote.digest.is_some() => pulled.descriptor.tag.clone(),
+        None => remote.tag().to_string(),
+    };
+    
+    // Check the signature before anything reaches the image store
+    let reference = format!("{}:{}", remote.name, tag);
+    let trust = TrustStore::from_config(&config.plugins)?;
+    let verification = verify_image(
+        &reference,
+        pulled.descriptor.signature.as_ref(),
+        &pulled.wasm,
+        pulled.manifest.as_deref(),
+        &trust,
+        config.security.image_policy,
+    )?;
+    
+    let image = ImageStore::new()?.import(&remote.name, &tag, &pulled.wasm, pulled.manifest.as_deref())?;
+    if let Some(signature) = &pulled.descriptor.signature {
+        image.save_signature(signature)?;
+    }
+    
+    let source = if remote.digest.is_some() { "the local cache" } else { remote.registry.as_str() };
+    println!("✅ Pulled {}:{} from {}", image.name, image.tag, source);
+    println!("Digest: sha256:{}", pulled.descriptor.wasm_sha256);
+    if let Verification::Trusted(publisher) = &verification {
+        println!("🔏 Verified signature from {}", publisher);
+    }
+    Ok(())
+}
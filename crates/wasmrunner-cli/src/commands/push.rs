@@ -0,0 +1,34 @@
+use anyhow::Result;
+use tracing::{info, warn};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::image::ImageStore;
+
+use super::registry::{explain, RemoteImage};
+
+pub async fn execute(config: &Config, image: String) -> Result<()> {
+    let remote = RemoteImage::parse(&image, config)?;
+    if remote.digest.is_some() {
+        return Err(anyhow::anyhow!("Push by tag; digests are assigned by the registry"));
+    }
+    let local = ImageStore::new()?.get(&remote.name, remote.tag())?;
+    
+    let signature = local.signature()?;
+    if signature.is_none() {
+        warn!(
+            "{}:{} is unsigned; hosts enforcing signatures will refuse to pull it",
+            remote.name,
+            remote.tag()
+        );
+    }
+    
+    info!("Pushing {}:{} to {}", remote.name, remote.tag(), remote.registry);
+    let client = super::registry::client(config, &remote.registry);
+    let descriptor = client
+        .push(&remote.name, remote.tag(), local.read_wasm()?, local.read_manifest_bytes()?, signature)
+        .await
+        .map_err(explain)?;
+    
+    println!("✅ Pushed {}/{}:{}", remote.registry, remote.name, remote.tag());
+    println!("Digest: sha256:{}", descriptor.wasm_sha256);
+    Ok(())
+}
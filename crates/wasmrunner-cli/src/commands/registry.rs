@@ -0,0 +1,210 @@
+use anyhow::Result;
+use tracing::{info, warn};
+use std::path::PathBuf;
+use wasmrunner_core::config::Config;
+use wasmrunner_core::image::ImageStore;
+use wasmrunner_core::reference::{ImageReference, DEFAULT_TAG};
+use wasmrunner_registry::server::{self, ServerConfig};
+use wasmrunner_registry::{BlobCache, PulledImage, RegistryClient, RegistryError};
+
+/// Environment variable holding a registry token, used instead of `registry.token`
+pub(crate) const REGISTRY_TOKEN_ENV: &str = "WASMRUNNER_REGISTRY_TOKEN";
+
+/// An image reference on a remote registry
+pub(crate) struct RemoteImage {
+    pub registry: String,
+    pub name: String,
+    
+    /// Tag given in the reference; see `tag()` for the default
+    pub explicit_tag: Option<String>,
+    
+    /// Module sha256 from a `name@sha256:<digest>` reference
+    pub digest: Option<String>,
+}
+
+impl RemoteImage {
+    /// Parse a reference, using `registry.default_registry` when it names no registry
+    pub(crate) fn parse(reference: &str, config: &Config) -> Result<Self> {
+        let reference = ImageReference::parse(reference)?;
+        Ok(Self {
+            registry: reference
+                .registry
+                .clone()
+                .unwrap_or_else(|| config.registry.default_registry.clone()),
+            name: reference.repository(),
+            explicit_tag: reference.tag,
+            digest: reference.digest,
+        })
+    }
+    
+    pub(crate) fn tag(&self) -> &str {
+        self.explicit_tag.as_deref().unwrap_or(DEFAULT_TAG)
+    }
+}
+
+/// Client for `registry`, authenticated with its token from `registry.tokens`
+/// or else the configured token
+pub(crate) fn client(config: &Config, registry: &str) -> RegistryClient {
+    let token = config.registry.tokens.get(registry).cloned().or_else(|| {
+        std::env::var(REGISTRY_TOKEN_ENV)
+            .ok()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| config.registry.token.clone())
+    });
+    authenticated(config, registry, token)
+}
+
+/// Client for a pull mirror, which gets no token but its own from `registry.tokens`
+///
+/// The configured token belongs to the registry being mirrored; a third
+/// party mirror must never see it.
+fn mirror_client(config: &Config, mirror: &str) -> RegistryClient {
+    authenticated(config, mirror, config.registry.tokens.get(mirror).cloned())
+}
+
+fn authenticated(config: &Config, registry: &str, token: Option<String>) -> RegistryClient {
+    let url = if registry.starts_with("http://") || registry.starts_with("https://") {
+        registry.to_string()
+    } else {
+        let host = registry.split(':').next().unwrap_or(registry);
+        let insecure = host == "localhost"
+            || host == "127.0.0.1"
+            || config.registry.insecure_registries.iter().any(|r| r == registry || r == host);
+        format!("{}://{}", if insecure { "http" } else { "https" }, registry)
+    };
+    
+    match token {
+        Some(token) => RegistryClient::new(url).with_token(token.trim()),
+        None => RegistryClient::new(url),
+    }
+}
+
+/// Cache of pulled files under `registry.cache_dir`
+pub(crate) fn cache(config: &Config) -> Result<BlobCache> {
+    Ok(BlobCache::new(
+        config.registry.cache_dir.join("registry"),
+        config.registry.cache_max_mb * 1024 * 1024,
+    )?)
+}
+
+/// Registries to pull from for `registry`, in the order to try them
+///
+/// Pulls from the default registry try each configured mirror first.
+pub(crate) fn pull_sources(config: &Config, registry: &str) -> Vec<String> {
+    let mut sources = Vec::new();
+    if registry == config.registry.default_registry {
+        sources.extend(config.registry.mirrors.iter().cloned());
+    }
+    sources.push(registry.to_string());
+    sources
+}
+
+/// Pull `remote` through the cache, falling back from one source to the next
+pub(crate) async fn pull(config: &Config, remote: &RemoteImage) -> Result<PulledImage> {
+    let cache = cache(config)?;
+    if let Some(digest) = &remote.digest {
+        return client(config, &remote.registry)
+            .with_cache(cache)
+            .pull_cached(digest)
+            .map_err(explain);
+    }
+    
+    let sources = pull_sources(config, &remote.registry);
+    let mut last_error = None;
+    for (i, source) in sources.iter().enumerate() {
+        let source_client = if *source == remote.registry {
+            client(config, source)
+        } else {
+            mirror_client(config, source)
+        };
+        match source_client.with_cache(cache.clone()).pull(&remote.name, remote.tag()).await {
+            Ok(pulled) => {
+                if *source != remote.registry {
+                    info!("Pulled {}:{} from mirror {}", remote.name, remote.tag(), source);
+                }
+                return Ok(pulled);
+            }
+            Err(e) => {
+                if i + 1 < sources.len() {
+                    warn!("Pulling from {} failed, trying {}: {}", source, sources[i + 1], e);
+                }
+                last_error = Some(e);
+            }
+        }
+    }
+    
+    Err(explain(last_error.expect("pull_sources is never empty")))
+}
+
+/// Add a hint on how to authenticate to registry auth failures
+pub(crate) fn explain(error: RegistryError) -> anyhow::Error {
+    match error {
+        RegistryError::Unauthorized(message) => anyhow::anyhow!(
+            "Registry rejected the request: {}. Set {} or registry.token in the config file.",
+            message,
+            REGISTRY_TOKEN_ENV
+        ),
+        error => error.into(),
+    }
+}
+
+/// Serve the local image store to other machines
+pub async fn serve(
+    config: &Config,
+    listen: String,
+    root: Option<PathBuf>,
+    mut tokens: Vec<String>,
+    allow_anonymous_pull: bool,
+    upstream: Option<String>,
+) -> Result<()> {
+    if let Some(token) = std::env::var(REGISTRY_TOKEN_ENV).ok().filter(|t| !t.trim().is_empty()) {
+        tokens.push(token.trim().to_string());
+    }
+    if tokens.is_empty() {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        println!("🔑 Generated registry token: {}", token);
+        println!("Clients use it by setting {}; pass --token to choose your own", REGISTRY_TOKEN_ENV);
+        tokens.push(token);
+    }
+    
+    let store = match root {
+        Some(root) => ImageStore::with_root(root)?,
+        None => ImageStore::new()?,
+    };
+    let listener = tokio::net::TcpListener::bind(&listen).await?;
+    println!("📦 Serving {} on http://{}", store.root().display(), listener.local_addr()?);
+    if allow_anonymous_pull {
+        println!("Anonymous pulls are allowed; pushes need a token");
+    }
+    
+    // A pull-through mirror authenticates upstream with the client token settings
+    let upstream = upstream.map(|registry| client(config, &registry));
+    if let Some(upstream) = &upstream {
+        println!("Fetching missing images from {}", upstream.base_url());
+    }
+    
+    let server_config = ServerConfig {
+        tokens,
+        anonymous_pull: allow_anonymous_pull,
+        upstream,
+        ..Default::default()
+    };
+    server::serve(listener, store, server_config).await?;
+    Ok(())
+}
+
+/// Trim the pull cache to `max_mb`, or to `registry.cache_max_mb`
+pub async fn gc(config: &Config, max_mb: Option<u64>) -> Result<()> {
+    let cache = cache(config)?;
+    let limit = max_mb.unwrap_or(config.registry.cache_max_mb) * 1024 * 1024;
+    let stats = cache.gc_to(limit)?;
+    
+    println!(
+        "🧹 Removed {} cached file(s), freed {:.1} MB; {:.1} MB remain in {}",
+        stats.removed,
+        stats.freed_bytes as f64 / (1024.0 * 1024.0),
+        stats.remaining_bytes as f64 / (1024.0 * 1024.0),
+        cache.dir().display()
+    );
+    Ok(())
+}
@@ -0,0 +1,51 @@
+
+use anyhow::Result;
+use tracing::{info, warn};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::container::Container;
+use wasmrunner_core::events::{EventBus, EventKind};
+use wasmrunner_core::registry::LocalRegistry;
+use wasmrunner_plugins::PluginHost;
+
+pub async fn execute(config: &Config, container_id: String, force: bool) -> Result<()> {
+    let registry = LocalRegistry::new()?;
+    let plugins = PluginHost::from_config(&config.plugins)?;
+    let events = EventBus::new();
+    let handlers = plugins.subscribe_events(&events)?;
+    
+    let container = registry.find_container(&container_id).await?.with_events(events.clone());
+    
+    if container.is_running() {
+        if !force {
+            return Err(anyhow::anyhow!(
+                "Container {} is running; stop it first or use --force",
+                container.config.name
+            ));
+        }
+        
+        kill(&container, libc::SIGKILL);
+    }
+    
+    registry.remove_container(&container.id).await?;
+    info!("Removed container {}", container.id);
+    container.emit(EventKind::Removed).await;
+    
+    events.close();
+    handlers.join();
+    
+    println!("{}", container.id);
+    Ok(())
+}
+
+/// Send `signal` to the process hosting a detached container
+pub(crate) fn kill(container: &Container, signal: libc::c_int) {
+    match container.pid {
+        Some(pid) => {
+            // Safety: kill has no memory-safety preconditions
+            if unsafe { libc::kill(pid as libc::pid_t, signal) } != 0 {
+                warn!("Failed to signal container process {}: {}", pid, std::io::Error::last_os_error());
+            }
+        }
+        None => warn!("Container {} has no recorded process", container.id),
+    }
+}
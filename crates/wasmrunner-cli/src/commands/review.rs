@@ -0,0 +1,74 @@
+use anyhow::Result;
+use tracing::info;
+use wasmrunner_core::config::Config;
+use wasmrunner_store::models::{NewReview, ReviewUpdate};
+
+pub async fn execute(
+    config: &Config,
+    app: String,
+    rating: Option<i32>,
+    title: Option<String>,
+    content: Option<String>,
+    delete: bool,
+) -> Result<()> {
+    if let Some(rating) = rating {
+        if !(1..=5).contains(&rating) {
+            return Err(anyhow::anyhow!("--rating must be between 1 and 5"));
+        }
+    }
+    
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let user = client.current_user().await?;
+    let store_app = client.app_by_slug(&app).await?;
+    let existing = client.user_review(&store_app.id, &user.id).await?;
+    
+    if delete {
+        match existing {
+            Some(review) => {
+                client.delete_review(&review.id).await?;
+                println!("🗑️  Deleted your review of {}", app);
+            }
+            None => println!("You have not reviewed {}", app),
+        }
+        return Ok(());
+    }
+    
+    match existing {
+        Some(review) => {
+            if rating.is_none() && title.is_none() && content.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Nothing to change; pass --rating, --title or --content"
+                ));
+            }
+            
+            info!("Updating review {} of {}", review.id, app);
+            let changes = ReviewUpdate { rating, title, content };
+            let review = client.update_review(&review.id, &changes).await?;
+            println!("✏️  Updated your review of {} ({})", app, stars(review.rating));
+        }
+        None => {
+            let rating = rating.ok_or_else(|| {
+                anyhow::anyhow!("--rating is required for a new review")
+            })?;
+            
+            info!("Reviewing {}", app);
+            let review = NewReview {
+                app_id: store_app.id,
+                user_id: user.id,
+                rating,
+                title,
+                content,
+            };
+            let review = client.create_review(&review).await?;
+            println!("⭐ Reviewed {} ({})", app, stars(review.rating));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Render a 1-5 rating as filled and empty stars
+pub fn stars(rating: i32) -> String {
+    let filled = rating.clamp(0, 5) as usize;
+    format!("{}{}", "★".repeat(filled), "☆".repeat(5 - filled))
+}
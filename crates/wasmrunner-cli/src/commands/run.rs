@@ -1,22 +1,91 @@
 
 use anyhow::Result;
-use tracing::{info, error};
-use wasmrunner_core::container::{Container, ContainerConfig, ResourceLimits};
-use wasmrunner_runtime::RuntimeManager;
+use tracing::{info, info_span, error, warn};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::container::{
+    Container, ContainerConfig, HealthCheck, HealthTest, PortMapping, ResourceLimits, RestartPolicy, VolumeMount,
+};
+use wasmrunner_core::error::WasmRunnerError;
+use wasmrunner_core::events::{EventBus, EventKind};
+use wasmrunner_core::image::ImageStore;
+use wasmrunner_core::metrics;
+use wasmrunner_core::reference::ImageReference;
+use wasmrunner_core::registry::LocalRegistry;
+use wasmrunner_core::trust::TrustStore;
+use wasmrunner_runtime::{GuestEnvironment, GuestStats, RuntimeManager, StdioConfig};
+use wasmrunner_plugins::{CapabilitySet, PluginHost};
 use wasmrunner_sandbox::SandboxConfig;
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::os::unix::process::CommandExt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often a running guest's resource usage is written for `top`
+const USAGE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Directory under `registry.cache_dir` holding compiled modules
+pub(crate) const COMPILE_CACHE_DIR: &str = "compiled";
+
+/// Health check flags of `run`; no check is configured without an export or HTTP path
+pub struct HealthOptions {
+    pub export: Option<String>,
+    pub http: Option<String>,
+    pub interval_secs: u64,
+    pub timeout_secs: u64,
+    pub retries: u32,
+}
+
+impl HealthOptions {
+    fn health_check(self) -> Option<HealthCheck> {
+        let test = match (self.export, self.http) {
+            (Some(export), _) => HealthTest::Export(export),
+            (None, Some(path)) => HealthTest::Http(path),
+            (None, None) => return None,
+        };
+        Some(HealthCheck {
+            interval_secs: self.interval_secs,
+            timeout_secs: self.timeout_secs,
+            retries: self.retries,
+            ..HealthCheck::new(test)
+        })
+    }
+}
 
 pub async fn execute(
+    config: &Config,
     image: String,
     memory: u64,
     cpu: u32,
     env: Vec<String>,
     network: String,
     detach: bool,
+    interactive: bool,
+    tty: bool,
     name: Option<String>,
+    capabilities: Vec<String>,
+    volumes: Vec<String>,
+    ports: Vec<String>,
+    restart: String,
+    health: HealthOptions,
     args: Vec<String>,
 ) -> Result<()> {
     info!("Running WASM container: {}", image);
+    if !std::path::Path::new(&image).is_file() {
+        // Fail before a container is created for a malformed reference
+        ImageReference::parse(&image)?;
+    }
+    super::store::warn_if_deprecated(config, &image).await;
+    
+    if tty && detach {
+        error!("--tty cannot be combined with --detach; use --interactive and `wasmrunner attach`");
+        return Err(anyhow::anyhow!("Conflicting options: --tty and --detach"));
+    }
+    
+    if tty && (!std::io::stdout().is_terminal() || (interactive && !std::io::stdin().is_terminal())) {
+        error!("The input device is not a TTY");
+        return Err(anyhow::anyhow!("--tty requires a terminal"));
+    }
     
     // Parse environment variables
     let mut environment = HashMap::new();
@@ -29,8 +98,43 @@ pub async fn execute(
         }
     }
     
+    // The shim runs from another directory, so volumes are recorded as absolute paths
+    let volumes = volumes
+        .iter()
+        .map(|spec| {
+            let mut volume: VolumeMount = spec.parse()?;
+            volume.host = std::fs::canonicalize(&volume.host)
+                .map_err(|e| anyhow::anyhow!("Volume {}: {}", volume.host.display(), e))?;
+            Ok(volume)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let ports = ports.iter().map(|spec| spec.parse()).collect::<Result<Vec<PortMapping>>>()?;
+    
+    // Only the shim of a detached container outlives the guest to restart or probe it
+    let restart: RestartPolicy = restart.parse()?;
+    let healthcheck = health.health_check();
+    if !detach && (restart != RestartPolicy::No || healthcheck.is_some()) {
+        error!("--restart and health checks need --detach");
+        return Err(anyhow::anyhow!("Conflicting options: --restart or --health-* without --detach"));
+    }
+    if let Some(HealthCheck { test: HealthTest::Http(path), .. }) = &healthcheck {
+        if ports.is_empty() {
+            return Err(anyhow::anyhow!("--health-http needs a published port (--publish)"));
+        }
+        if !path.starts_with('/') {
+            return Err(anyhow::anyhow!("--health-http path must start with '/'"));
+        }
+    }
+    
+    // Guests attached to a terminal need to know what kind it is
+    if tty {
+        if let Ok(term) = std::env::var("TERM") {
+            environment.entry("TERM".to_string()).or_insert(term);
+        }
+    }
+    
     // Create container configuration
-    let config = ContainerConfig {
+    let container_config = ContainerConfig {
         image: image.clone(),
         name: name.unwrap_or_else(|| format!("wasm-{}", uuid::Uuid::new_v4().to_string()[..8].to_string())),
         args,
@@ -41,32 +145,214 @@ pub async fn execute(
         },
         network_mode: network,
         detached: detach,
+        interactive,
+        tty,
+        capabilities,
+        volumes,
+        ports,
+        labels: HashMap::new(),
+        restart,
+        healthcheck,
     };
     
-    // Create sandbox configuration
-    let sandbox_config = SandboxConfig {
-        memory_limit: memory * 1024 * 1024, // Convert MB to bytes
-        cpu_limit: cpu,
-        allow_network: config.network_mode != "none",
+    if detach {
+        let container = start_detached(config, container_config).await?;
+        println!("Container started with ID: {}", container.id);
+        return Ok(());
+    }
+    
+    let sandbox_config = sandbox_config(&container_config);
+    let registry = LocalRegistry::new()?;
+    
+    // Plugins see this container's lifecycle from creation onwards
+    let plugins = Arc::new(PluginHost::from_config(&config.plugins)?);
+    let events = EventBus::new();
+    let handlers = plugins.subscribe_events(&events)?;
+    
+    // Create and start container
+    let mut container = Container::new(container_config, sandbox_config)?.with_events(events.clone());
+    registry.save_container(&container).await?;
+    container.emit(EventKind::Created).await;
+    
+    // Start and wait for completion
+    let stdio = StdioConfig::foreground(interactive);
+    let result = run_container(config, plugins, &mut container, &registry, stdio).await;
+    
+    // Let plugins drain their queues before the process goes away
+    events.close();
+    handlers.join();
+    
+    // Exiting skips the flush at the end of main
+    crate::telemetry::shutdown();
+    std::process::exit(result?)
+}
+
+fn sandbox_config(container_config: &ContainerConfig) -> SandboxConfig {
+    SandboxConfig {
+        memory_limit: container_config.resource_limits.memory_mb * 1024 * 1024, // Convert MB to bytes
+        cpu_limit: container_config.resource_limits.cpu_percent,
+        allow_network: container_config.network_mode != "none",
         allow_filesystem: true, // TODO: Make configurable
         seccomp_profile: None, // TODO: Load default profile
-    };
+    }
+}
+
+/// Create a container and hand it to a background shim that outlives this process
+pub(crate) async fn start_detached(config: &Config, container_config: ContainerConfig) -> Result<Container> {
+    let sandbox_config = sandbox_config(&container_config);
+    let registry = LocalRegistry::new()?;
     
-    // Initialize runtime manager
-    let runtime_manager = RuntimeManager::new()?;
+    // Plugins see this container's lifecycle from creation onwards
+    let plugins = PluginHost::from_config(&config.plugins)?;
+    let events = EventBus::new();
+    let handlers = plugins.subscribe_events(&events)?;
     
-    // Create and start container
-    let mut container = Container::new(config, sandbox_config)?;
+    let container = Container::new(container_config, sandbox_config)?.with_events(events.clone());
+    registry.save_container(&container).await?;
+    container.emit(EventKind::Created).await;
     
-    if detach {
-        // Start in background
-        let container_id = container.start_detached(&runtime_manager).await?;
-        println!("Container started with ID: {}", container_id);
-    } else {
-        // Start and wait for completion
-        let exit_code = container.start_and_wait(&runtime_manager).await?;
-        std::process::exit(exit_code);
+    let mut shim = std::process::Command::new(std::env::current_exe()?);
+    shim.arg("shim")
+        .arg(&container.id)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    
+    // A session of its own keeps the shim out of reach of Ctrl-C and terminal hangups
+    // Safety: setsid is async-signal-safe
+    unsafe {
+        shim.pre_exec(|| match libc::setsid() {
+            -1 => Err(std::io::Error::last_os_error()),
+            _ => Ok(()),
+        });
+    }
+    let result = shim.spawn();
+    
+    events.close();
+    handlers.join();
+    
+    result?;
+    Ok(container)
+}
+
+/// Execute a container to completion, recording its state transitions
+#[tracing::instrument(name = "run", skip_all, fields(container = %container.id, image = %container.config.image))]
+pub(crate) async fn run_container(
+    config: &Config,
+    plugins: Arc<PluginHost>,
+    container: &mut Container,
+    registry: &LocalRegistry,
+    stdio: StdioConfig,
+) -> Result<i32> {
+    let starting = Instant::now();
+    let image_store = ImageStore::new()?;
+    let (wasm_bytes, imports) = info_span!("validate").in_scope(|| -> Result<_> {
+        let trust = TrustStore::from_config(&config.plugins)?;
+        image_store.verify(&container.config.image, &trust, config.security.image_policy)?;
+        let wasm_bytes = image_store.load_wasm(&container.config.image)?;
+        let imports = image_store.plugin_imports(&container.config.image)?;
+        Ok((wasm_bytes, imports))
+    })?;
+    
+    // Plugin host functions are linked only for capabilities granted to this container
+    let mut capabilities = CapabilitySet::from_security(&config.security);
+    for capability in &container.config.capabilities {
+        capabilities.grant(capability);
+    }
+    
+    let mut runtime_manager = RuntimeManager::with_plugins(plugins, capabilities, imports)?;
+    let runtime = runtime_manager.get_runtime(None)?;
+    runtime.set_compile_cache(config.registry.cache_dir.join(COMPILE_CACHE_DIR));
+    runtime.set_fuel_metering(config.runtime.fuel_metering)?;
+    
+    let compile = info_span!("compile", cached = tracing::field::Empty);
+    let mut module = compile.in_scope(|| runtime.load_module(&wasm_bytes))?;
+    compile.record("cached", module.compiled_from_cache());
+    module.set_memory_limit(container.config.resource_limits.memory_mb * 1024 * 1024);
+    module.set_environment(guest_environment(&container.config)?);
+    
+    container.mark_running().await;
+    registry.save_container(container).await?;
+    
+    let cached = module.compiled_from_cache();
+    let start_latency = starting.elapsed().as_secs_f64();
+    metrics::record(|counters| {
+        counters.containers_started += 1;
+        counters.observe_start_latency(start_latency);
+        if cached {
+            counters.compile_cache_hits += 1;
+        } else {
+            counters.compile_cache_misses += 1;
+        }
+    })
+    .await;
+    
+    // argv[0] is the image the guest was started from
+    let args = std::iter::once(container.config.image.clone())
+        .chain(container.config.args.iter().cloned())
+        .collect();
+    
+    let stats = module.stats();
+    let sampler = tokio::spawn(sample_usage(container.id.clone(), stats.clone()));
+    
+    // Health checks and usage sampling keep running on other workers meanwhile
+    let result = tokio::task::block_in_place(|| module.execute(args, stdio));
+    sampler.abort();
+    if let Err(e) = registry.save_usage(&container.id, &stats.usage()).await {
+        warn!("Failed to record resource usage: {}", e);
+    }
+    match &result {
+        Ok(exit_code) => container.mark_exited(*exit_code).await,
+        Err(e) => match e.downcast_ref::<WasmRunnerError>() {
+            Some(WasmRunnerError::OutOfMemory { .. }) => {
+                metrics::record(|counters| counters.oom_kills += 1).await;
+                container.mark_oom_killed().await
+            }
+            Some(WasmRunnerError::Trap(_)) => {
+                metrics::record(|counters| counters.traps += 1).await;
+                container.mark_trapped(e.to_string()).await
+            }
+            _ => container.mark_trapped(e.to_string()).await,
+        },
     }
+    registry.save_container(container).await?;
+    
+    result
+}
+
+/// Write the guest's resource usage for `top` every [`USAGE_INTERVAL`] until aborted
+async fn sample_usage(container_id: String, stats: Arc<GuestStats>) {
+    let Ok(registry) = LocalRegistry::new() else { return };
+    loop {
+        if let Err(e) = registry.save_usage(&container_id, &stats.usage()).await {
+            warn!("Failed to record resource usage: {}", e);
+            return;
+        }
+        tokio::time::sleep(USAGE_INTERVAL).await;
+    }
+}
+
+/// Environment variables, volumes and listening sockets for a container's guest
+fn guest_environment(container_config: &ContainerConfig) -> Result<GuestEnvironment> {
+    let mut variables: Vec<_> = container_config.environment.clone().into_iter().collect();
+    variables.sort();
+    
+    let listeners = container_config
+        .ports
+        .iter()
+        .map(|port| {
+            std::net::TcpListener::bind(port.host)
+                .map_err(|e| anyhow::anyhow!("Cannot listen on {}: {}", port, e))
+        })
+        .collect::<Result<Vec<_>>>()?;
     
-    Ok(())
+    Ok(GuestEnvironment {
+        variables,
+        volumes: container_config
+            .volumes
+            .iter()
+            .map(|volume| (volume.host.clone(), volume.guest.clone()))
+            .collect(),
+        listeners,
+    })
 }
@@ -0,0 +1,93 @@
+
+use anyhow::Result;
+use tracing::info;
+use prettytable::{Table, Row, Cell};
+use wasmrunner_core::config::Config;
+use wasmrunner_store::models::AppQuery;
+use wasmrunner_store::PageRequest;
+
+/// Longest description shown in the results table
+const DESCRIPTION_WIDTH: usize = 50;
+
+pub async fn execute(
+    config: &Config,
+    term: String,
+    category: Option<String>,
+    verified: bool,
+    limit: u64,
+    page: u64,
+    json: bool,
+) -> Result<()> {
+    info!("Searching app store for {}", term);
+    
+    if limit == 0 || page == 0 {
+        return Err(anyhow::anyhow!("--limit and --page must be at least 1"));
+    }
+    
+    let client = super::store::client(config)?;
+    let query = AppQuery {
+        category,
+        verified_only: verified,
+        ..Default::default()
+    };
+    let results = client
+        .search_apps(&term, &query, PageRequest::new((page - 1) * limit, limit))
+        .await?;
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+    
+    if results.items.is_empty() {
+        println!("No apps found matching \"{}\"", term);
+        return Ok(());
+    }
+    
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("NAME"),
+        Cell::new("VERSION"),
+        Cell::new("DESCRIPTION"),
+        Cell::new("CATEGORY"),
+        Cell::new("DOWNLOADS"),
+        Cell::new("STARS"),
+        Cell::new("VERIFIED"),
+    ]));
+    
+    for app in &results.items {
+        table.add_row(Row::new(vec![
+            Cell::new(&app.slug),
+            Cell::new(&app.version),
+            Cell::new(&truncate(app.description.as_deref().unwrap_or(""), DESCRIPTION_WIDTH)),
+            Cell::new(app.category.as_deref().unwrap_or("")),
+            Cell::new(&app.download_count.to_string()),
+            Cell::new(&app.star_count.to_string()),
+            Cell::new(if app.is_verified { "✓" } else { "" }),
+        ]));
+    }
+    
+    table.printstd();
+    
+    let first = results.offset + 1;
+    let last = results.offset + results.items.len() as u64;
+    match results.total {
+        Some(total) => println!("Showing {}-{} of {} results", first, last, total),
+        None => println!("Showing {}-{}", first, last),
+    }
+    if results.has_more() {
+        println!("Use --page {} for more", page + 1);
+    }
+    
+    Ok(())
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    
+    let mut truncated: String = text.chars().take(width - 1).collect();
+    truncated.push('…');
+    truncated
+}
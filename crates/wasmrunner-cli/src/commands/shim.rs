@@ -0,0 +1,86 @@
+
+use anyhow::Result;
+use tracing::{info, error};
+use wasmrunner_core::config::Config;
+use wasmrunner_core::container::{RestartPolicy, RESTART_BACKOFF_RESET};
+use wasmrunner_core::events::EventBus;
+use wasmrunner_core::metrics;
+use wasmrunner_core::registry::{LocalRegistry, ATTACH_SOCKET, LOG_FILE};
+use wasmrunner_runtime::stdio::AttachPoint;
+use wasmrunner_plugins::PluginHost;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Run a detached container in the background, serving `attach` connections
+pub async fn execute(config: &Config, container_id: String) -> Result<()> {
+    let registry = LocalRegistry::new()?;
+    let plugins = Arc::new(PluginHost::from_config(&config.plugins)?);
+    let events = EventBus::new();
+    let handlers = plugins.subscribe_events(&events)?;
+    
+    let mut container = registry.find_container(&container_id).await?.with_events(events.clone());
+    info!("Shim starting container {}", container.id);
+    
+    // `remove --force` signals the shim that owns the guest
+    container.pid = Some(std::process::id());
+    
+    let dir = registry.container_dir(&container.id);
+    let (attach_point, stdio) = AttachPoint::bind(
+        &dir.join(ATTACH_SOCKET),
+        &dir.join(LOG_FILE),
+        container.config.interactive,
+    )?;
+    
+    let health = container.config.healthcheck.clone().map(|check| {
+        tokio::spawn(super::health::monitor(
+            config.clone(),
+            plugins.clone(),
+            events.clone(),
+            container.id.clone(),
+            check,
+        ))
+    });
+    
+    // Restarts back off exponentially until a run lasts long enough to count as stable
+    let mut stdio = Some(stdio);
+    let mut consecutive = 0;
+    let result = loop {
+        let stdio = match stdio.take() {
+            Some(stdio) => stdio,
+            None => attach_point.stdio()?,
+        };
+        let started = Instant::now();
+        let result = super::run::run_container(config, plugins.clone(), &mut container, &registry, stdio).await;
+        
+        if started.elapsed() >= RESTART_BACKOFF_RESET {
+            consecutive = 0;
+        }
+        let failed = !matches!(result, Ok(0));
+        if !container.config.restart.should_restart(failed, consecutive) {
+            break result;
+        }
+        
+        let delay = RestartPolicy::backoff(consecutive);
+        consecutive += 1;
+        container.mark_restarting().await;
+        metrics::record(|counters| counters.restarts += 1).await;
+        registry.save_container(&container).await?;
+        info!("Restarting container {} in {:?}", container.id, delay);
+        tokio::time::sleep(delay).await;
+    };
+    
+    if let Some(health) = health {
+        health.abort();
+    }
+    attach_point.close();
+    let _ = std::fs::remove_file(dir.join(ATTACH_SOCKET));
+    
+    events.close();
+    handlers.join();
+    
+    if let Err(e) = &result {
+        error!("Container {} failed: {}", container.id, e);
+    }
+    
+    result.map(|_| ())
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::io::Write;
+use tracing::info;
+use prettytable::{Table, Row, Cell};
+use chrono::{Duration, NaiveDate, Utc};
+use wasmrunner_core::config::Config;
+use wasmrunner_store::models::{DownloadStat, StatsInterval, StatsQuery};
+
+pub async fn execute(
+    config: &Config,
+    app: String,
+    since: Option<String>,
+    until: Option<String>,
+    days: u32,
+    interval: String,
+    format: String,
+    output: Option<String>,
+) -> Result<()> {
+    let interval = match interval.as_str() {
+        "day" => StatsInterval::Day,
+        "week" => StatsInterval::Week,
+        "month" => StatsInterval::Month,
+        other => return Err(anyhow::anyhow!("Unknown interval {}; use day, week or month", other)),
+    };
+    let until = until.as_deref().map(parse_date).transpose()?;
+    let since = match since {
+        Some(since) => parse_date(&since)?,
+        None => until.unwrap_or_else(|| Utc::now().date_naive()) - Duration::days(i64::from(days.max(1)) - 1),
+    };
+    if until.is_some_and(|until| until < since) {
+        return Err(anyhow::anyhow!("--until must not be before --since"));
+    }
+    
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let user = client.current_user().await?;
+    let store_app = client.app_by_slug(&app).await?;
+    if store_app.author_id != user.id {
+        return Err(anyhow::anyhow!("Only the author of {} can view its download stats", app));
+    }
+    
+    info!("Fetching download stats for {} since {}", app, since);
+    let query = StatsQuery { since: Some(since), until, interval };
+    let stats = client.download_stats(&store_app.id, &query).await?;
+    
+    let mut out: Box<dyn Write> = match &output {
+        Some(path) => Box::new(std::fs::File::create(path)?),
+        None => Box::new(std::io::stdout()),
+    };
+    
+    match format.as_str() {
+        "json" => {
+            serde_json::to_writer_pretty(&mut out, &stats)?;
+            writeln!(out)?;
+        }
+        "csv" => write_csv(&mut out, &stats)?,
+        "table" => print_tables(&mut out, &app, since, until, &stats)?,
+        other => return Err(anyhow::anyhow!("Unknown format {}; use table, csv or json", other)),
+    }
+    
+    if let Some(path) = output {
+        println!("📊 Wrote {} rows to {}", stats.len(), path);
+    }
+    Ok(())
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date {}; expected YYYY-MM-DD", value))
+}
+
+fn write_csv(out: &mut dyn Write, stats: &[DownloadStat]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    for stat in stats {
+        writer.serialize(stat)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn print_tables(
+    out: &mut dyn Write,
+    app: &str,
+    since: NaiveDate,
+    until: Option<NaiveDate>,
+    stats: &[DownloadStat],
+) -> Result<()> {
+    let until = until.unwrap_or_else(|| Utc::now().date_naive());
+    let total: i64 = stats.iter().map(|s| s.downloads).sum();
+    writeln!(out, "Downloads of {} from {} to {}: {}", app, since, until, total)?;
+    if stats.is_empty() {
+        return Ok(());
+    }
+    
+    let mut by_version: BTreeMap<&str, i64> = BTreeMap::new();
+    let mut by_platform: BTreeMap<&str, i64> = BTreeMap::new();
+    let mut by_period: BTreeMap<NaiveDate, BTreeMap<&str, i64>> = BTreeMap::new();
+    for stat in stats {
+        *by_version.entry(&stat.version).or_default() += stat.downloads;
+        *by_platform.entry(&stat.platform).or_default() += stat.downloads;
+        *by_period.entry(stat.period).or_default().entry(&stat.version).or_default() += stat.downloads;
+    }
+    
+    // Newest versions first, by semver where versions parse
+    let mut versions: Vec<&str> = by_version.keys().copied().collect();
+    versions.sort_by(|a, b| match (semver::Version::parse(a), semver::Version::parse(b)) {
+        (Ok(a), Ok(b)) => b.cmp(&a),
+        _ => b.cmp(a),
+    });
+    
+    writeln!(out)?;
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![Cell::new("VERSION"), Cell::new("DOWNLOADS"), Cell::new("SHARE")]));
+    for version in &versions {
+        let count = by_version[version];
+        table.add_row(Row::new(vec![
+            Cell::new(version),
+            Cell::new(&count.to_string()),
+            Cell::new(&share(count, total)),
+        ]));
+    }
+    table.print(out)?;
+    
+    writeln!(out)?;
+    let mut platforms: Vec<(&str, i64)> = by_platform.into_iter().collect();
+    platforms.sort_by(|a, b| b.1.cmp(&a.1));
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![Cell::new("PLATFORM"), Cell::new("DOWNLOADS"), Cell::new("SHARE")]));
+    for (platform, count) in platforms {
+        table.add_row(Row::new(vec![
+            Cell::new(platform),
+            Cell::new(&count.to_string()),
+            Cell::new(&share(count, total)),
+        ]));
+    }
+    table.print(out)?;
+    
+    writeln!(out)?;
+    let mut table = Table::new();
+    let mut header = vec![Cell::new("PERIOD")];
+    header.extend(versions.iter().map(|v| Cell::new(v)));
+    header.push(Cell::new("TOTAL"));
+    table.add_row(Row::new(header));
+    for (period, counts) in &by_period {
+        let mut row = vec![Cell::new(&period.to_string())];
+        row.extend(versions.iter().map(|v| Cell::new(&counts.get(v).copied().unwrap_or(0).to_string())));
+        row.push(Cell::new(&counts.values().sum::<i64>().to_string()));
+        table.add_row(Row::new(row));
+    }
+    table.print(out)?;
+    
+    Ok(())
+}
+
+fn share(count: i64, total: i64) -> String {
+    if total == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", count as f64 * 100.0 / total as f64)
+}
@@ -0,0 +1,134 @@
+
+use anyhow::Result;
+use tracing::{debug, warn};
+use std::path::PathBuf;
+use std::time::Duration;
+use wasmrunner_core::config::Config;
+use wasmrunner_core::image::{ImageOrigin, ImageStore};
+use wasmrunner_store::models::{App, AppVersion};
+use wasmrunner_store::{Credentials, StoreClient, StoreError};
+
+/// Environment variable holding an API token, used instead of saved credentials
+pub(crate) const TOKEN_ENV: &str = "WASMRUNNER_TOKEN";
+
+/// How long `run` waits for the store when checking an image's release
+const RELEASE_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where `wasmrunner login` keeps the session
+pub(crate) fn credentials_path(config: &Config) -> PathBuf {
+    config.registry.auth_config.clone().unwrap_or_else(|| {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".wasmrunner")
+            .join("auth.json")
+    })
+}
+
+/// Anonymous client for the configured app store
+pub(crate) fn client(config: &Config) -> Result<StoreClient> {
+    let supabase = config.supabase.as_ref().ok_or(StoreError::NotConfigured)?;
+    Ok(StoreClient::from_config(supabase))
+}
+
+/// Client acting as the logged-in user, refreshing an expiring session first
+///
+/// An API token in `WASMRUNNER_TOKEN` takes precedence over saved credentials
+/// and is exchanged for a session that is never written to disk.
+pub(crate) async fn authenticated_client(config: &Config) -> Result<(StoreClient, Credentials)> {
+    if let Some(token) = std::env::var(TOKEN_ENV).ok().filter(|t| !t.trim().is_empty()) {
+        let client = client(config)?;
+        debug!("Authenticating with API token from {}", TOKEN_ENV);
+        
+        let session = match client.exchange_api_token(token.trim()).await {
+            Err(StoreError::Unauthorized(message)) => {
+                return Err(anyhow::anyhow!("{} was rejected: {}", TOKEN_ENV, message));
+            }
+            result => result?,
+        };
+        let credentials = Credentials::from_session(session, "", client.base_url());
+        let client = client.with_access_token(&credentials.access_token);
+        return Ok((client, credentials));
+    }
+    
+    let path = credentials_path(config);
+    let mut credentials = Credentials::load(&path)?;
+    let client = client(config)?;
+    
+    if credentials.needs_refresh() {
+        let refresh_token = credentials
+            .refresh_token
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Session expired. Run `wasmrunner login` again."))?;
+        
+        debug!("Refreshing session for {}", credentials.email);
+        let session = match client.refresh_session(refresh_token).await {
+            Err(StoreError::Unauthorized(_)) | Err(StoreError::Api { status: 400, .. }) => {
+                return Err(anyhow::anyhow!("Session expired. Run `wasmrunner login` again."));
+            }
+            result => result?,
+        };
+        
+        credentials = Credentials::from_session(session, &credentials.email, client.base_url());
+        credentials.save(&path)?;
+    }
+    
+    let client = client.with_access_token(&credentials.access_token);
+    Ok((client, credentials))
+}
+
+/// Deprecation notice for a release, preferring the version's own message over the app's
+pub(crate) fn deprecation_notice(app: &App, release: &AppVersion) -> Option<String> {
+    match (&release.deprecation_message, &app.deprecation_message) {
+        (Some(message), _) => Some(format!("{}:{} is deprecated: {}", app.slug, release.version, message)),
+        (None, Some(message)) => Some(format!("{} is deprecated: {}", app.slug, message)),
+        (None, None) => None,
+    }
+}
+
+/// Notice for a yanked release, or `None` if it is not yanked
+pub(crate) fn yank_notice(app: &App, release: &AppVersion) -> Option<String> {
+    release.is_yanked().then(|| match &release.yank_reason {
+        Some(reason) => format!("{}:{} has been yanked: {}", app.slug, release.version, reason),
+        None => format!("{}:{} has been yanked", app.slug, release.version),
+    })
+}
+
+/// Warn when an image installed from the store is deprecated or yanked
+///
+/// The store is asked briefly for the release's current state; when it cannot
+/// be reached, the notice recorded at install time is shown instead.
+pub(crate) async fn warn_if_deprecated(config: &Config, image: &str) {
+    let Ok(image) = ImageStore::new().and_then(|store| store.resolve(image)) else {
+        return;
+    };
+    let Ok(Some(origin)) = image.origin() else {
+        return;
+    };
+    
+    let current = match client(config) {
+        Ok(client) if client.base_url() == origin.store_url => {
+            tokio::time::timeout(RELEASE_CHECK_TIMEOUT, release_notices(&client, &origin))
+                .await
+                .ok()
+                .and_then(|result| result.ok())
+        }
+        _ => None,
+    };
+    
+    for notice in current.unwrap_or_else(|| origin.deprecation.into_iter().collect()) {
+        warn!("{}", notice);
+    }
+}
+
+async fn release_notices(client: &StoreClient, origin: &ImageOrigin) -> Result<Vec<String>> {
+    let app = client.app_by_slug(&origin.app).await?;
+    let release = client
+        .app_version(&app.id, &origin.version)
+        .await?
+        .ok_or_else(|| StoreError::NotFound(format!("{}:{}", origin.app, origin.version)))?;
+    
+    Ok(yank_notice(&app, &release)
+        .into_iter()
+        .chain(deprecation_notice(&app, &release))
+        .collect())
+}
@@ -0,0 +1,85 @@
+
+use anyhow::Result;
+use prettytable::{Table, Row, Cell};
+use wasmrunner_core::config::Config;
+use wasmrunner_store::API_TOKEN_SCOPES;
+
+pub async fn create(config: &Config, name: String, scopes: Vec<String>, expires_in_days: Option<u32>) -> Result<()> {
+    if let Some(unknown) = scopes.iter().find(|s| !API_TOKEN_SCOPES.contains(&s.as_str())) {
+        return Err(anyhow::anyhow!(
+            "Unknown scope {}; expected one of: {}",
+            unknown,
+            API_TOKEN_SCOPES.join(", ")
+        ));
+    }
+    
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let created = client.create_api_token(&name, &scopes, expires_in_days).await?;
+    
+    println!("🔑 Created API token {} ({})", created.info.name, created.info.scopes.join(", "));
+    if let Some(expires_at) = created.info.expires_at {
+        println!("Expires: {}", expires_at.format("%Y-%m-%d"));
+    }
+    println!();
+    println!("{}", created.token);
+    println!();
+    println!("This token will not be shown again. Use it by setting {}.", super::store::TOKEN_ENV);
+    
+    Ok(())
+}
+
+pub async fn list(config: &Config) -> Result<()> {
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let tokens = client.list_api_tokens().await?;
+    
+    if tokens.is_empty() {
+        println!("No API tokens");
+        return Ok(());
+    }
+    
+    let date = |d: Option<chrono::DateTime<chrono::Utc>>| {
+        d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string())
+    };
+    
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("ID"),
+        Cell::new("NAME"),
+        Cell::new("SCOPES"),
+        Cell::new("CREATED"),
+        Cell::new("EXPIRES"),
+        Cell::new("LAST USED"),
+        Cell::new("STATUS"),
+    ]));
+    
+    for token in tokens {
+        let status = if token.revoked_at.is_some() {
+            "revoked"
+        } else if token.expires_at.is_some_and(|e| e <= chrono::Utc::now()) {
+            "expired"
+        } else {
+            "active"
+        };
+        
+        table.add_row(Row::new(vec![
+            Cell::new(&token.id),
+            Cell::new(&token.name),
+            Cell::new(&token.scopes.join(",")),
+            Cell::new(&date(token.created_at)),
+            Cell::new(&date(token.expires_at)),
+            Cell::new(&date(token.last_used_at)),
+            Cell::new(status),
+        ]));
+    }
+    
+    table.printstd();
+    Ok(())
+}
+
+pub async fn revoke(config: &Config, id: String) -> Result<()> {
+    let (client, _) = super::store::authenticated_client(config).await?;
+    client.revoke_api_token(&id).await?;
+    
+    println!("🚫 Revoked API token {}", id);
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use tracing::error;
+use prettytable::{Table, Row, Cell};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use wasmrunner_core::container::{Container, ContainerState, ResourceUsage};
+use wasmrunner_core::registry::LocalRegistry;
+
+/// Time between redraws of the live view
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+pub async fn execute(containers: Vec<String>, no_stream: bool) -> Result<()> {
+    let registry = LocalRegistry::new()?;
+    
+    // Each container's sample at the previous redraw, for the rate columns
+    let mut previous: HashMap<String, (ResourceUsage, Instant)> = HashMap::new();
+    loop {
+        let selected = select(&registry, &containers).await?;
+        
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![
+            Cell::new("CONTAINER ID"),
+            Cell::new("NAME"),
+            Cell::new("MEMORY / LIMIT"),
+            Cell::new("CPU"),
+            Cell::new("CPU %"),
+            Cell::new("FUEL"),
+            Cell::new("FUEL/S"),
+            Cell::new("UPTIME"),
+            Cell::new("HANDLES"),
+            Cell::new("NET RX / TX"),
+        ]));
+        
+        for container in &selected {
+            // The sample left by a guest waiting to restart is out of date
+            let usage = match container.state {
+                ContainerState::Restarting => None,
+                _ => registry.load_usage(&container.id).await?,
+            };
+            table.add_row(usage_row(container, usage.as_ref(), previous.get(&container.id)));
+            if let Some(usage) = usage {
+                previous.insert(container.id.clone(), (usage, Instant::now()));
+            }
+        }
+        
+        if no_stream {
+            table.printstd();
+            return Ok(());
+        }
+        
+        // Clear the screen and redraw from the top-left corner
+        print!("\x1b[2J\x1b[H");
+        if selected.is_empty() {
+            println!("No running containers");
+        } else {
+            table.printstd();
+        }
+        tokio::time::sleep(REFRESH_INTERVAL).await;
+    }
+}
+
+/// The named containers, or every running one when none are named
+async fn select(registry: &LocalRegistry, containers: &[String]) -> Result<Vec<Container>> {
+    if containers.is_empty() {
+        return registry.running_containers().await;
+    }
+    
+    let mut selected = Vec::new();
+    for name in containers {
+        let container = registry.find_container(name).await?;
+        if !container.is_running() {
+            error!("Container {} is not running", container.config.name);
+            return Err(anyhow::anyhow!("Cannot show usage of a stopped container"));
+        }
+        selected.push(container);
+    }
+    Ok(selected)
+}
+
+fn usage_row(
+    container: &Container,
+    usage: Option<&ResourceUsage>,
+    previous: Option<&(ResourceUsage, Instant)>,
+) -> Row {
+    let id = Cell::new(&container.id[..12]);
+    let name = Cell::new(&container.config.name);
+    
+    // No sample while the guest is restarting or before it first starts
+    let Some(usage) = usage else {
+        let mut cells = vec![id, name];
+        cells.extend((0..8).map(|_| Cell::new("-")));
+        return Row::new(cells);
+    };
+    
+    // CPU time is measured on every platform that can; fuel only with fuel metering on
+    let (cpu, cpu_percent) = match usage.cpu_time_ms {
+        Some(cpu_ms) => {
+            let previous = previous.and_then(|(sample, at)| Some((sample.cpu_time_ms?, *at)));
+            (
+                format!("{:.1}s", cpu_ms as f64 / 1000.0),
+                format!("{:.1}%", rate(cpu_ms, previous, usage.uptime_secs) / 10.0),
+            )
+        }
+        None => ("-".to_string(), "-".to_string()),
+    };
+    let (fuel, fuel_rate) = match usage.fuel_consumed {
+        Some(consumed) => {
+            let previous = previous.and_then(|(sample, at)| Some((sample.fuel_consumed?, *at)));
+            (format_count(consumed as f64), format_count(rate(consumed, previous, usage.uptime_secs)))
+        }
+        None => ("-".to_string(), "-".to_string()),
+    };
+    
+    Row::new(vec![
+        id,
+        name,
+        Cell::new(&format!(
+            "{} / {}",
+            format_bytes(usage.memory_bytes),
+            format_bytes(container.config.resource_limits.memory_mb * 1024 * 1024)
+        )),
+        Cell::new(&cpu),
+        Cell::new(&cpu_percent),
+        Cell::new(&fuel),
+        Cell::new(&fuel_rate),
+        Cell::new(&format_uptime(usage.uptime_secs)),
+        Cell::new(&usage.open_handles.to_string()),
+        Cell::new(&format!("{} / {}", format_bytes(usage.net_rx_bytes), format_bytes(usage.net_tx_bytes))),
+    ])
+}
+
+/// Growth per second of a counter since the previous redraw, or the average
+/// over the uptime when there is none or the guest has restarted since
+fn rate(count: u64, previous: Option<(u64, Instant)>, uptime_secs: u64) -> f64 {
+    match previous {
+        Some((before, at)) if count >= before && at.elapsed().as_secs_f64() > 0.0 => {
+            (count - before) as f64 / at.elapsed().as_secs_f64()
+        }
+        _ => count as f64 / uptime_secs.max(1) as f64,
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.1} GB", bytes / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", bytes / (1024.0 * 1024.0))
+    } else if bytes >= 1024.0 {
+        format!("{:.1} KB", bytes / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Fuel with a metric suffix, e.g. `12.5M`
+fn format_count(count: f64) -> String {
+    if count >= 1e9 {
+        format!("{:.1}G", count / 1e9)
+    } else if count >= 1e6 {
+        format!("{:.1}M", count / 1e6)
+    } else if count >= 1e3 {
+        format!("{:.1}K", count / 1e3)
+    } else {
+        format!("{:.0}", count)
+    }
+}
+
+fn format_uptime(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
@@ -0,0 +1,24 @@
+
+use anyhow::Result;
+use wasmrunner_core::config::Config;
+
+pub async fn execute(config: &Config) -> Result<()> {
+    let (client, credentials) = super::store::authenticated_client(config).await?;
+    let user = client.current_user().await?;
+    let profile = client.profile(&user.id).await.ok();
+    
+    println!("Email:    {}", user.email.as_deref().unwrap_or(&credentials.email));
+    if let Some(username) = profile.as_ref().and_then(|p| p.username.as_deref()) {
+        println!("Username: {}", username);
+    }
+    if let Some(profile) = &profile {
+        println!("Role:     {}", profile.role);
+    }
+    println!("User ID:  {}", user.id);
+    println!("Store:    {}", client.base_url());
+    if let Some(expires_at) = credentials.expires_at {
+        println!("Session:  valid until {}", expires_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+    
+    Ok(())
+}
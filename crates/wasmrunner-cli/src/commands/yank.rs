@@ -0,0 +1,53 @@
+use anyhow::Result;
+use tracing::info;
+use wasmrunner_core::config::Config;
+use wasmrunner_store::models::App;
+use wasmrunner_store::StoreClient;
+
+/// Yank `slug@version`, or restore it with `undo`
+pub async fn execute(config: &Config, target: String, reason: Option<String>, undo: bool) -> Result<()> {
+    let (slug, version) = target
+        .split_once('@')
+        .ok_or_else(|| anyhow::anyhow!("Give the version to yank as {}@<version>", target))?;
+    
+    let (client, app) = authored_app(config, slug).await?;
+    let release = client
+        .app_version(&app.id, version)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("{} has no version {}", slug, version))?;
+    
+    if undo {
+        if !release.is_yanked() {
+            println!("{}@{} is not yanked", slug, version);
+            return Ok(());
+        }
+        info!("Restoring {}@{}", slug, version);
+        client.unyank_version(&release.id).await?;
+        println!("♻️  Restored {}@{}", slug, version);
+        return Ok(());
+    }
+    
+    if release.is_yanked() {
+        println!("{}@{} is already yanked", slug, version);
+        return Ok(());
+    }
+    
+    info!("Yanking {}@{}", slug, version);
+    client.yank_version(&release.id, reason.as_deref()).await?;
+    println!("🚫 Yanked {}@{}", slug, version);
+    println!("Installs skip it unless they pin {} exactly", version);
+    
+    Ok(())
+}
+
+/// Logged-in client and the app `slug`, which the user must author
+pub(crate) async fn authored_app(config: &Config, slug: &str) -> Result<(StoreClient, App)> {
+    let (client, _) = super::store::authenticated_client(config).await?;
+    let user = client.current_user().await?;
+    let app = client.app_by_slug(slug).await?;
+    if app.author_id != user.id {
+        return Err(anyhow::anyhow!("Only the author of {} can change its releases", slug));
+    }
+    
+    Ok((client, app))
+}
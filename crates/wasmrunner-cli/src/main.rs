@@ -3,8 +3,10 @@ use clap::{Parser, Subcommand};
 use anyhow::Result;
 use tracing::{info, error};
 use wasmrunner_core::config::Config;
+use wasmrunner_store::models::ModerationAction;
 
 mod commands;
+mod telemetry;
 mod utils;
 
 #[derive(Parser)]
@@ -26,7 +28,7 @@ struct Cli {
 enum Commands {
     /// Run a WASM application
     Run {
-        /// Image name with optional tag (e.g., hello-world:latest)
+        /// Image reference (e.g., hello-world:latest or hello-world@sha256:<digest>) or .wasm file
         image: String,
         
         /// Memory limit in MB
@@ -49,14 +51,84 @@ enum Commands {
         #[arg(long, short)]
         detach: bool,
         
+        /// Keep stdin open and forward it to the WASM app
+        #[arg(long, short)]
+        interactive: bool,
+        
+        /// Attach the WASM app to the host terminal
+        #[arg(long, short)]
+        tty: bool,
+        
         /// Container name
         #[arg(long)]
         name: Option<String>,
         
+        /// Grant a plugin capability to the WASM app
+        #[arg(long = "cap")]
+        capabilities: Vec<String>,
+        
+        /// Make a host directory available to the WASM app (HOST_DIR:/GUEST_DIR)
+        #[arg(long = "volume", short = 'v')]
+        volumes: Vec<String>,
+        
+        /// Accept TCP connections on a host address for the WASM app ([IP:]PORT)
+        #[arg(long = "publish", short = 'p')]
+        ports: Vec<String>,
+        
+        /// Restart policy for detached containers: no, on-failure[:MAX_RETRIES], always
+        #[arg(long, default_value = "no")]
+        restart: String,
+        
+        /// Health check: call this export, which must return 0 or nothing
+        #[arg(long, conflicts_with = "health_http")]
+        health_export: Option<String>,
+        
+        /// Health check: GET this path on the first published port
+        #[arg(long)]
+        health_http: Option<String>,
+        
+        /// Seconds between health checks
+        #[arg(long, default_value = "30")]
+        health_interval: u64,
+        
+        /// Seconds a health check may take
+        #[arg(long, default_value = "5")]
+        health_timeout: u64,
+        
+        /// Failed health checks in a row before the container is unhealthy
+        #[arg(long, default_value = "3")]
+        health_retries: u32,
+        
         /// Arguments to pass to the WASM app
         args: Vec<String>,
     },
     
+    /// Attach to a detached container's stdin and stdout
+    Attach {
+        /// Container ID or name
+        container: String,
+    },
+    
+    /// Run a detached container in the background
+    #[command(hide = true)]
+    Shim {
+        /// Container ID
+        container: String,
+    },
+    
+    /// Call an exported function of a WASM module or component
+    Call {
+        /// Image name with optional tag, or path to a .wasm file
+        image: String,
+        
+        /// Name of the exported function
+        export: String,
+        
+        /// Arguments to pass to the function
+        #[arg(allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    
     /// Build a WASM application
     Build {
         /// Tag for the built image
@@ -70,6 +142,16 @@ enum Commands {
         /// Dockerfile path
         #[arg(long, short, default_value = "Dockerfile.wasm")]
         file: String,
+        
+        /// Publisher key to sign the image with (default: security.signing_key)
+        #[arg(long)]
+        key: Option<String>,
+    },
+    
+    /// Manage image signing keys
+    Key {
+        #[command(subcommand)]
+        command: KeyCommands,
     },
     
     /// Push an image to registry
@@ -80,10 +162,30 @@ enum Commands {
     
     /// Pull an image from registry
     Pull {
-        /// Image name with tag
+        /// Image name with tag, or name@sha256:digest to load from the pull cache
         image: String,
     },
     
+    /// Run or manage an image registry
+    Registry {
+        #[command(subcommand)]
+        command: RegistryCommands,
+    },
+    
+    /// Run a group of services described by a compose file
+    Compose {
+        /// Compose file (default: ./wasmrunner.compose.toml)
+        #[arg(long, short)]
+        file: Option<std::path::PathBuf>,
+        
+        /// Project name (default: from the compose file, else the current directory)
+        #[arg(long, short = 'p')]
+        project_name: Option<String>,
+        
+        #[command(subcommand)]
+        command: ComposeCommands,
+    },
+    
     /// List running containers
     List {
         /// Show all containers (including stopped)
@@ -91,6 +193,23 @@ enum Commands {
         all: bool,
     },
     
+    /// Show live resource usage of running containers
+    Top {
+        /// Container IDs or names; all running containers when omitted
+        containers: Vec<String>,
+        
+        /// Print the usage once instead of refreshing it
+        #[arg(long)]
+        no_stream: bool,
+    },
+    
+    /// Serve runner metrics for Prometheus
+    Metrics {
+        /// Address to listen on (default: telemetry.metrics_listen from the config)
+        #[arg(long)]
+        listen: Option<String>,
+    },
+    
     /// Show container logs
     Logs {
         /// Container ID or name
@@ -133,14 +252,26 @@ enum Commands {
         /// Show only verified apps
         #[arg(long)]
         verified: bool,
+        
+        /// Results per page
+        #[arg(long, default_value = "20")]
+        limit: u64,
+        
+        /// Page of results to show, starting at 1
+        #[arg(long, default_value = "1")]
+        page: u64,
+        
+        /// Print results as JSON
+        #[arg(long)]
+        json: bool,
     },
     
     /// Install an app from the store
     Install {
-        /// App name or slug
+        /// App slug, optionally as slug:version or slug@sha256:<digest>
         name: String,
         
-        /// Specific version
+        /// Exact version or semver requirement, e.g. 1.2.0 or ^1.2
         #[arg(long)]
         version: Option<String>,
     },
@@ -150,8 +281,18 @@ enum Commands {
         /// Email address
         #[arg(long)]
         email: Option<String>,
+        
+        /// Read the password from stdin instead of prompting
+        #[arg(long)]
+        password_stdin: bool,
     },
     
+    /// Log out of WasmRunner app store
+    Logout,
+    
+    /// Show the logged-in account
+    Whoami,
+    
     /// Register new account
     Register {
         /// Email address
@@ -161,6 +302,20 @@ enum Commands {
         /// Username
         #[arg(long)]
         username: Option<String>,
+        
+        /// Read the password from stdin instead of prompting
+        #[arg(long)]
+        password_stdin: bool,
+        
+        /// Accept the Terms of Service without prompting
+        #[arg(long)]
+        accept_terms: bool,
+    },
+    
+    /// Manage API tokens for non-interactive use
+    Token {
+        #[command(subcommand)]
+        command: TokenCommands,
     },
     
     /// Publish app to store
@@ -183,16 +338,302 @@ enum Commands {
         /// Tags
         #[arg(long)]
         tag: Vec<String>,
+        
+        /// Release notes for this version
+        #[arg(long)]
+        changelog: Option<String>,
+        
+        /// Mark as a prerelease even without a prerelease version suffix
+        #[arg(long)]
+        prerelease: bool,
+        
+        /// Publisher key to sign the image with (default: security.signing_key)
+        #[arg(long)]
+        key: Option<String>,
     },
     
     /// Manage favorites
     Favorite {
-        /// App name to favorite/unfavorite
-        app: String,
+        /// App slug to favorite/unfavorite
+        #[arg(required_unless_present = "list")]
+        app: Option<String>,
         
         /// Remove from favorites
-        #[arg(long)]
+        #[arg(long, conflicts_with = "list")]
         remove: bool,
+        
+        /// List your favorites
+        #[arg(long)]
+        list: bool,
+    },
+    
+    /// Rate and review an app, or change or delete your review
+    Review {
+        /// App slug
+        app: String,
+        
+        /// Rating from 1 to 5; required for a new review
+        #[arg(long)]
+        rating: Option<i32>,
+        
+        /// Review title
+        #[arg(long)]
+        title: Option<String>,
+        
+        /// Review text
+        #[arg(long)]
+        content: Option<String>,
+        
+        /// Delete your review
+        #[arg(long, conflicts_with_all = ["rating", "title", "content"])]
+        delete: bool,
+    },
+    
+    /// Show details, versions and reviews of a store app
+    Info {
+        /// App slug
+        app: String,
+    },
+    
+    /// Show download stats for an app you publish
+    Stats {
+        /// App slug
+        app: String,
+        
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        
+        /// Last day to include (YYYY-MM-DD, default: today)
+        #[arg(long)]
+        until: Option<String>,
+        
+        /// Number of days to include when --since is not given
+        #[arg(long, default_value = "30", conflicts_with = "since")]
+        days: u32,
+        
+        /// Group downloads by day, week or month
+        #[arg(long, default_value = "day")]
+        interval: String,
+        
+        /// Output format: table, csv, json
+        #[arg(long, default_value = "table")]
+        format: String,
+        
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    
+    /// Withdraw a published version so installs skip it
+    Yank {
+        /// App and version as slug@version
+        target: String,
+        
+        /// Why the version was yanked, shown to users who pin it
+        #[arg(long)]
+        reason: Option<String>,
+        
+        /// Restore the version instead
+        #[arg(long, conflicts_with = "reason")]
+        undo: bool,
+    },
+    
+    /// Mark an app or one version (slug@version) as deprecated
+    Deprecate {
+        /// App slug, optionally as slug@version
+        target: String,
+        
+        /// Warning shown by install and run
+        #[arg(long, required_unless_present = "undo")]
+        message: Option<String>,
+        
+        /// Clear the deprecation instead
+        #[arg(long, conflicts_with = "message")]
+        undo: bool,
+    },
+    
+    /// Moderate store apps (moderators and admins only)
+    Admin {
+        #[command(subcommand)]
+        command: AdminCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyCommands {
+    /// Generate a signing key pair for a publisher
+    Generate {
+        /// Publisher name (default: security.signing_key)
+        publisher: Option<String>,
+    },
+    
+    /// Trust a publisher's public key for verifying images and plugins
+    Trust {
+        /// Publisher name
+        publisher: String,
+        
+        /// Hex-encoded ed25519 public key
+        public_key: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum RegistryCommands {
+    /// Serve the local image store over the push/pull protocol
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:5000")]
+        listen: String,
+        
+        /// Image store to serve (default: ~/.wasmrunner/images)
+        #[arg(long)]
+        root: Option<std::path::PathBuf>,
+        
+        /// Token clients must present; repeat for several (default: generate one)
+        #[arg(long)]
+        token: Vec<String>,
+        
+        /// Let clients without a token pull images
+        #[arg(long)]
+        allow_anonymous_pull: bool,
+        
+        /// Registry to fetch missing images from, making this a pull-through mirror
+        #[arg(long)]
+        upstream: Option<String>,
+    },
+    
+    /// Remove least recently used files from the pull cache
+    Gc {
+        /// Shrink the cache to this many MB (default: registry.cache_max_mb)
+        #[arg(long)]
+        max_size_mb: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ComposeCommands {
+    /// Start services, dependencies first, and follow their logs
+    Up {
+        /// Services to start, with their dependencies (default: all)
+        services: Vec<String>,
+        
+        /// Return once the services have started instead of following their logs
+        #[arg(long, short)]
+        detach: bool,
+    },
+    
+    /// Stop and remove the project's containers
+    Down,
+    
+    /// List the project's containers
+    Ps,
+    
+    /// Show service output, each line prefixed with its service
+    Logs {
+        /// Services to show (default: all)
+        services: Vec<String>,
+        
+        /// Keep printing output until the services exit
+        #[arg(long, short)]
+        follow: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AdminCommands {
+    /// Mark an app as verified
+    Verify {
+        /// App slug
+        app: String,
+        
+        /// Remove verification instead
+        #[arg(long)]
+        undo: bool,
+        
+        /// Reason recorded in the moderation log
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    
+    /// Feature an app in the store
+    Feature {
+        /// App slug
+        app: String,
+        
+        /// Stop featuring it instead
+        #[arg(long)]
+        undo: bool,
+        
+        /// Reason recorded in the moderation log
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    
+    /// Hide an app from the store; its author cannot republish it
+    Unpublish {
+        /// App slug
+        app: String,
+        
+        /// Republish it instead
+        #[arg(long)]
+        undo: bool,
+        
+        /// Reason recorded in the moderation log
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    
+    /// Yank one version (slug@version) or every version of an app
+    Yank {
+        /// App slug, optionally as slug@version
+        target: String,
+        
+        /// Restore the yanked versions instead
+        #[arg(long)]
+        undo: bool,
+        
+        /// Reason recorded in the moderation log and shown to users
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    
+    /// Show recent moderation actions
+    Log {
+        /// Only show actions on this app
+        #[arg(long)]
+        app: Option<String>,
+        
+        /// Number of entries to show
+        #[arg(long, default_value = "20")]
+        limit: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum TokenCommands {
+    /// Create a scoped API token; set it as WASMRUNNER_TOKEN to use it
+    Create {
+        /// Name to identify the token
+        #[arg(long)]
+        name: String,
+        
+        /// Scope to grant: publish, favorites or reviews
+        #[arg(long, required = true)]
+        scope: Vec<String>,
+        
+        /// Days until the token expires (default: never)
+        #[arg(long)]
+        expires_in_days: Option<u32>,
+    },
+    
+    /// List your API tokens
+    List,
+    
+    /// Revoke an API token
+    Revoke {
+        /// Token ID
+        id: String,
     },
 }
 
@@ -200,39 +641,93 @@ enum Commands {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
     
-    // Initialize logging
-    let subscriber = tracing_subscriber::fmt()
-        .with_max_level(if cli.verbose {
-            tracing::Level::DEBUG
-        } else {
-            tracing::Level::INFO
-        })
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)?;
-    
     // Load configuration
     let config = Config::load(cli.config.as_deref())?;
+    
+    // Initialize logging and trace export
+    telemetry::init(cli.verbose, &config.telemetry)?;
     info!("WasmRunner starting with config: {:?}", config);
     
     // Execute command
-    match cli.command {
+    let result = run(cli.command, config).await;
+    telemetry::shutdown();
+    result
+}
+
+async fn run(command: Commands, config: Config) -> Result<()> {
+    match command {
         Commands::Run { 
-            image, memory, cpu, env, network, detach, name, args 
+            image, memory, cpu, env, network, detach, interactive, tty, name, capabilities, volumes, ports, restart,
+            health_export, health_http, health_interval, health_timeout, health_retries, args 
         } => {
-            commands::run::execute(image, memory, cpu, env, network, detach, name, args).await
+            let health = commands::run::HealthOptions {
+                export: health_export,
+                http: health_http,
+                interval_secs: health_interval,
+                timeout_secs: health_timeout,
+                retries: health_retries,
+            };
+            commands::run::execute(
+                &config, image, memory, cpu, env, network, detach, interactive, tty, name, capabilities, volumes,
+                ports, restart, health, args
+            ).await
+        },
+        Commands::Attach { container } => {
+            commands::attach::execute(container).await
+        },
+        Commands::Shim { container } => {
+            commands::shim::execute(&config, container).await
+        },
+        Commands::Call { image, export, args } => {
+            commands::call::execute(&config, image, export, args).await
+        },
+        Commands::Build { tag, context, file, key } => {
+            commands::build::execute(&config, tag, context, file, key).await
         },
-        Commands::Build { tag, context, file } => {
-            commands::build::execute(tag, context, file).await
+        Commands::Key { command } => match command {
+            KeyCommands::Generate { publisher } => {
+                commands::key::generate(&config, publisher).await
+            },
+            KeyCommands::Trust { publisher, public_key } => {
+                commands::key::trust(&config, publisher, public_key).await
+            },
         },
         Commands::Push { image } => {
-            commands::push::execute(image).await
+            commands::push::execute(&config, image).await
         },
         Commands::Pull { image } => {
-            commands::pull::execute(image).await
+            commands::pull::execute(&config, image).await
+        },
+        Commands::Compose { file, project_name, command } => {
+            let options = commands::compose::ProjectOptions { file, project_name };
+            match command {
+                ComposeCommands::Up { services, detach } => {
+                    commands::compose::up(&config, options, services, detach).await
+                },
+                ComposeCommands::Down => commands::compose::down(&config, options).await,
+                ComposeCommands::Ps => commands::compose::ps(options).await,
+                ComposeCommands::Logs { services, follow } => {
+                    commands::compose::logs(options, services, follow).await
+                },
+            }
+        },
+        Commands::Registry { command } => match command {
+            RegistryCommands::Serve { listen, root, token, allow_anonymous_pull, upstream } => {
+                commands::registry::serve(&config, listen, root, token, allow_anonymous_pull, upstream).await
+            },
+            RegistryCommands::Gc { max_size_mb } => {
+                commands::registry::gc(&config, max_size_mb).await
+            },
         },
         Commands::List { all } => {
             commands::list::execute(all).await
         },
+        Commands::Top { containers, no_stream } => {
+            commands::top::execute(containers, no_stream).await
+        },
+        Commands::Metrics { listen } => {
+            commands::metrics::serve(&config, listen).await
+        },
         Commands::Logs { container, follow, tail } => {
             commands::logs::execute(container, follow, tail).await
         },
@@ -240,25 +735,80 @@ async fn main() -> Result<()> {
             commands::stop::execute(container).await
         },
         Commands::Remove { container, force } => {
-            commands::remove::execute(container, force).await
+            commands::remove::execute(&config, container, force).await
         },
-        Commands::Search { term, category, verified } => {
-            commands::search::execute(term, category, verified).await
+        Commands::Search { term, category, verified, limit, page, json } => {
+            commands::search::execute(&config, term, category, verified, limit, page, json).await
         },
         Commands::Install { name, version } => {
-            commands::install::execute(name, version).await
+            commands::install::execute(&config, name, version).await
+        },
+        Commands::Login { email, password_stdin } => {
+            commands::login::execute(&config, email, password_stdin).await
+        },
+        Commands::Logout => {
+            commands::logout::execute(&config).await
+        },
+        Commands::Whoami => {
+            commands::whoami::execute(&config).await
+        },
+        Commands::Register { email, username, password_stdin, accept_terms } => {
+            commands::register::execute(&config, email, username, password_stdin, accept_terms).await
+        },
+        Commands::Token { command } => match command {
+            TokenCommands::Create { name, scope, expires_in_days } => {
+                commands::token::create(&config, name, scope, expires_in_days).await
+            },
+            TokenCommands::List => {
+                commands::token::list(&config).await
+            },
+            TokenCommands::Revoke { id } => {
+                commands::token::revoke(&config, id).await
+            },
+        },
+        Commands::Publish { image, manifest, description, category, tag, changelog, prerelease, key } => {
+            commands::publish::execute(
+                &config, image, manifest, description, category, tag, changelog, prerelease, key
+            ).await
+        },
+        Commands::Favorite { app, remove, list } => {
+            commands::favorite::execute(&config, app, remove, list).await
+        },
+        Commands::Review { app, rating, title, content, delete } => {
+            commands::review::execute(&config, app, rating, title, content, delete).await
+        },
+        Commands::Info { app } => {
+            commands::info::execute(&config, app).await
         },
-        Commands::Login { email } => {
-            commands::login::execute(email).await
+        Commands::Stats { app, since, until, days, interval, format, output } => {
+            commands::stats::execute(&config, app, since, until, days, interval, format, output).await
         },
-        Commands::Register { email, username } => {
-            commands::register::execute(email, username).await
+        Commands::Yank { target, reason, undo } => {
+            commands::yank::execute(&config, target, reason, undo).await
         },
-        Commands::Publish { image, manifest, description, category, tag } => {
-            commands::publish::execute(image, manifest, description, category, tag).await
+        Commands::Deprecate { target, message, undo } => {
+            commands::deprecate::execute(&config, target, message, undo).await
         },
-        Commands::Favorite { app, remove } => {
-            commands::favorite::execute(app, remove).await
+        Commands::Admin { command } => match command {
+            AdminCommands::Verify { app, undo, reason } => {
+                let action = if undo { ModerationAction::Unverify } else { ModerationAction::Verify };
+                commands::admin::moderate(&config, action, app, reason).await
+            },
+            AdminCommands::Feature { app, undo, reason } => {
+                let action = if undo { ModerationAction::Unfeature } else { ModerationAction::Feature };
+                commands::admin::moderate(&config, action, app, reason).await
+            },
+            AdminCommands::Unpublish { app, undo, reason } => {
+                let action = if undo { ModerationAction::Publish } else { ModerationAction::Unpublish };
+                commands::admin::moderate(&config, action, app, reason).await
+            },
+            AdminCommands::Yank { target, undo, reason } => {
+                let action = if undo { ModerationAction::Unyank } else { ModerationAction::Yank };
+                commands::admin::moderate(&config, action, target, reason).await
+            },
+            AdminCommands::Log { app, limit } => {
+                commands::admin::log(&config, app, limit).await
+            },
         },
     }
 }
@@ -0,0 +1,61 @@
+//! Logging, and trace export to an OTLP collector when one is configured
+
+use anyhow::Result;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use wasmrunner_core::config::TelemetryConfig;
+
+/// Install the global subscriber: logs to stdout, plus spans to the OTLP
+/// endpoint when one is configured and the `otlp` feature is built in
+pub fn init(verbose: bool, config: &TelemetryConfig) -> Result<()> {
+    let level = if verbose { LevelFilter::DEBUG } else { LevelFilter::INFO };
+    let logs = tracing_subscriber::fmt::layer().with_filter(level);
+    
+    #[cfg(feature = "otlp")]
+    let traces = match config.otlp_endpoint() {
+        Some(endpoint) => Some(otlp::layer(&endpoint, &config.service_name)?.with_filter(LevelFilter::INFO)),
+        None => None,
+    };
+    #[cfg(not(feature = "otlp"))]
+    let traces: Option<tracing_subscriber::layer::Identity> = None;
+    
+    tracing_subscriber::registry().with(logs).with(traces).try_init()?;
+    
+    #[cfg(not(feature = "otlp"))]
+    if config.otlp_endpoint().is_some() {
+        tracing::warn!("Traces are not exported: wasmrunner was built without the otlp feature");
+    }
+    Ok(())
+}
+
+/// Flush spans not yet exported; call before the process exits
+pub fn shutdown() {
+    #[cfg(feature = "otlp")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+#[cfg(feature = "otlp")]
+mod otlp {
+    use anyhow::Result;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace, Resource};
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+    
+    /// A layer exporting spans in batches over OTLP gRPC
+    pub fn layer<S>(endpoint: &str, service_name: &str) -> Result<impl Layer<S>>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+            .with_trace_config(
+                trace::config().with_resource(Resource::new([KeyValue::new("service.name", service_name.to_string())])),
+            )
+            .install_batch(runtime::Tokio)?;
+        
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
@@ -0,0 +1,25 @@
+
+use anyhow::Result;
+use std::io::{BufRead, IsTerminal};
+
+/// Read a password piped on stdin, without its trailing newline
+pub fn read_password_stdin() -> Result<String> {
+    let mut password = String::new();
+    std::io::stdin().lock().read_line(&mut password)?;
+    
+    let password = password.trim_end_matches(['\r', '\n']).to_string();
+    if password.is_empty() {
+        return Err(anyhow::anyhow!("No password given on stdin"));
+    }
+    
+    Ok(password)
+}
+
+/// Fail instead of prompting when there is no terminal to prompt on
+pub fn require_terminal(missing: &str) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow::anyhow!("{} is required when not running in a terminal", missing));
+    }
+    
+    Ok(())
+}
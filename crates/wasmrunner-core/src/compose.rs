@@ -0,0 +1,366 @@
+use crate::container::{
+    ContainerConfig, HealthCheck, HealthTest, PortMapping, ResourceLimits, RestartPolicy, VolumeMount,
+};
+use crate::reference::ImageReference;
+use crate::{Result, WasmRunnerError};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// File name `compose` commands look for in the current directory
+pub const COMPOSE_FILE: &str = "wasmrunner.compose.toml";
+
+/// Container label naming the compose project a container belongs to
+pub const PROJECT_LABEL: &str = "wasmrunner.compose.project";
+
+/// Container label naming the compose service a container runs
+pub const SERVICE_LABEL: &str = "wasmrunner.compose.service";
+
+/// A group of cooperating services, as described by a compose file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComposeFile {
+    /// Project name; defaults to the name of the directory holding the file
+    pub name: Option<String>,
+    
+    pub services: BTreeMap<String, ServiceSpec>,
+    
+    /// Directory holding the file, which relative volume paths start from
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+/// One service of a compose file, run as a single detached container
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceSpec {
+    pub image: String,
+    
+    #[serde(default)]
+    pub args: Vec<String>,
+    
+    #[serde(default)]
+    pub environment: HashMap<String, String>,
+    
+    /// Memory limit in MB
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u64,
+    
+    /// CPU limit as percentage
+    #[serde(default = "default_cpu_percent")]
+    pub cpu_percent: u32,
+    
+    /// Network mode: none, host
+    #[serde(default = "default_network")]
+    pub network: String,
+    
+    /// Plugin capabilities granted to the service
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    
+    /// `HOST_DIR:/GUEST_DIR` mounts
+    #[serde(default)]
+    pub volumes: Vec<String>,
+    
+    /// `[IP:]PORT` addresses the service accepts connections on
+    #[serde(default)]
+    pub ports: Vec<String>,
+    
+    /// Services that must have started before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    
+    /// `no`, `on-failure[:MAX_RETRIES]` or `always`
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    
+    /// Probe run while the service is up, e.g. `{ http = "/healthz", interval_secs = 10 }`
+    pub healthcheck: Option<HealthCheck>,
+}
+
+fn default_memory_mb() -> u64 {
+    128
+}
+
+fn default_cpu_percent() -> u32 {
+    100
+}
+
+fn default_network() -> String {
+    "none".to_string()
+}
+
+impl ComposeFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let invalid = |message: String| WasmRunnerError::InvalidCompose {
+            path: path.display().to_string(),
+            message,
+        };
+        
+        let content = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let mut compose: ComposeFile = toml::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+        compose.dir = std::fs::canonicalize(path)?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        
+        compose.validate().map_err(|e| invalid(e.to_string()))?;
+        Ok(compose)
+    }
+    
+    fn validate(&self) -> Result<()> {
+        if self.services.is_empty() {
+            return Err(anyhow::anyhow!("no services defined"));
+        }
+        
+        for (name, service) in &self.services {
+            if !is_valid_service_name(name) {
+                return Err(anyhow::anyhow!(
+                    "service name {} must be letters, digits, '_' and '-'",
+                    name
+                ));
+            }
+            ImageReference::parse(&service.image)
+                .map_err(|e| anyhow::anyhow!("service {}: {}", name, e))?;
+            service
+                .ports()
+                .map_err(|e| anyhow::anyhow!("service {}: {}", name, e))?;
+            for spec in &service.volumes {
+                spec.parse::<VolumeMount>()
+                    .map_err(|e| anyhow::anyhow!("service {}: {}", name, e))?;
+            }
+            if let Some(HealthCheck { test: HealthTest::Http(path), .. }) = &service.healthcheck {
+                if service.ports.is_empty() || !path.starts_with('/') {
+                    return Err(anyhow::anyhow!(
+                        "service {}: HTTP health checks need a published port and a path starting with '/'",
+                        name
+                    ));
+                }
+            }
+            for dependency in &service.depends_on {
+                if dependency == name || !self.services.contains_key(dependency) {
+                    return Err(anyhow::anyhow!(
+                        "service {} depends on unknown service {}",
+                        name, dependency
+                    ));
+                }
+            }
+        }
+        
+        // Reports dependency cycles
+        self.start_order(&[])?;
+        Ok(())
+    }
+    
+    /// The project name: `name` from the file, else the directory name
+    pub fn project_name(&self) -> String {
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => self
+                .dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "default".to_string()),
+        };
+        project_name(&name)
+    }
+    
+    /// Services in the order to start them, dependencies first
+    ///
+    /// With `only` non-empty, just those services and what they depend on.
+    /// Stop services in the reverse order.
+    pub fn start_order(&self, only: &[String]) -> Result<Vec<String>> {
+        let mut wanted: Vec<&str> = Vec::new();
+        let mut pending: Vec<&str> = if only.is_empty() {
+            self.services.keys().map(String::as_str).collect()
+        } else {
+            only.iter().map(String::as_str).collect()
+        };
+        while let Some(name) = pending.pop() {
+            let service = self
+                .services
+                .get(name)
+                .ok_or_else(|| anyhow::anyhow!("No service named {}", name))?;
+            if !wanted.contains(&name) {
+                wanted.push(name);
+                pending.extend(service.depends_on.iter().map(String::as_str));
+            }
+        }
+        
+        // Repeatedly take the first service, by name, whose dependencies have all been taken
+        let mut order: Vec<String> = Vec::new();
+        wanted.sort();
+        while !wanted.is_empty() {
+            let ready = wanted.iter().position(|name| {
+                self.services[*name]
+                    .depends_on
+                    .iter()
+                    .all(|dependency| order.contains(dependency))
+            });
+            match ready {
+                Some(i) => order.push(wanted.remove(i).to_string()),
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "services {} depend on each other",
+                        wanted.join(", ")
+                    ))
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+impl ServiceSpec {
+    pub fn ports(&self) -> Result<Vec<PortMapping>> {
+        self.ports.iter().map(|spec| spec.parse()).collect()
+    }
+    
+    /// Volume mounts with host paths made absolute against `dir`
+    pub fn volumes(&self, dir: &Path) -> Result<Vec<VolumeMount>> {
+        self.volumes
+            .iter()
+            .map(|spec| {
+                let mut volume: VolumeMount = spec.parse()?;
+                let host = dir.join(&volume.host);
+                volume.host = std::fs::canonicalize(&host)
+                    .map_err(|e| anyhow::anyhow!("Volume {}: {}", host.display(), e))?;
+                Ok(volume)
+            })
+            .collect()
+    }
+    
+    /// Configuration for the container running `service` of `project`
+    pub fn container_config(&self, project: &str, service: &str, dir: &Path) -> Result<ContainerConfig> {
+        Ok(ContainerConfig {
+            image: self.image.clone(),
+            name: format!("{}-{}", project, service),
+            args: self.args.clone(),
+            environment: self.environment.clone(),
+            resource_limits: ResourceLimits {
+                memory_mb: self.memory_mb,
+                cpu_percent: self.cpu_percent,
+            },
+            network_mode: self.network.clone(),
+            detached: true,
+            interactive: false,
+            tty: false,
+            capabilities: self.capabilities.clone(),
+            volumes: self.volumes(dir)?,
+            ports: self.ports()?,
+            labels: HashMap::from([
+                (PROJECT_LABEL.to_string(), project.to_string()),
+                (SERVICE_LABEL.to_string(), service.to_string()),
+            ]),
+            restart: self.restart,
+            healthcheck: self.healthcheck.clone(),
+        })
+    }
+}
+
+/// `name` reduced to lowercase letters, digits and `-`, for use in container names
+pub fn project_name(name: &str) -> String {
+    let name: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    
+    if name.is_empty() {
+        "default".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn is_valid_service_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn compose(content: &str) -> Result<ComposeFile> {
+        let compose: ComposeFile = toml::from_str(content)?;
+        compose.validate()?;
+        Ok(compose)
+    }
+    
+    #[test]
+    fn dependencies_start_first() {
+        let compose = compose(
+            r#"
+            [services.web]
+            image = "web"
+            depends_on = ["api"]
+            
+            [services.api]
+            image = "api"
+            depends_on = ["db", "cache"]
+            
+            [services.db]
+            image = "db"
+            
+            [services.cache]
+            image = "cache"
+            "#,
+        )
+        .unwrap();
+        
+        assert_eq!(compose.start_order(&[]).unwrap(), ["cache", "db", "api", "web"]);
+    }
+    
+    #[test]
+    fn selected_services_bring_their_dependencies() {
+        let compose = compose(
+            r#"
+            [services.web]
+            image = "web"
+            depends_on = ["db"]
+            
+            [services.db]
+            image = "db"
+            
+            [services.worker]
+            image = "worker"
+            "#,
+        )
+        .unwrap();
+        
+        assert_eq!(compose.start_order(&["web".to_string()]).unwrap(), ["db", "web"]);
+        assert!(compose.start_order(&["missing".to_string()]).is_err());
+    }
+    
+    #[test]
+    fn cycles_are_rejected() {
+        let err = compose(
+            r#"
+            [services.a]
+            image = "a"
+            depends_on = ["b"]
+            
+            [services.b]
+            image = "b"
+            depends_on = ["a"]
+            "#,
+        )
+        .unwrap_err();
+        
+        assert!(err.to_string().contains("depend on each other"), "{}", err);
+    }
+    
+    #[test]
+    fn unknown_and_self_dependencies_are_rejected() {
+        for depends_on in ["db", "web"] {
+            let err = compose(&format!(
+                "[services.web]\nimage = \"web\"\ndepends_on = [\"{}\"]\n",
+                depends_on
+            ))
+            .unwrap_err();
+            
+            assert!(err.to_string().contains("unknown service"), "{}", err);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use crate::signing::ImagePolicy;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +12,9 @@ pub struct Config {
     pub registry: RegistryConfig,
     pub plugins: PluginConfig,
     pub supabase: Option<SupabaseConfig>,
+    
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +23,11 @@ pub struct RuntimeConfig {
     pub memory_limit_mb: u64,
     pub cpu_limit_percent: u32,
     pub timeout_seconds: u64,
+    
+    /// Count the WebAssembly instructions guests execute, shown by `top`;
+    /// off by default, as metered code runs noticeably slower
+    #[serde(default)]
+    pub fuel_metering: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +37,13 @@ pub struct SecurityConfig {
     pub enable_memory_guard: bool,
     pub allow_network: bool,
     pub allow_filesystem: bool,
+    pub image_policy: ImagePolicy,
+    
+    /// Directory holding this machine's private signing keys
+    pub signing_keys_dir: PathBuf,
+    
+    /// Publisher whose key signs built and published images by default
+    pub signing_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +51,31 @@ pub struct RegistryConfig {
     pub default_registry: String,
     pub cache_dir: PathBuf,
     pub auth_config: Option<PathBuf>,
+    
+    /// Bearer token for push and pull; `WASMRUNNER_REGISTRY_TOKEN` takes precedence
+    #[serde(default)]
+    pub token: Option<String>,
+    
+    /// Tokens for particular registries, keyed as they are written in image
+    /// references; mirrors are only ever sent the token listed here for them
+    #[serde(default)]
+    pub tokens: HashMap<String, String>,
+    
+    /// Registries reached over plain HTTP; `localhost` and `127.0.0.1` always are
+    #[serde(default)]
+    pub insecure_registries: Vec<String>,
+    
+    /// Registries tried in order before `default_registry` when pulling from it
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    
+    /// Size limit of the pull cache under `cache_dir`, in MB
+    #[serde(default = "default_cache_max_mb")]
+    pub cache_max_mb: u64,
+}
+
+fn default_cache_max_mb() -> u64 {
+    1024
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +85,49 @@ pub struct PluginConfig {
     pub trusted_publishers: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Address `wasmrunner metrics` serves Prometheus metrics on
+    #[serde(default = "default_metrics_listen")]
+    pub metrics_listen: String,
+    
+    /// OTLP gRPC endpoint to export traces to; `OTEL_EXPORTER_OTLP_ENDPOINT` takes precedence
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    
+    /// `service.name` of exported traces
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+fn default_metrics_listen() -> String {
+    "127.0.0.1:9464".to_string()
+}
+
+fn default_service_name() -> String {
+    "wasmrunner".to_string()
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            metrics_listen: default_metrics_listen(),
+            otlp_endpoint: None,
+            service_name: default_service_name(),
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// Where to export traces, if anywhere
+    pub fn otlp_endpoint(&self) -> Option<String> {
+        std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+            .ok()
+            .filter(|endpoint| !endpoint.is_empty())
+            .or_else(|| self.otlp_endpoint.clone())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SupabaseConfig {
     pub url: String,
@@ -66,6 +151,7 @@ impl Default for Config {
                 memory_limit_mb: 128,
                 cpu_limit_percent: 100,
                 timeout_seconds: 300,
+                fuel_metering: false,
             },
             security: SecurityConfig {
                 enable_seccomp: true,
@@ -73,11 +159,19 @@ impl Default for Config {
                 enable_memory_guard: true,
                 allow_network: false,
                 allow_filesystem: true,
+                image_policy: ImagePolicy::Permissive,
+                signing_keys_dir: wasmrunner_dir.join("signing"),
+                signing_key: None,
             },
             registry: RegistryConfig {
                 default_registry: "registry.wasmrunner.dev".to_string(),
                 cache_dir: wasmrunner_dir.join("cache"),
                 auth_config: Some(wasmrunner_dir.join("auth.json")),
+                token: None,
+                tokens: HashMap::new(),
+                insecure_registries: Vec::new(),
+                mirrors: Vec::new(),
+                cache_max_mb: default_cache_max_mb(),
             },
             plugins: PluginConfig {
                 plugin_dir: wasmrunner_dir.join("plugins"),
@@ -85,6 +179,7 @@ impl Default for Config {
                 trusted_publishers: vec!["wasmrunner.dev".to_string()],
             },
             supabase: None,
+            telemetry: TelemetryConfig::default(),
         }
     }
 }
@@ -1,7 +1,13 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 use uuid::Uuid;
+use crate::events::{EventBus, EventKind, LifecycleEvent};
 use crate::Result;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +18,19 @@ pub struct Container {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub started_at: Option<chrono::DateTime<chrono::Utc>>,
     pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    
+    /// Process hosting the container, when it runs detached
+    #[serde(default)]
+    pub pid: Option<u32>,
+    
+    /// Times the guest has been started again under the restart policy
+    #[serde(default)]
+    pub restart_count: u32,
+    
+    #[serde(skip)]
+    events: Option<EventBus>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +42,97 @@ pub struct ContainerConfig {
     pub resource_limits: ResourceLimits,
     pub network_mode: String,
     pub detached: bool,
+    
+    /// Forward host stdin to the guest
+    #[serde(default)]
+    pub interactive: bool,
+    
+    /// Attach the guest to the host terminal
+    #[serde(default)]
+    pub tty: bool,
+    
+    /// Plugin capabilities granted to the guest
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    
+    /// Host directories preopened for the guest
+    #[serde(default)]
+    pub volumes: Vec<VolumeMount>,
+    
+    /// Host addresses the guest accepts TCP connections on
+    #[serde(default)]
+    pub ports: Vec<PortMapping>,
+    
+    /// Free-form metadata, e.g. the compose project and service a container belongs to
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    
+    /// Whether a detached guest is started again after it exits
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    
+    /// Probe marking a running detached container unhealthy when it keeps failing
+    #[serde(default)]
+    pub healthcheck: Option<HealthCheck>,
+}
+
+/// A host directory visible to the guest at `guest`, written `HOST:GUEST`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeMount {
+    pub host: PathBuf,
+    pub guest: String,
+}
+
+impl FromStr for VolumeMount {
+    type Err = anyhow::Error;
+    
+    fn from_str(spec: &str) -> Result<Self> {
+        match spec.rsplit_once(':') {
+            Some((host, guest)) if !host.is_empty() && guest.starts_with('/') => Ok(Self {
+                host: PathBuf::from(host),
+                guest: guest.to_string(),
+            }),
+            _ => Err(anyhow::anyhow!("Invalid volume {}; expected HOST_DIR:/GUEST_DIR", spec)),
+        }
+    }
+}
+
+impl fmt::Display for VolumeMount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host.display(), self.guest)
+    }
+}
+
+/// A host address whose listening socket is handed to the guest, written `[IP:]PORT`
+///
+/// WASI guests cannot bind sockets themselves; they accept connections on
+/// sockets preopened after their volumes, in the order the ports are given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortMapping {
+    pub host: SocketAddr,
+}
+
+impl FromStr for PortMapping {
+    type Err = anyhow::Error;
+    
+    fn from_str(spec: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("Invalid port {}; expected [IP:]PORT", spec);
+        let (ip, port) = match spec.rsplit_once(':') {
+            Some((ip, port)) => {
+                let ip = ip.trim_start_matches('[').trim_end_matches(']');
+                (ip.parse::<IpAddr>().map_err(|_| invalid())?, port)
+            }
+            None => (IpAddr::V4(Ipv4Addr::UNSPECIFIED), spec),
+        };
+        let port = port.parse::<u16>().map_err(|_| invalid())?;
+        Ok(Self { host: SocketAddr::new(ip, port) })
+    }
+}
+
+impl fmt::Display for PortMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.host)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,14 +141,200 @@ pub struct ResourceLimits {
     pub cpu_percent: u32,
 }
 
+/// What a running guest instance is using, as measured by the runtime
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// Current size of the instance's linear memories
+    pub memory_bytes: u64,
+    
+    /// Wasmtime fuel consumed, roughly one unit per WebAssembly instruction,
+    /// when `runtime.fuel_metering` is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel_consumed: Option<u64>,
+    
+    /// CPU time the thread running the instance has spent in it, where the
+    /// platform can measure it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time_ms: Option<u64>,
+    
+    /// Seconds since the instance was created
+    pub uptime_secs: u64,
+    
+    /// WASI file descriptors the guest holds, including standard I/O
+    pub open_handles: u64,
+    
+    /// Bytes read from and written to sockets
+    pub net_rx_bytes: u64,
+    pub net_tx_bytes: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ContainerState {
     Created,
     Running,
+    
+    /// Waiting to start the guest again under the restart policy
+    Restarting,
+    
+    /// Running, but the health check has failed `retries` times in a row
+    Unhealthy,
+    
     Stopped,
     Error(String),
 }
 
+/// Delay before the first restart; each consecutive restart doubles it
+pub const RESTART_BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+
+/// Longest delay between restarts
+pub const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// A guest that ran at least this long before exiting restarts without backoff
+pub const RESTART_BACKOFF_RESET: Duration = Duration::from_secs(10);
+
+/// When a detached container's guest is started again after it exits,
+/// written `no`, `on-failure[:MAX_RETRIES]` or `always`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RestartPolicy {
+    #[default]
+    No,
+    
+    /// After a non-zero exit code or a trap, up to `max_retries` restarts in a row
+    OnFailure { max_retries: Option<u32> },
+    
+    /// After every exit
+    Always,
+}
+
+impl RestartPolicy {
+    /// Whether to restart a guest that exited, after `consecutive` restarts without a stable run
+    pub fn should_restart(&self, failed: bool, consecutive: u32) -> bool {
+        match self {
+            RestartPolicy::No => false,
+            RestartPolicy::OnFailure { max_retries } => {
+                failed && max_retries.is_none_or(|max| consecutive < max)
+            }
+            RestartPolicy::Always => true,
+        }
+    }
+    
+    /// Delay before the restart following `consecutive` earlier ones
+    pub fn backoff(consecutive: u32) -> Duration {
+        RESTART_BACKOFF_INITIAL
+            .saturating_mul(2u32.saturating_pow(consecutive))
+            .min(RESTART_BACKOFF_MAX)
+    }
+}
+
+impl FromStr for RestartPolicy {
+    type Err = anyhow::Error;
+    
+    fn from_str(spec: &str) -> Result<Self> {
+        match spec.split_once(':') {
+            None if spec == "no" => Ok(RestartPolicy::No),
+            None if spec == "always" => Ok(RestartPolicy::Always),
+            None if spec == "on-failure" => Ok(RestartPolicy::OnFailure { max_retries: None }),
+            Some(("on-failure", retries)) => match retries.parse() {
+                Ok(max_retries) => Ok(RestartPolicy::OnFailure { max_retries: Some(max_retries) }),
+                Err(_) => Err(anyhow::anyhow!("Invalid retry count in restart policy {}", spec)),
+            },
+            _ => Err(anyhow::anyhow!(
+                "Invalid restart policy {}; expected no, on-failure[:MAX_RETRIES] or always",
+                spec
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for RestartPolicy {
+    type Error = anyhow::Error;
+    
+    fn try_from(spec: String) -> Result<Self> {
+        spec.parse()
+    }
+}
+
+impl From<RestartPolicy> for String {
+    fn from(policy: RestartPolicy) -> Self {
+        policy.to_string()
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestartPolicy::No => write!(f, "no"),
+            RestartPolicy::OnFailure { max_retries: None } => write!(f, "on-failure"),
+            RestartPolicy::OnFailure { max_retries: Some(max) } => write!(f, "on-failure:{}", max),
+            RestartPolicy::Always => write!(f, "always"),
+        }
+    }
+}
+
+/// A probe run periodically against a running detached container
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthCheck {
+    #[serde(flatten)]
+    pub test: HealthTest,
+    
+    /// Seconds between probes
+    #[serde(default = "default_health_interval")]
+    pub interval_secs: u64,
+    
+    /// Seconds a probe may take before it counts as failed
+    #[serde(default = "default_health_timeout")]
+    pub timeout_secs: u64,
+    
+    /// Failures in a row before the container is unhealthy
+    #[serde(default = "default_health_retries")]
+    pub retries: u32,
+    
+    /// Seconds after the guest starts during which failures are not counted
+    #[serde(default)]
+    pub start_period_secs: u64,
+}
+
+/// What a health check probes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthTest {
+    /// Call this export, taking no arguments, on a separate instance of the
+    /// module; a trap, a timeout or a non-zero result is a failure
+    ///
+    /// The instance is fresh on every probe, so this checks that the module
+    /// can still start and answer, not that the running guest is responsive.
+    Export(String),
+    
+    /// GET this path on the container's first published port; any status
+    /// below 400 is healthy
+    Http(String),
+}
+
+fn default_health_interval() -> u64 {
+    30
+}
+
+fn default_health_timeout() -> u64 {
+    5
+}
+
+fn default_health_retries() -> u32 {
+    3
+}
+
+impl HealthCheck {
+    pub fn new(test: HealthTest) -> Self {
+        Self {
+            test,
+            interval_secs: default_health_interval(),
+            timeout_secs: default_health_timeout(),
+            retries: default_health_retries(),
+            start_period_secs: 0,
+        }
+    }
+}
+
 impl Container {
     pub fn new(config: ContainerConfig, sandbox_config: crate::sandbox::SandboxConfig) -> Result<Self> {
         Ok(Self {
@@ -48,32 +344,175 @@ impl Container {
             created_at: chrono::Utc::now(),
             started_at: None,
             finished_at: None,
+            exit_code: None,
+            pid: None,
+            restart_count: 0,
+            events: None,
         })
     }
     
-    pub async fn start_detached(&mut self, runtime_manager: &crate::runtime::RuntimeManager) -> Result<String> {
+    /// Publish this container's state transitions on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+    
+    /// Whether the guest is running or about to run again
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self.state,
+            ContainerState::Running | ContainerState::Restarting | ContainerState::Unhealthy
+        )
+    }
+    
+    /// Record that the guest has started executing
+    pub async fn mark_running(&mut self) {
         self.state = ContainerState::Running;
         self.started_at = Some(chrono::Utc::now());
+        self.emit(EventKind::Started).await;
+    }
+    
+    /// Record that the guest exited with the given code
+    pub async fn mark_exited(&mut self, exit_code: i32) {
+        self.state = ContainerState::Stopped;
+        self.exit_code = Some(exit_code);
+        self.finished_at = Some(chrono::Utc::now());
+        self.emit(EventKind::Exited { exit_code }).await;
+    }
+    
+    /// Record that the guest trapped or failed before producing an exit code
+    pub async fn mark_trapped(&mut self, message: String) {
+        self.state = ContainerState::Error(message.clone());
+        self.finished_at = Some(chrono::Utc::now());
+        self.emit(EventKind::Trapped { message }).await;
+    }
+    
+    /// Record that the guest will be started again after exiting
+    pub async fn mark_restarting(&mut self) {
+        self.state = ContainerState::Restarting;
+        self.restart_count += 1;
+        self.emit(EventKind::Restarting { restart_count: self.restart_count }).await;
+    }
+    
+    /// Record a health check verdict, returning whether the state changed
+    ///
+    /// Only running containers change between Running and Unhealthy.
+    pub async fn mark_health(&mut self, healthy: bool) -> bool {
+        match (&self.state, healthy) {
+            (ContainerState::Running, false) => {
+                self.state = ContainerState::Unhealthy;
+                self.emit(EventKind::Unhealthy).await;
+                true
+            }
+            (ContainerState::Unhealthy, true) => {
+                self.state = ContainerState::Running;
+                self.emit(EventKind::Healthy).await;
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Record that the guest was killed for exceeding its memory limit
+    pub async fn mark_oom_killed(&mut self) {
+        self.state = ContainerState::Error("OOM killed".to_string());
+        self.finished_at = Some(chrono::Utc::now());
+        self.emit(EventKind::OomKilled).await;
+    }
+    
+    /// Publish a lifecycle event, waiting for room in subscriber queues
+    pub async fn emit(&self, kind: EventKind) {
+        if let Some(events) = &self.events {
+            events.emit(LifecycleEvent::new(self, kind)).await;
+        }
+    }
+    
+    /// Human readable status, as shown by `list`
+    pub fn status(&self) -> String {
+        match &self.state {
+            ContainerState::Created => "Created".to_string(),
+            ContainerState::Running if self.restart_count > 0 => {
+                format!("Running (restarted {}x)", self.restart_count)
+            }
+            ContainerState::Running => "Running".to_string(),
+            ContainerState::Restarting => format!("Restarting ({})", self.restart_count),
+            ContainerState::Unhealthy => "Running (unhealthy)".to_string(),
+            ContainerState::Stopped => format!("Exited ({})", self.exit_code.unwrap_or(0)),
+            ContainerState::Error(message) => format!("Error: {}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn volume_mounts_split_on_the_last_colon() {
+        let volume: VolumeMount = "C:/data:/data".parse().unwrap();
+        assert_eq!(volume.host, PathBuf::from("C:/data"));
+        assert_eq!(volume.guest, "/data");
+        
+        for spec in ["/data", ":/data", "./data:data"] {
+            assert!(spec.parse::<VolumeMount>().is_err(), "{} should be rejected", spec);
+        }
+    }
+    
+    #[test]
+    fn ports_default_to_every_address() {
+        let port: PortMapping = "8080".parse().unwrap();
+        assert_eq!(port.host, "0.0.0.0:8080".parse::<SocketAddr>().unwrap());
         
-        // TODO: Actually start the container in background
-        // This would involve:
-        // 1. Loading the WASM module
-        // 2. Setting up the sandbox
-        // 3. Starting execution in a separate task
+        let port: PortMapping = "127.0.0.1:80".parse().unwrap();
+        assert_eq!(port.to_string(), "127.0.0.1:80");
         
-        Ok(self.id.clone())
+        let port: PortMapping = "[::1]:443".parse().unwrap();
+        assert_eq!(port.host, "[::1]:443".parse::<SocketAddr>().unwrap());
+        
+        for spec in ["", "http", "70000", "localhost:80", "1.2.3.4:"] {
+            assert!(spec.parse::<PortMapping>().is_err(), "{} should be rejected", spec);
+        }
     }
     
-    pub async fn start_and_wait(&mut self, runtime_manager: &crate::runtime::RuntimeManager) -> Result<i32> {
-        self.state = ContainerState::Running;
-        self.started_at = Some(chrono::Utc::now());
+    #[test]
+    fn restart_policies_parse_and_display() {
+        let cases = [
+            ("no", RestartPolicy::No),
+            ("always", RestartPolicy::Always),
+            ("on-failure", RestartPolicy::OnFailure { max_retries: None }),
+            ("on-failure:3", RestartPolicy::OnFailure { max_retries: Some(3) }),
+        ];
+        for (spec, policy) in cases {
+            assert_eq!(spec.parse::<RestartPolicy>().unwrap(), policy);
+            assert_eq!(policy.to_string(), spec);
+        }
         
-        // TODO: Actually execute the WASM module and wait for completion
-        // Return exit code
+        for spec in ["", "never", "always:3", "on-failure:", "on-failure:-1", "on-failure:many"] {
+            assert!(spec.parse::<RestartPolicy>().is_err(), "{} should be rejected", spec);
+        }
+    }
+    
+    #[test]
+    fn on_failure_stops_after_max_retries() {
+        let policy = RestartPolicy::OnFailure { max_retries: Some(2) };
+        assert!(policy.should_restart(true, 0));
+        assert!(policy.should_restart(true, 1));
+        assert!(!policy.should_restart(true, 2));
+        assert!(!policy.should_restart(false, 0));
         
-        self.state = ContainerState::Stopped;
-        self.finished_at = Some(chrono::Utc::now());
+        let unlimited = RestartPolicy::OnFailure { max_retries: None };
+        assert!(unlimited.should_restart(true, u32::MAX));
         
-        Ok(0)
+        assert!(RestartPolicy::Always.should_restart(false, 100));
+        assert!(!RestartPolicy::No.should_restart(true, 0));
+    }
+    
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(RestartPolicy::backoff(0), RESTART_BACKOFF_INITIAL);
+        assert_eq!(RestartPolicy::backoff(1), RESTART_BACKOFF_INITIAL * 2);
+        assert_eq!(RestartPolicy::backoff(3), RESTART_BACKOFF_INITIAL * 8);
+        assert_eq!(RestartPolicy::backoff(10), RESTART_BACKOFF_MAX);
+        assert_eq!(RestartPolicy::backoff(u32::MAX), RESTART_BACKOFF_MAX);
     }
 }
@@ -0,0 +1,48 @@
+
+use thiserror::Error;
+
+/// Result type used throughout WasmRunner
+pub type Result<T> = anyhow::Result<T>;
+
+/// Errors raised by WasmRunner components
+#[derive(Debug, Error)]
+pub enum WasmRunnerError {
+    #[error("Image not found: {0}")]
+    ImageNotFound(String),
+    
+    #[error("Invalid image reference {reference}: {reason}")]
+    InvalidReference { reference: String, reason: String },
+    
+    #[error("Image {image} does not have digest sha256:{digest}")]
+    DigestMismatch { image: String, digest: String },
+    
+    #[error("Invalid compose file {path}: {message}")]
+    InvalidCompose { path: String, message: String },
+    
+    #[error("Export not found: {0}")]
+    ExportNotFound(String),
+    
+    #[error("Invalid argument for {export}: {message}")]
+    InvalidArgument { export: String, message: String },
+    
+    #[error("WASM trap: {0}")]
+    Trap(String),
+    
+    #[error("Memory limit of {limit_bytes} bytes exceeded")]
+    OutOfMemory { limit_bytes: u64 },
+    
+    #[error("Guest did not finish within {timeout_ms} ms")]
+    TimedOut { timeout_ms: u64 },
+    
+    #[error("No public key for publisher {0}")]
+    MissingKey(String),
+    
+    #[error("Signature verification failed for {0}")]
+    BadSignature(String),
+    
+    #[error("Image {0} is not signed")]
+    UnsignedImage(String),
+    
+    #[error("Image {image} is signed by untrusted publisher {publisher}")]
+    UntrustedImage { image: String, publisher: String },
+}
@@ -0,0 +1,108 @@
+
+//! Container lifecycle events
+
+use crate::container::Container;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Events buffered per subscriber before emitters wait for it to catch up
+pub const DEFAULT_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    pub container_id: String,
+    pub container_name: String,
+    pub image: String,
+    #[serde(flatten)]
+    pub kind: EventKind,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    Created,
+    Started,
+    Exited { exit_code: i32 },
+    Trapped { message: String },
+    OomKilled,
+    Restarting { restart_count: u32 },
+    Unhealthy,
+    Healthy,
+    Removed,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::Started => "started",
+            EventKind::Exited { .. } => "exited",
+            EventKind::Trapped { .. } => "trapped",
+            EventKind::OomKilled => "oom_killed",
+            EventKind::Restarting { .. } => "restarting",
+            EventKind::Unhealthy => "unhealthy",
+            EventKind::Healthy => "healthy",
+            EventKind::Removed => "removed",
+        }
+    }
+}
+
+impl LifecycleEvent {
+    pub fn new(container: &Container, kind: EventKind) -> Self {
+        Self {
+            container_id: container.id.clone(),
+            container_name: container.config.name.clone(),
+            image: container.config.image.clone(),
+            kind,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Fan-out of lifecycle events to subscribers
+///
+/// Each subscriber has a bounded queue. Emitting waits while any queue is
+/// full, so a slow subscriber applies back-pressure instead of losing events.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<mpsc::Sender<LifecycleEvent>>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn subscribe(&self, capacity: usize) -> mpsc::Receiver<LifecycleEvent> {
+        let (tx, rx) = mpsc::channel(capacity);
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+    
+    pub async fn emit(&self, event: LifecycleEvent) {
+        let subscribers = self.subscribers.lock().unwrap().clone();
+        
+        for subscriber in subscribers {
+            // A send error only means the subscriber has gone away
+            let _ = subscriber.send(event.clone()).await;
+        }
+        
+        self.subscribers.lock().unwrap().retain(|s| !s.is_closed());
+    }
+    
+    /// Stop accepting events; subscribers drain what is queued and then finish
+    pub fn close(&self) {
+        self.subscribers.lock().unwrap().clear();
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.subscribers.lock().unwrap().len())
+            .finish()
+    }
+}
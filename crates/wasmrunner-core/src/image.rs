@@ -0,0 +1,374 @@
+
+use crate::manifest::AppManifest;
+use crate::reference::ImageReference;
+use crate::signing::sha256_hex;
+use crate::{Result, WasmRunnerError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File name of the WASM module inside an image directory
+pub const WASM_FILE: &str = "app.wasm";
+
+/// File name of the manifest inside an image directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// File name of the install record inside an image directory
+pub const ORIGIN_FILE: &str = "origin.json";
+
+/// Directory holding a repository's tags, apart from its nested repositories
+///
+/// Name components start with a letter or digit, so it never collides with one.
+pub const TAGS_DIR: &str = "_tags";
+
+/// The app store release an image was installed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageOrigin {
+    pub store_url: String,
+    pub app: String,
+    pub version: String,
+    
+    /// Deprecation notice for the release at install time
+    #[serde(default)]
+    pub deprecation: Option<String>,
+}
+
+/// A WASM image stored on the local machine
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub name: String,
+    pub tag: String,
+    pub path: PathBuf,
+}
+
+impl Image {
+    pub fn wasm_path(&self) -> PathBuf {
+        self.path.join(WASM_FILE)
+    }
+    
+    pub fn manifest_path(&self) -> PathBuf {
+        self.path.join(MANIFEST_FILE)
+    }
+    
+    pub fn read_wasm(&self) -> Result<Vec<u8>> {
+        Ok(std::fs::read(self.wasm_path())?)
+    }
+    
+    /// Hex sha256 of the module, as used in `name@sha256:<digest>` references
+    pub fn digest(&self) -> Result<String> {
+        Ok(sha256_hex(&self.read_wasm()?))
+    }
+    
+    /// Read the image manifest, if the image has one
+    pub fn manifest(&self) -> Result<Option<AppManifest>> {
+        let path = self.manifest_path();
+        if !path.is_file() {
+            return Ok(None);
+        }
+        
+        AppManifest::load(&path).map(Some)
+    }
+    
+    /// Where the image was installed from, if it came from the app store
+    pub fn origin(&self) -> Result<Option<ImageOrigin>> {
+        let path = self.path.join(ORIGIN_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        
+        Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+    }
+    
+    pub fn save_origin(&self, origin: &ImageOrigin) -> Result<()> {
+        std::fs::write(self.path.join(ORIGIN_FILE), serde_json::to_string_pretty(origin)?)?;
+        Ok(())
+    }
+}
+
+/// Local image store laid out as `<root>/<name>/_tags/<tag>/app.wasm`
+///
+/// Keeping tags under [`TAGS_DIR`] means `team:app` and the repository
+/// `team/app` live in different directories.
+pub struct ImageStore {
+    root: PathBuf,
+}
+
+impl ImageStore {
+    pub fn new() -> Result<Self> {
+        let home_dir = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."));
+        
+        Self::with_root(home_dir.join(".wasmrunner").join("images"))
+    }
+    
+    pub fn with_root(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root)?;
+        
+        let store = Self { root };
+        store.move_legacy_images()?;
+        Ok(store)
+    }
+    
+    /// Move images stored as `<root>/<name>/<tag>`, before namespaces existed, under [`TAGS_DIR`]
+    fn move_legacy_images(&self) -> Result<()> {
+        for repository in std::fs::read_dir(&self.root)? {
+            let repository = repository?;
+            if !repository.file_type()?.is_dir() || repository.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            
+            for tag in std::fs::read_dir(repository.path())? {
+                let tag = tag?;
+                if tag.file_name() == TAGS_DIR || !tag.path().join(WASM_FILE).is_file() {
+                    continue;
+                }
+                
+                let tags = repository.path().join(TAGS_DIR);
+                std::fs::create_dir_all(&tags)?;
+                std::fs::rename(tag.path(), tags.join(tag.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+    
+    fn image_dir(&self, name: &str, tag: &str) -> PathBuf {
+        self.root.join(name).join(TAGS_DIR).join(tag)
+    }
+    
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    
+    /// Look up an image by reference, defaulting the tag to `latest`
+    ///
+    /// See [`ImageStore::resolve_reference`] for how registries and digests are handled.
+    pub fn resolve(&self, image: &str) -> Result<Image> {
+        self.resolve_reference(&ImageReference::parse(image)?)
+    }
+    
+    /// Look up a parsed reference
+    ///
+    /// The registry is ignored since local images are stored by repository
+    /// alone. A digest-pinned reference must match the module byte for byte:
+    /// with a tag, that tag's module must have the digest, and without one any
+    /// tag of the repository with that module is used.
+    pub fn resolve_reference(&self, reference: &ImageReference) -> Result<Image> {
+        let repository = reference.repository();
+        let digest = match &reference.digest {
+            Some(digest) => digest,
+            None => return self.get(&repository, reference.tag()),
+        };
+        
+        if let Some(tag) = &reference.tag {
+            let image = self.get(&repository, tag)?;
+            if image.digest()? != *digest {
+                return Err(WasmRunnerError::DigestMismatch {
+                    image: format!("{}:{}", repository, tag),
+                    digest: digest.clone(),
+                }
+                .into());
+            }
+            return Ok(image);
+        }
+        
+        for image in self.list()?.into_iter().filter(|image| image.name == repository) {
+            if image.digest()? == *digest {
+                return Ok(image);
+            }
+        }
+        Err(WasmRunnerError::ImageNotFound(reference.to_string()).into())
+    }
+    
+    /// Look up `name:tag` exactly
+    pub fn get(&self, name: &str, tag: &str) -> Result<Image> {
+        let path = self.image_dir(name, tag);
+        
+        if !path.join(WASM_FILE).is_file() {
+            return Err(WasmRunnerError::ImageNotFound(format!("{}:{}", name, tag)).into());
+        }
+        
+        Ok(Image {
+            name: name.to_string(),
+            tag: tag.to_string(),
+            path,
+        })
+    }
+    
+    /// Every image in the store, sorted by name and tag
+    pub fn list(&self) -> Result<Vec<Image>> {
+        let mut images = Vec::new();
+        self.collect(&self.root, &mut images)?;
+        
+        images.sort_by(|a, b| (&a.name, &a.tag).cmp(&(&b.name, &b.tag)));
+        Ok(images)
+    }
+    
+    /// Find images below `dir`; namespaced names nest one directory per segment
+    fn collect(&self, dir: &Path, images: &mut Vec<Image>) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            // Dot entries are staging directories and other bookkeeping
+            if file_name.starts_with('.') || !entry.file_type()?.is_dir() {
+                continue;
+            }
+            
+            if file_name != TAGS_DIR {
+                self.collect(&entry.path(), images)?;
+                continue;
+            }
+            
+            let name = dir
+                .strip_prefix(&self.root)
+                .unwrap_or(dir)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            for tag in std::fs::read_dir(entry.path())? {
+                let tag = tag?;
+                let tag_name = tag.file_name().to_string_lossy().into_owned();
+                if tag_name.starts_with('.') || !tag.path().join(WASM_FILE).is_file() {
+                    continue;
+                }
+                
+                images.push(Image {
+                    name: name.clone(),
+                    tag: tag_name,
+                    path: tag.path(),
+                });
+            }
+        }
+        Ok(())
+    }
+    
+    /// Store an image as `name:tag`, replacing any existing image with that reference
+    pub fn import(&self, name: &str, tag: &str, wasm: &[u8], manifest: Option<&[u8]>) -> Result<Image> {
+        check_path_segments(name, tag)?;
+        let path = self.image_dir(name, tag);
+        
+        // Write into a staging directory so a failed import never leaves a partial image
+        let staging = self.root.join(name).join(TAGS_DIR).join(format!(".{}.partial", tag));
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        std::fs::create_dir_all(&staging)?;
+        std::fs::write(staging.join(WASM_FILE), wasm)?;
+        if let Some(manifest) = manifest {
+            std::fs::write(staging.join(MANIFEST_FILE), manifest)?;
+        }
+        
+        if path.exists() {
+            std::fs::remove_dir_all(&path)?;
+        }
+        std::fs::rename(&staging, &path)?;
+        
+        Ok(Image {
+            name: name.to_string(),
+            tag: tag.to_string(),
+            path,
+        })
+    }
+    
+    /// Load the WASM bytes for an image, accepting a path to a `.wasm` file as well
+    ///
+    /// The bytes of a digest-pinned reference are guaranteed to have that digest.
+    pub fn load_wasm(&self, image: &str) -> Result<Vec<u8>> {
+        if is_wasm_file(image) {
+            return Ok(std::fs::read(image)?);
+        }
+        
+        let reference = ImageReference::parse(image)?;
+        let wasm = self.resolve_reference(&reference)?.read_wasm()?;
+        
+        // Check again what was read, in case the file changed after it was resolved
+        if let Some(digest) = &reference.digest {
+            if sha256_hex(&wasm) != *digest {
+                return Err(WasmRunnerError::DigestMismatch {
+                    image: image.to_string(),
+                    digest: digest.clone(),
+                }
+                .into());
+            }
+        }
+        Ok(wasm)
+    }
+    
+    /// Component plugins an image's manifest imports; bare `.wasm` files import none
+    pub fn plugin_imports(&self, image: &str) -> Result<Vec<String>> {
+        if is_wasm_file(image) {
+            return Ok(Vec::new());
+        }
+        
+        Ok(self.resolve(image)?.manifest()?.map(|m| m.plugins).unwrap_or_default())
+    }
+}
+
+/// Refuse names and tags that would place an image outside its own directory
+///
+/// Each `/`-separated segment of the name, and the tag as a whole, must be
+/// non-empty and must not start with `.`, which also rules out `..`.
+fn check_path_segments(name: &str, tag: &str) -> Result<()> {
+    let invalid = |reason: &str| WasmRunnerError::InvalidReference {
+        reference: format!("{}:{}", name, tag),
+        reason: reason.to_string(),
+    };
+    
+    if tag.contains(['/', '\\']) {
+        return Err(invalid("tag must not contain path separators").into());
+    }
+    for segment in name.split('/').chain(std::iter::once(tag)) {
+        if segment.is_empty() || segment.starts_with('.') || segment.contains('\\') || segment == TAGS_DIR {
+            return Err(invalid("names and tags must not be empty, start with '.', contain '\\' or be _tags").into());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn is_wasm_file(image: &str) -> bool {
+    let path = Path::new(image);
+    path.extension().is_some_and(|ext| ext == "wasm") && path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+    
+    fn store() -> ImageStore {
+        ImageStore::with_root(std::env::temp_dir().join(format!("wasmrunner-images-{}", uuid::Uuid::new_v4()))).unwrap()
+    }
+    
+    #[test]
+    fn tag_and_namespaced_repository_do_not_collide() {
+        let store = store();
+        store.import("team/app", "1.0", WASM, None).unwrap();
+        store.import("team", "app", WASM, None).unwrap();
+        
+        let images = store.list().unwrap();
+        let references: Vec<_> = images.iter().map(|image| format!("{}:{}", image.name, image.tag)).collect();
+        assert_eq!(references, ["team:app", "team/app:1.0"]);
+    }
+    
+    #[test]
+    fn legacy_layout_is_moved_under_tags() {
+        let store = store();
+        let legacy = store.root().join("hello").join("1.0");
+        std::fs::create_dir_all(&legacy).unwrap();
+        std::fs::write(legacy.join(WASM_FILE), WASM).unwrap();
+        
+        let store = ImageStore::with_root(store.root()).unwrap();
+        assert_eq!(store.get("hello", "1.0").unwrap().path, store.root().join("hello").join(TAGS_DIR).join("1.0"));
+    }
+    
+    #[test]
+    fn import_rejects_paths_outside_the_store() {
+        let store = store();
+        for (name, tag) in [("../x", "1.0"), ("app", "../../x"), ("app", "a/b"), (".hidden", "1.0"), ("app", "")] {
+            assert!(store.import(name, tag, WASM, None).is_err(), "{}:{} should be rejected", name, tag);
+        }
+    }
+}
@@ -1,14 +1,21 @@
 
 //! Core types and functionality for WasmRunner
 
+pub mod compose;
 pub mod config;
 pub mod container;
 pub mod image;
+pub mod reference;
 pub mod registry;
 pub mod manifest;
+pub mod metrics;
 pub mod error;
+pub mod events;
+pub mod signing;
+pub mod trust;
 
 pub use error::{WasmRunnerError, Result};
+pub use reference::ImageReference;
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
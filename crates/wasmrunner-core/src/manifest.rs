@@ -0,0 +1,35 @@
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Application manifest stored alongside an image's WASM module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppManifest {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    
+    #[serde(default = "default_runtime")]
+    pub runtime: String,
+    
+    /// Memory limit in MB
+    pub memory_limit: Option<u64>,
+    
+    /// CPU limit as percentage
+    pub cpu_limit: Option<u32>,
+    
+    /// Component plugins linked into the application's instance
+    #[serde(default)]
+    pub plugins: Vec<String>,
+}
+
+fn default_runtime() -> String {
+    "wasmtime".to_string()
+}
+
+impl AppManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
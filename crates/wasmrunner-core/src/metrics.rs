@@ -0,0 +1,120 @@
+//! Runner metrics shared by every wasmrunner process on a machine
+//!
+//! Containers run in short-lived CLI and shim processes, so counters are
+//! kept in a file that each process updates under a lock and
+//! `wasmrunner metrics` reads when scraped.
+
+use crate::registry::lock_exclusive;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use tracing::warn;
+
+/// File the counters are kept in, under `~/.wasmrunner`
+pub const METRICS_FILE: &str = "metrics.json";
+
+/// Upper bounds, in seconds, of the start latency histogram buckets
+pub const START_LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Totals since the metrics file was created
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Counters {
+    pub containers_started: u64,
+    pub restarts: u64,
+    pub traps: u64,
+    
+    /// Guests killed for exceeding their memory limit
+    pub oom_kills: u64,
+    
+    pub compile_cache_hits: u64,
+    pub compile_cache_misses: u64,
+    
+    /// Starts that took at most each of [`START_LATENCY_BUCKETS`], not cumulative
+    pub start_latency_buckets: Vec<u64>,
+    pub start_latency_sum_secs: f64,
+    pub start_latency_count: u64,
+}
+
+impl Counters {
+    /// Record how long a guest took from being started to running
+    pub fn observe_start_latency(&mut self, secs: f64) {
+        self.start_latency_buckets.resize(START_LATENCY_BUCKETS.len() + 1, 0);
+        let bucket = START_LATENCY_BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(START_LATENCY_BUCKETS.len());
+        self.start_latency_buckets[bucket] += 1;
+        self.start_latency_sum_secs += secs;
+        self.start_latency_count += 1;
+    }
+}
+
+pub struct MetricsStore {
+    path: PathBuf,
+}
+
+impl MetricsStore {
+    pub fn new() -> Result<Self> {
+        let home_dir = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."));
+        
+        let dir = home_dir.join(".wasmrunner");
+        std::fs::create_dir_all(&dir)?;
+        
+        Ok(Self { path: dir.join(METRICS_FILE) })
+    }
+    
+    pub fn load(&self) -> Result<Counters> {
+        let mut file = self.open_locked()?;
+        read_counters(&mut file)
+    }
+    
+    /// Apply `update` to the counters, holding the lock throughout
+    pub fn update(&self, update: impl FnOnce(&mut Counters)) -> Result<()> {
+        let mut file = self.open_locked()?;
+        let mut counters = read_counters(&mut file)?;
+        update(&mut counters);
+        
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(serde_json::to_string(&counters)?.as_bytes())?;
+        Ok(())
+    }
+    
+    /// The metrics file, locked until the handle is dropped
+    fn open_locked(&self) -> Result<File> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(&self.path)?;
+        
+        lock_exclusive(&file)?;
+        Ok(file)
+    }
+}
+
+fn read_counters(file: &mut File) -> Result<Counters> {
+    let mut data = String::new();
+    file.read_to_string(&mut data)?;
+    
+    if data.trim().is_empty() {
+        return Ok(Counters::default());
+    }
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Update the shared counters; failures are logged, never passed on to the container
+///
+/// Waiting for the file lock blocks, so the update runs off the async workers.
+pub async fn record(update: impl FnOnce(&mut Counters) + Send + 'static) {
+    let recorded = tokio::task::spawn_blocking(move || MetricsStore::new()?.update(update)).await;
+    if let Err(e) = recorded.map_err(Into::into).and_then(|result| result) {
+        warn!("Failed to record metrics: {}", e);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{Result, WasmRunnerError};
+use std::fmt;
+
+/// Tag used when a reference names no tag
+pub const DEFAULT_TAG: &str = "latest";
+
+/// Longest name component or tag accepted in a reference
+const MAX_COMPONENT_LEN: usize = 128;
+
+/// What a tag may look like, for error messages
+const TAG_RULE: &str = "a letter or digit followed by letters, digits, '.', '_' or '-'";
+
+/// What a name component may look like, for error messages; names are lowercase
+/// so that references differing only in case cannot name different images
+const NAME_RULE: &str = "a lowercase letter or digit followed by lowercase letters, digits, '.', '_' or '-'";
+
+/// A parsed image reference: `[registry/][namespace/]name[:tag][@sha256:digest]`
+///
+/// The first path segment names a registry when it contains `.` or `:` or is
+/// `localhost`, so `registry.example.com:5000/app:1.0` is `app:1.0` on that
+/// registry while `team/app` is `app` in namespace `team`. Local images are
+/// stored under [`ImageReference::repository`]; the registry only says where
+/// `push` and `pull` go.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImageReference {
+    pub registry: Option<String>,
+    pub namespace: Option<String>,
+    pub name: String,
+    
+    /// Tag given in the reference; see [`ImageReference::tag`] for the default
+    pub tag: Option<String>,
+    
+    /// Lowercase hex sha256 of the module, from `@sha256:<digest>`
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    pub fn parse(reference: &str) -> Result<Self> {
+        let invalid = |reason: &str| WasmRunnerError::InvalidReference {
+            reference: reference.to_string(),
+            reason: reason.to_string(),
+        };
+        
+        let (rest, digest) = match reference.split_once('@') {
+            Some((rest, digest)) => {
+                let digest = digest
+                    .strip_prefix("sha256:")
+                    .filter(|d| d.len() == 64 && d.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .ok_or_else(|| invalid("digest must be sha256: followed by 64 hex digits"))?;
+                (rest, Some(digest.to_ascii_lowercase()))
+            }
+            None => (reference, None),
+        };
+        
+        let mut segments: Vec<&str> = rest.split('/').collect();
+        let registry = match segments.first() {
+            Some(first) if segments.len() > 1 && is_registry(first) => {
+                if !valid_registry(first) {
+                    return Err(invalid("registry must be a host name with an optional port").into());
+                }
+                Some(segments.remove(0).to_string())
+            }
+            _ => None,
+        };
+        
+        // A `:` in the last segment separates the tag; ports only appear in the registry
+        let last = segments.pop().unwrap_or_default();
+        let (name, tag) = match last.split_once(':') {
+            Some((name, tag)) => (name, Some(tag)),
+            None => (last, None),
+        };
+        
+        if !tag.is_none_or(valid_tag) {
+            return Err(invalid(&format!("tag must be {}", TAG_RULE)).into());
+        }
+        if !segments.iter().chain([&name]).all(|segment| valid_name(segment)) {
+            return Err(invalid(&format!("each name must be {}", NAME_RULE)).into());
+        }
+        
+        Ok(Self {
+            registry,
+            namespace: (!segments.is_empty()).then(|| segments.join("/")),
+            name: name.to_string(),
+            tag: tag.map(str::to_string),
+            digest,
+        })
+    }
+    
+    /// The tag to use, defaulting to `latest`
+    pub fn tag(&self) -> &str {
+        self.tag.as_deref().unwrap_or(DEFAULT_TAG)
+    }
+    
+    /// `namespace/name`, the name an image is stored and served under
+    pub fn repository(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}/{}", namespace, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ImageReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(registry) = &self.registry {
+            write!(f, "{}/", registry)?;
+        }
+        write!(f, "{}", self.repository())?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{}", tag)?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@sha256:{}", digest)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_registry(segment: &str) -> bool {
+    segment.contains(['.', ':']) || segment == "localhost"
+}
+
+fn valid_registry(segment: &str) -> bool {
+    let (host, port) = match segment.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (segment, None),
+    };
+    
+    !host.is_empty()
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+        && port.is_none_or(|port| port.parse::<u16>().is_ok())
+}
+
+fn valid_tag(tag: &str) -> bool {
+    let mut chars = tag.chars();
+    tag.len() <= MAX_COMPONENT_LEN
+        && chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn valid_name(component: &str) -> bool {
+    valid_tag(component) && !component.chars().any(|c| c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const DIGEST: &str = "a3f1c2d4e5b6a7980112233445566778899aabbccddeeff00112233445566778";
+    
+    #[test]
+    fn host_and_port_name_a_registry() {
+        let reference = ImageReference::parse("registry.example.com:5000/app:1.0").unwrap();
+        
+        assert_eq!(reference.registry.as_deref(), Some("registry.example.com:5000"));
+        assert_eq!(reference.namespace, None);
+        assert_eq!(reference.name, "app");
+        assert_eq!(reference.tag(), "1.0");
+        assert_eq!(reference.to_string(), "registry.example.com:5000/app:1.0");
+    }
+    
+    #[test]
+    fn localhost_names_a_registry() {
+        let reference = ImageReference::parse("localhost/app").unwrap();
+        
+        assert_eq!(reference.registry.as_deref(), Some("localhost"));
+        assert_eq!(reference.name, "app");
+        assert_eq!(reference.tag(), DEFAULT_TAG);
+    }
+    
+    #[test]
+    fn plain_first_segment_is_a_namespace() {
+        let reference = ImageReference::parse("team/tools/app:2").unwrap();
+        
+        assert_eq!(reference.registry, None);
+        assert_eq!(reference.namespace.as_deref(), Some("team/tools"));
+        assert_eq!(reference.repository(), "team/tools/app");
+        assert_eq!(reference.tag.as_deref(), Some("2"));
+    }
+    
+    #[test]
+    fn tag_and_digest_are_both_kept() {
+        let reference = ImageReference::parse(&format!("app:1.0@sha256:{}", DIGEST.to_uppercase())).unwrap();
+        
+        assert_eq!(reference.tag.as_deref(), Some("1.0"));
+        assert_eq!(reference.digest.as_deref(), Some(DIGEST));
+        assert_eq!(reference.to_string(), format!("app:1.0@sha256:{}", DIGEST));
+    }
+    
+    #[test]
+    fn digest_without_tag_leaves_tag_unset() {
+        let reference = ImageReference::parse(&format!("team/app@sha256:{}", DIGEST)).unwrap();
+        
+        assert_eq!(reference.tag, None);
+        assert_eq!(reference.digest.as_deref(), Some(DIGEST));
+    }
+    
+    #[test]
+    fn digest_must_be_64_hex_digits() {
+        assert!(ImageReference::parse(&format!("app@sha256:{}", &DIGEST[..63])).is_err());
+        assert!(ImageReference::parse(&format!("app@sha256:{}0", DIGEST)).is_err());
+        assert!(ImageReference::parse(&format!("app@sha256:{}g", &DIGEST[..63])).is_err());
+        assert!(ImageReference::parse(&format!("app@md5:{}", DIGEST)).is_err());
+    }
+    
+    #[test]
+    fn uppercase_names_are_rejected_but_tags_may_use_it() {
+        assert!(ImageReference::parse("App:1.0").is_err());
+        assert!(ImageReference::parse("Team/app").is_err());
+        assert_eq!(ImageReference::parse("app:RC1").unwrap().tag(), "RC1");
+    }
+    
+    #[test]
+    fn empty_components_are_rejected() {
+        for reference in ["", "app:", ":1.0", "team//app", "/app", "team/", "registry.example.com/", "app@"] {
+            assert!(ImageReference::parse(reference).is_err(), "{:?} should be rejected", reference);
+        }
+    }
+}
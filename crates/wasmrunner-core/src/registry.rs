@@ -0,0 +1,210 @@
+
+use crate::container::{Container, ResourceUsage};
+use crate::Result;
+use std::fs::{File, OpenOptions};
+use std::path::PathBuf;
+
+/// File holding a container's serialized state
+pub const CONTAINER_FILE: &str = "container.json";
+
+/// Unix socket detached containers accept `attach` connections on
+pub const ATTACH_SOCKET: &str = "attach.sock";
+
+/// File detached containers write their output to
+pub const LOG_FILE: &str = "container.log";
+
+/// File a running container's resource usage is sampled into
+pub const USAGE_FILE: &str = "usage.json";
+
+/// File locked while a container's state is written
+pub const LOCK_FILE: &str = "container.lock";
+
+/// Row shown by `wasmrunner list`
+#[derive(Debug, Clone)]
+pub struct ContainerSummary {
+    pub id: String,
+    pub image: String,
+    pub command: String,
+    pub created: String,
+    pub status: String,
+    pub name: String,
+}
+
+/// Exclusive hold on a container's saved state, released when dropped
+pub struct ContainerLock {
+    _file: File,
+}
+
+/// Local container state laid out as `<root>/<id>/container.json`
+pub struct LocalRegistry {
+    root: PathBuf,
+}
+
+impl LocalRegistry {
+    pub fn new() -> Result<Self> {
+        let home_dir = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."));
+        
+        let root = home_dir.join(".wasmrunner").join("containers");
+        std::fs::create_dir_all(&root)?;
+        
+        Ok(Self { root })
+    }
+    
+    pub fn container_dir(&self, id: &str) -> PathBuf {
+        self.root.join(id)
+    }
+    
+    pub async fn save_container(&self, container: &Container) -> Result<()> {
+        tokio::fs::create_dir_all(self.container_dir(&container.id)).await?;
+        let lock = self.lock_container(&container.id).await?;
+        self.save_locked(container, &lock).await
+    }
+    
+    /// Save a container whose lock is already held, as after a read-check-write
+    pub async fn save_locked(&self, container: &Container, _lock: &ContainerLock) -> Result<()> {
+        tokio::fs::write(
+            self.container_dir(&container.id).join(CONTAINER_FILE),
+            serde_json::to_string_pretty(container)?
+        ).await?;
+        
+        Ok(())
+    }
+    
+    /// Wait until no other process is writing the container's state
+    ///
+    /// Hold the lock from reading the state to saving a change derived from
+    /// it, so another writer's transition in between is not overwritten.
+    pub async fn lock_container(&self, id: &str) -> Result<ContainerLock> {
+        let path = self.container_dir(id).join(LOCK_FILE);
+        
+        // flock blocks, so wait for it off the async workers
+        tokio::task::spawn_blocking(move || {
+            let file = OpenOptions::new().write(true).create(true).truncate(false).open(&path)?;
+            lock_exclusive(&file)?;
+            Ok(ContainerLock { _file: file })
+        })
+        .await?
+    }
+    
+    /// Load a container by its full ID
+    pub async fn load_container(&self, id: &str) -> Result<Container> {
+        let data = tokio::fs::read_to_string(self.container_dir(id).join(CONTAINER_FILE)).await?;
+        Ok(serde_json::from_str(&data)?)
+    }
+    
+    /// Record a sample of a running container's resource usage
+    pub async fn save_usage(&self, id: &str, usage: &ResourceUsage) -> Result<()> {
+        // Written aside and renamed so `top` never reads half a sample
+        let dir = self.container_dir(id);
+        let partial = dir.join(format!("{}.tmp", USAGE_FILE));
+        tokio::fs::write(&partial, serde_json::to_string(usage)?).await?;
+        tokio::fs::rename(&partial, dir.join(USAGE_FILE)).await?;
+        
+        Ok(())
+    }
+    
+    /// The last usage sample of a container, if its guest has run
+    pub async fn load_usage(&self, id: &str) -> Result<Option<ResourceUsage>> {
+        match tokio::fs::read_to_string(self.container_dir(id).join(USAGE_FILE)).await {
+            Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    pub async fn remove_container(&self, id: &str) -> Result<()> {
+        tokio::fs::remove_dir_all(self.container_dir(id)).await?;
+        Ok(())
+    }
+    
+    /// Find a container by full ID, ID prefix or name
+    pub async fn find_container(&self, id_or_name: &str) -> Result<Container> {
+        let containers = self.load_all().await?;
+        
+        containers
+            .into_iter()
+            .find(|c| c.id.starts_with(id_or_name) || c.config.name == id_or_name)
+            .ok_or_else(|| anyhow::anyhow!("Container not found: {}", id_or_name))
+    }
+    
+    /// Containers whose label `key` is `value`, oldest first
+    pub async fn containers_labelled(&self, key: &str, value: &str) -> Result<Vec<Container>> {
+        let mut containers: Vec<_> = self
+            .load_all()
+            .await?
+            .into_iter()
+            .filter(|c| c.config.labels.get(key).map(String::as_str) == Some(value))
+            .collect();
+        containers.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(containers)
+    }
+    
+    /// Every container, in no particular order
+    pub async fn containers(&self) -> Result<Vec<Container>> {
+        self.load_all().await
+    }
+    
+    /// Containers whose guest is running or about to run again, oldest first
+    pub async fn running_containers(&self) -> Result<Vec<Container>> {
+        let mut containers: Vec<Container> = self
+            .load_all()
+            .await?
+            .into_iter()
+            .filter(Container::is_running)
+            .collect();
+        containers.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(containers)
+    }
+    
+    pub async fn list_containers(&self, all: bool) -> Result<Vec<ContainerSummary>> {
+        let mut containers = self.load_all().await?;
+        containers.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        
+        Ok(containers
+            .into_iter()
+            .filter(|c| all || c.is_running())
+            .map(|c| ContainerSummary {
+                command: c.config.args.join(" "),
+                created: c.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                status: c.status(),
+                image: c.config.image,
+                name: c.config.name,
+                id: c.id,
+            })
+            .collect())
+    }
+    
+    async fn load_all(&self) -> Result<Vec<Container>> {
+        let mut containers = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.root).await?;
+        
+        while let Some(entry) = entries.next_entry().await? {
+            let file = entry.path().join(CONTAINER_FILE);
+            if let Ok(data) = tokio::fs::read_to_string(&file).await {
+                containers.push(serde_json::from_str(&data)?);
+            }
+        }
+        
+        Ok(containers)
+    }
+}
+
+/// Block until no other process holds a lock on `file`; released when it is closed
+#[cfg(unix)]
+pub(crate) fn lock_exclusive(file: &File) -> Result<()> {
+    use std::os::unix::io::AsRawFd;
+    
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Without flock, writers from different processes are not serialized
+#[cfg(not(unix))]
+pub(crate) fn lock_exclusive(_file: &File) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,371 @@
+
+use crate::image::{is_wasm_file, Image, ImageStore};
+use crate::trust::{check_publisher, TrustStore};
+use crate::{Result, WasmRunnerError};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// File name of the signature inside an image directory
+pub const SIGNATURE_FILE: &str = "signature.json";
+
+/// Detached ed25519 signature over an image's WASM module and manifest
+///
+/// The signature covers digests rather than names, so an image keeps its
+/// signature when it is retagged or installed under another reference.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageSignature {
+    pub publisher: String,
+    pub wasm_sha256: String,
+    pub manifest_sha256: Option<String>,
+    pub signature: String,
+}
+
+impl ImageSignature {
+    pub fn sign(publisher: &str, key: &SigningKey, wasm: &[u8], manifest: Option<&[u8]>) -> Self {
+        let wasm_sha256 = sha256_hex(wasm);
+        let manifest_sha256 = manifest.map(sha256_hex);
+        let signature = key.sign(&payload(&wasm_sha256, manifest_sha256.as_deref()));
+        
+        Self {
+            publisher: publisher.to_string(),
+            wasm_sha256,
+            manifest_sha256,
+            signature: hex::encode(signature.to_bytes()),
+        }
+    }
+    
+    /// Check the digests against the artifacts, then the signature against the publisher's key
+    pub fn verify(&self, trust: &TrustStore, wasm: &[u8], manifest: Option<&[u8]>) -> Result<()> {
+        self.check_digests(wasm, manifest)?;
+        trust.verify(
+            &self.publisher,
+            &payload(&self.wasm_sha256, self.manifest_sha256.as_deref()),
+            &self.signature,
+        )
+    }
+    
+    /// Check that the artifacts are the ones whose digests were signed
+    pub fn check_digests(&self, wasm: &[u8], manifest: Option<&[u8]>) -> Result<()> {
+        if sha256_hex(wasm) != self.wasm_sha256 || manifest.map(sha256_hex) != self.manifest_sha256 {
+            return Err(WasmRunnerError::BadSignature(format!(
+                "image signed by {}: contents do not match the signed digests",
+                self.publisher
+            )).into());
+        }
+        Ok(())
+    }
+}
+
+fn payload(wasm_sha256: &str, manifest_sha256: Option<&str>) -> Vec<u8> {
+    format!("wasmrunner-image-v1\n{}\n{}\n", wasm_sha256, manifest_sha256.unwrap_or("")).into_bytes()
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// What to do with images that are unsigned or signed by an unknown publisher
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImagePolicy {
+    /// Run them with a warning
+    #[default]
+    Permissive,
+    
+    /// Refuse them
+    Enforce,
+}
+
+/// Outcome of checking an image against the trust store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// Signed by a trusted publisher
+    Trusted(String),
+    
+    /// Signed by a publisher without a trusted key
+    Untrusted(String),
+    
+    Unsigned,
+}
+
+/// Verify an image's signature, applying `policy` to unsigned and untrusted images
+///
+/// A signature that does not match the image is refused under every policy.
+pub fn verify_image(
+    reference: &str,
+    signature: Option<&ImageSignature>,
+    wasm: &[u8],
+    manifest: Option<&[u8]>,
+    trust: &TrustStore,
+    policy: ImagePolicy,
+) -> Result<Verification> {
+    // Mismatched digests mean tampering whoever the publisher is, so they are checked before trust
+    if let Some(signature) = signature {
+        signature.check_digests(wasm, manifest)?;
+    }
+    
+    let verification = match signature {
+        None => Verification::Unsigned,
+        Some(signature) if !trust.is_trusted(&signature.publisher) => {
+            Verification::Untrusted(signature.publisher.clone())
+        }
+        Some(signature) => {
+            signature.verify(trust, wasm, manifest)?;
+            Verification::Trusted(signature.publisher.clone())
+        }
+    };
+    
+    match (&verification, policy) {
+        (Verification::Unsigned, ImagePolicy::Enforce) => {
+            Err(WasmRunnerError::UnsignedImage(reference.to_string()).into())
+        }
+        (Verification::Untrusted(publisher), ImagePolicy::Enforce) => Err(WasmRunnerError::UntrustedImage {
+            image: reference.to_string(),
+            publisher: publisher.clone(),
+        }.into()),
+        (Verification::Unsigned, ImagePolicy::Permissive) => {
+            warn!("Image {} is not signed", reference);
+            Ok(verification)
+        }
+        (Verification::Untrusted(publisher), ImagePolicy::Permissive) => {
+            warn!("Image {} is signed by untrusted publisher {}", reference, publisher);
+            Ok(verification)
+        }
+        (Verification::Trusted(_), _) => Ok(verification),
+    }
+}
+
+impl Image {
+    /// Read the image signature, if the image has one
+    pub fn signature(&self) -> Result<Option<ImageSignature>> {
+        let path = self.path.join(SIGNATURE_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        
+        Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+    }
+    
+    pub fn read_manifest_bytes(&self) -> Result<Option<Vec<u8>>> {
+        let path = self.manifest_path();
+        if !path.is_file() {
+            return Ok(None);
+        }
+        
+        Ok(Some(std::fs::read(path)?))
+    }
+    
+    /// Sign the image's current contents, replacing any previous signature
+    pub fn sign(&self, publisher: &str, key: &SigningKey) -> Result<ImageSignature> {
+        let signature = ImageSignature::sign(
+            publisher,
+            key,
+            &self.read_wasm()?,
+            self.read_manifest_bytes()?.as_deref(),
+        );
+        self.save_signature(&signature)?;
+        Ok(signature)
+    }
+    
+    pub fn save_signature(&self, signature: &ImageSignature) -> Result<()> {
+        std::fs::write(self.path.join(SIGNATURE_FILE), serde_json::to_string_pretty(signature)?)?;
+        Ok(())
+    }
+    
+    /// Verify the image against the trust store under `policy`
+    pub fn verify(&self, trust: &TrustStore, policy: ImagePolicy) -> Result<Verification> {
+        verify_image(
+            &format!("{}:{}", self.name, self.tag),
+            self.signature()?.as_ref(),
+            &self.read_wasm()?,
+            self.read_manifest_bytes()?.as_deref(),
+            trust,
+            policy,
+        )
+    }
+}
+
+impl ImageStore {
+    /// Verify an image reference before running it
+    ///
+    /// Bare `.wasm` files carry no signature and are treated as unsigned.
+    pub fn verify(&self, image: &str, trust: &TrustStore, policy: ImagePolicy) -> Result<Verification> {
+        if is_wasm_file(image) {
+            return verify_image(image, None, &std::fs::read(image)?, None, trust, policy);
+        }
+        
+        self.resolve(image)?.verify(trust, policy)
+    }
+}
+
+/// Signing keys kept on this machine as `<dir>/<publisher>.key`
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+    
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    
+    /// Generate a key pair for `publisher`, refusing to overwrite an existing key
+    pub fn generate(&self, publisher: &str) -> Result<VerifyingKey> {
+        let key_path = self.key_path(publisher)?;
+        if key_path.exists() {
+            return Err(anyhow::anyhow!(
+                "A signing key for {} already exists at {}",
+                publisher,
+                key_path.display()
+            ));
+        }
+        
+        std::fs::create_dir_all(&self.dir)?;
+        let key = SigningKey::generate(&mut OsRng);
+        write_private(&key_path, &hex::encode(key.to_bytes()))?;
+        std::fs::write(self.dir.join(format!("{}.pub", publisher)), hex::encode(key.verifying_key().to_bytes()))?;
+        
+        Ok(key.verifying_key())
+    }
+    
+    pub fn load(&self, publisher: &str) -> Result<SigningKey> {
+        let key_path = self.key_path(publisher)?;
+        let hex_key = std::fs::read_to_string(&key_path)
+            .map_err(|_| anyhow::anyhow!("No signing key for {}; run `wasmrunner key generate {}`", publisher, publisher))?;
+        
+        let bytes: [u8; 32] = hex::decode(hex_key.trim())?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Signing key {} must be 32 bytes", key_path.display()))?;
+        
+        Ok(SigningKey::from_bytes(&bytes))
+    }
+    
+    fn key_path(&self, publisher: &str) -> Result<PathBuf> {
+        check_publisher(publisher)?;
+        Ok(self.dir.join(format!("{}.key", publisher)))
+    }
+}
+
+/// Write a file readable only by its owner
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(content.as_bytes())?;
+    }
+    
+    #[cfg(not(unix))]
+    std::fs::write(path, content)?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+    const MANIFEST: &[u8] = b"name = \"app\"";
+    
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+    
+    fn trusting(publisher: &str, key: &SigningKey) -> TrustStore {
+        let mut trust = TrustStore::default();
+        trust.add(publisher, key.verifying_key());
+        trust
+    }
+    
+    fn verify(
+        signature: Option<&ImageSignature>,
+        wasm: &[u8],
+        trust: &TrustStore,
+        policy: ImagePolicy,
+    ) -> Result<Verification> {
+        verify_image("app:1.0", signature, wasm, Some(MANIFEST), trust, policy)
+    }
+    
+    #[test]
+    fn tampered_images_are_refused_under_every_policy() {
+        let signature = ImageSignature::sign("acme", &key(1), WASM, Some(MANIFEST));
+        let tampered = b"\0asm\x01\0\0\0\x01";
+        
+        // Even from a publisher nobody trusts, which Permissive would otherwise run
+        for trust in [TrustStore::default(), trusting("acme", &key(1))] {
+            for policy in [ImagePolicy::Permissive, ImagePolicy::Enforce] {
+                let err = verify(Some(&signature), tampered, &trust, policy).unwrap_err();
+                assert!(matches!(err.downcast_ref(), Some(WasmRunnerError::BadSignature(_))));
+            }
+        }
+        
+        let result = verify_image("app:1.0", Some(&signature), WASM, None, &TrustStore::default(), ImagePolicy::Permissive);
+        assert!(result.is_err(), "dropping the signed manifest is tampering too");
+    }
+    
+    #[test]
+    fn enforce_refuses_unsigned_and_untrusted_images() {
+        let trust = TrustStore::default();
+        let err = verify(None, WASM, &trust, ImagePolicy::Enforce).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(WasmRunnerError::UnsignedImage(_))));
+        
+        let signature = ImageSignature::sign("acme", &key(1), WASM, Some(MANIFEST));
+        let err = verify(Some(&signature), WASM, &trust, ImagePolicy::Enforce).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(WasmRunnerError::UntrustedImage { .. })));
+        
+        assert_eq!(verify(None, WASM, &trust, ImagePolicy::Permissive).unwrap(), Verification::Unsigned);
+        assert_eq!(
+            verify(Some(&signature), WASM, &trust, ImagePolicy::Permissive).unwrap(),
+            Verification::Untrusted("acme".to_string())
+        );
+    }
+    
+    #[test]
+    fn trusted_needs_a_valid_signature() {
+        let signature = ImageSignature::sign("acme", &key(1), WASM, Some(MANIFEST));
+        assert_eq!(
+            verify(Some(&signature), WASM, &trusting("acme", &key(1)), ImagePolicy::Enforce).unwrap(),
+            Verification::Trusted("acme".to_string())
+        );
+        
+        // Signed by someone else's key under a trusted publisher's name
+        let forged = ImageSignature::sign("acme", &key(2), WASM, Some(MANIFEST));
+        for policy in [ImagePolicy::Permissive, ImagePolicy::Enforce] {
+            let err = verify(Some(&forged), WASM, &trusting("acme", &key(1)), policy).unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(WasmRunnerError::BadSignature(_))));
+        }
+    }
+    
+    #[test]
+    fn generate_refuses_to_overwrite_a_key() {
+        let keys = KeyStore::new(std::env::temp_dir().join(format!("wasmrunner-keys-{}", uuid::Uuid::new_v4())));
+        let public_key = keys.generate("acme").unwrap();
+        
+        assert!(keys.generate("acme").is_err());
+        assert_eq!(keys.load("acme").unwrap().verifying_key(), public_key);
+    }
+    
+    #[test]
+    fn publisher_names_cannot_leave_the_key_directory() {
+        let keys = KeyStore::new(std::env::temp_dir().join(format!("wasmrunner-keys-{}", uuid::Uuid::new_v4())));
+        for publisher in ["", "../acme", "acme/keys", ".acme", "acme\\keys"] {
+            assert!(keys.generate(publisher).is_err(), "{:?} should be rejected", publisher);
+            assert!(keys.load(publisher).is_err(), "{:?} should be rejected", publisher);
+        }
+        assert!(TrustStore::load(keys.dir(), &["../acme".to_string()]).is_err());
+    }
+}
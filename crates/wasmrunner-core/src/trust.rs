@@ -0,0 +1,100 @@
+
+use crate::config::PluginConfig;
+use crate::{Result, WasmRunnerError};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Directory under `plugins.plugin_dir` holding publisher keys
+pub const KEYS_DIR: &str = "keys";
+
+/// Public keys of trusted publishers, shared by plugins and images
+///
+/// Keys are read from `<keys_dir>/<publisher>.pub` as hex-encoded ed25519
+/// public keys, and only for publishers listed in `trusted_publishers`.
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    keys: HashMap<String, VerifyingKey>,
+}
+
+impl TrustStore {
+    pub fn load(keys_dir: &Path, trusted_publishers: &[String]) -> Result<Self> {
+        let mut store = Self::default();
+        
+        for publisher in trusted_publishers {
+            check_publisher(publisher)?;
+            let key_file = keys_dir.join(format!("{}.pub", publisher));
+            match std::fs::read_to_string(&key_file) {
+                Ok(hex_key) => store.add(publisher, parse_key(hex_key.trim())?),
+                Err(_) => warn!("No key found for trusted publisher {}", publisher),
+            }
+        }
+        
+        Ok(store)
+    }
+    
+    /// Trust store for the configured publishers
+    pub fn from_config(config: &PluginConfig) -> Result<Self> {
+        Self::load(&keys_dir(config), &config.trusted_publishers)
+    }
+    
+    pub fn add(&mut self, publisher: &str, key: VerifyingKey) {
+        self.keys.insert(publisher.to_string(), key);
+    }
+    
+    /// Add a hex-encoded public key
+    pub fn add_hex(&mut self, publisher: &str, hex_key: &str) -> Result<()> {
+        self.add(publisher, parse_key(hex_key)?);
+        Ok(())
+    }
+    
+    pub fn is_trusted(&self, publisher: &str) -> bool {
+        self.keys.contains_key(publisher)
+    }
+    
+    /// Verify a hex-encoded signature of `message` by `publisher`
+    pub fn verify(&self, publisher: &str, message: &[u8], signature_hex: &str) -> Result<()> {
+        let key = self.keys
+            .get(publisher)
+            .ok_or_else(|| WasmRunnerError::MissingKey(publisher.to_string()))?;
+        
+        let bytes: [u8; 64] = hex::decode(signature_hex.trim())?
+            .try_into()
+            .map_err(|_| WasmRunnerError::BadSignature(publisher.to_string()))?;
+        
+        key.verify(message, &Signature::from_bytes(&bytes))
+            .map_err(|_| WasmRunnerError::BadSignature(publisher.to_string()).into())
+    }
+}
+
+/// Refuse publisher names that could not safely name a key file
+///
+/// Names become `<publisher>.pub` and `<publisher>.key`, so they are limited
+/// to letters, digits, `.`, `_` and `-`, and may not start with `.`.
+pub fn check_publisher(publisher: &str) -> Result<()> {
+    let valid = !publisher.is_empty()
+        && !publisher.starts_with('.')
+        && publisher.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    
+    if !valid {
+        return Err(anyhow::anyhow!(
+            "Invalid publisher name {:?}: use letters, digits, '.', '_' and '-', not starting with '.'",
+            publisher
+        ));
+    }
+    Ok(())
+}
+
+/// Where publisher keys live for a plugin configuration
+pub fn keys_dir(config: &PluginConfig) -> PathBuf {
+    config.plugin_dir.join(KEYS_DIR)
+}
+
+fn parse_key(hex_key: &str) -> Result<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(hex_key)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes"))?;
+    
+    Ok(VerifyingKey::from_bytes(&bytes)?)
+}
@@ -0,0 +1,54 @@
+
+use std::collections::HashSet;
+use wasmrunner_core::config::SecurityConfig;
+
+/// Capability guarding network access
+pub const NETWORK: &str = "network";
+
+/// Capability guarding filesystem access
+pub const FILESYSTEM: &str = "filesystem";
+
+/// Capabilities granted to a guest
+///
+/// Host functions are only linked when the capability they declare is granted,
+/// so a guest importing anything else fails to instantiate.
+#[derive(Debug, Clone, Default)]
+pub struct CapabilitySet {
+    granted: HashSet<String>,
+}
+
+impl CapabilitySet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Capabilities implied by the security configuration
+    pub fn from_security(security: &SecurityConfig) -> Self {
+        let mut set = Self::new();
+        if security.allow_network {
+            set.grant(NETWORK);
+        }
+        if security.allow_filesystem {
+            set.grant(FILESYSTEM);
+        }
+        set
+    }
+    
+    pub fn grant(&mut self, capability: &str) {
+        self.granted.insert(capability.to_string());
+    }
+    
+    pub fn allows(&self, capability: &str) -> bool {
+        self.granted.contains(capability)
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for CapabilitySet {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for capability in iter {
+            set.grant(capability.as_ref());
+        }
+        set
+    }
+}
@@ -0,0 +1,60 @@
+
+use crate::host::LoadedPlugin;
+use tracing::debug;
+use wasmrunner_core::Result;
+use wasmtime::component::{Component, Func, Linker};
+use wasmtime::{Engine, Store};
+
+/// Instantiate component plugins and forward their exports to an application
+///
+/// Each plugin gets its own instance in the application's store with no
+/// imports of its own, so it stays sandboxed. Calls from the application to a
+/// plugin interface are forwarded to the matching export of that instance.
+pub fn link_components<T: 'static>(
+    engine: &Engine,
+    store: &mut Store<T>,
+    linker: &mut Linker<T>,
+    app: &Component,
+    plugins: &[&LoadedPlugin],
+) -> Result<()> {
+    for plugin in plugins {
+        let (bytes, spec) = match (plugin.component_bytes(), &plugin.manifest.component) {
+            (Some(bytes), Some(spec)) => (bytes, spec),
+            _ => continue,
+        };
+        
+        let component = Component::new(engine, bytes)?;
+        let instance = Linker::new(engine).instantiate(&mut *store, &component)?;
+        
+        for interface in &spec.exports {
+            let funcs = {
+                let mut exports = instance.exports(&mut *store);
+                let mut exported = exports.instance(&interface.interface).ok_or_else(|| {
+                    anyhow::anyhow!("Plugin {} does not export {}", plugin.name(), interface.interface)
+                })?;
+                
+                interface.functions
+                    .iter()
+                    .map(|name| {
+                        exported
+                            .func(name)
+                            .map(|func| (name.clone(), func))
+                            .ok_or_else(|| anyhow::anyhow!("Plugin {} does not export {}#{}", plugin.name(), interface.interface, name))
+                    })
+                    .collect::<Result<Vec<(String, Func)>>>()?
+            };
+            
+            let mut target = linker.instance(&interface.interface)?;
+            for (name, func) in funcs {
+                debug!("Linking {}#{} from plugin {}", interface.interface, name, plugin.name());
+                
+                target.func_new(app, &name, move |mut store, params, results| {
+                    func.call(&mut store, params, results)?;
+                    func.post_return(&mut store)
+                })?;
+            }
+        }
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,20 @@
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("Invalid plugin manifest {path}: {message}")]
+    InvalidManifest { path: String, message: String },
+    
+    #[error("Publisher {0} is not trusted")]
+    UntrustedPublisher(String),
+    
+    #[error("Digest mismatch for {path}: expected {expected}, got {actual}")]
+    DigestMismatch { path: String, expected: String, actual: String },
+    
+    #[error("Symbol {symbol} not found in plugin {plugin}")]
+    MissingSymbol { plugin: String, symbol: String },
+    
+    #[error("Host function {function} returned error code {code}")]
+    HostFunctionFailed { function: String, code: i32 },
+}
@@ -0,0 +1,118 @@
+
+use crate::host::{LoadedPlugin, PluginHost};
+use crate::manifest::EventsSpec;
+use crate::native::NativeEventHandler;
+use std::thread::JoinHandle;
+use tracing::{debug, warn};
+use wasmrunner_core::events::{EventBus, DEFAULT_CAPACITY};
+use wasmrunner_core::Result;
+use wasmtime::component::{self, Component, Linker};
+use wasmtime::{Engine, Store};
+
+/// Threads delivering lifecycle events to plugins
+pub struct EventHandlers {
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl EventHandlers {
+    /// Wait for every plugin to process its queued events
+    ///
+    /// Call after `EventBus::close`, otherwise this waits forever.
+    pub fn join(self) {
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+}
+
+enum Handler {
+    Native(NativeEventHandler),
+    Component {
+        store: Store<()>,
+        func: component::Func,
+    },
+}
+
+impl Handler {
+    fn handle(&mut self, event: &str) -> Result<()> {
+        match self {
+            Handler::Native(handler) => handler.call(event.as_bytes()),
+            Handler::Component { store, func } => {
+                func.call(&mut *store, &[component::Val::String(event.into())], &mut [])?;
+                func.post_return(&mut *store)
+            }
+        }
+    }
+}
+
+impl PluginHost {
+    /// Subscribe every plugin declaring `[events]` to lifecycle events on `bus`
+    pub fn subscribe_events(&self, bus: &EventBus) -> Result<EventHandlers> {
+        let mut threads = Vec::new();
+        
+        for plugin in self.plugins() {
+            let spec = match &plugin.manifest.events {
+                Some(spec) => spec.clone(),
+                None => continue,
+            };
+            
+            let mut handler = resolve_handler(plugin, &spec)?;
+            let mut events = bus.subscribe(spec.queue_size.unwrap_or(DEFAULT_CAPACITY));
+            let name = plugin.name().to_string();
+            
+            threads.push(std::thread::spawn(move || {
+                while let Some(event) = events.blocking_recv() {
+                    let kind = event.kind.name();
+                    if !spec.kinds.is_empty() && !spec.kinds.iter().any(|k| k == kind) {
+                        continue;
+                    }
+                    
+                    let payload = match serde_json::to_string(&event) {
+                        Ok(payload) => payload,
+                        Err(e) => {
+                            warn!("Failed to serialize {} event: {}", kind, e);
+                            continue;
+                        }
+                    };
+                    
+                    debug!("Delivering {} event to plugin {}", kind, name);
+                    if let Err(e) = handler.handle(&payload) {
+                        warn!("Plugin {} failed to handle {} event: {}", name, kind, e);
+                    }
+                }
+            }));
+        }
+        
+        Ok(EventHandlers { threads })
+    }
+}
+
+fn resolve_handler(plugin: &LoadedPlugin, spec: &EventsSpec) -> Result<Handler> {
+    if let Some(library) = plugin.library() {
+        return Ok(Handler::Native(library.event_handler(&spec.handler)?));
+    }
+    
+    let bytes = plugin
+        .component_bytes()
+        .ok_or_else(|| anyhow::anyhow!("Plugin {} has no event handler implementation", plugin.name()))?;
+    let (interface, function) = spec.handler.split_once('#').ok_or_else(|| {
+        anyhow::anyhow!("Component event handler must be `interface#function`: {}", spec.handler)
+    })?;
+    
+    let mut config = wasmtime::Config::new();
+    config.wasm_component_model(true);
+    let engine = Engine::new(&config)?;
+    
+    // The handler gets its own instance, separate from any application's
+    let mut store = Store::new(&engine, ());
+    let instance = Linker::new(&engine).instantiate(&mut store, &Component::new(&engine, bytes)?)?;
+    let func = {
+        let mut exports = instance.exports(&mut store);
+        exports
+            .instance(interface)
+            .and_then(|mut exported| exported.func(function))
+            .ok_or_else(|| anyhow::anyhow!("Plugin {} does not export {}", plugin.name(), spec.handler))?
+    };
+    
+    Ok(Handler::Component { store, func })
+}
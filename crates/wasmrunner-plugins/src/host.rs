@@ -0,0 +1,321 @@
+
+use crate::capability::CapabilitySet;
+use crate::error::PluginError;
+use crate::manifest::{HostFunctionSpec, PluginManifest, MANIFEST_FILE, SIGNATURE_FILE};
+use crate::native::NativeLibrary;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use wasmrunner_core::config::PluginConfig;
+use wasmrunner_core::trust::TrustStore;
+use wasmrunner_core::{Result, WasmRunnerError};
+use wasmtime::{FuncType, Linker};
+
+/// A verified plugin ready to be linked
+pub struct LoadedPlugin {
+    pub manifest: PluginManifest,
+    pub dir: PathBuf,
+    library: Option<Arc<NativeLibrary>>,
+    component: Option<Vec<u8>>,
+}
+
+impl LoadedPlugin {
+    pub fn name(&self) -> &str {
+        &self.manifest.plugin.name
+    }
+    
+    /// Verified bytes of a component plugin
+    pub fn component_bytes(&self) -> Option<&[u8]> {
+        self.component.as_deref()
+    }
+    
+    pub(crate) fn library(&self) -> Option<&Arc<NativeLibrary>> {
+        self.library.as_ref()
+    }
+    
+    /// Host functions whose capability is granted
+    fn granted_functions<'a>(&'a self, capabilities: &'a CapabilitySet) -> impl Iterator<Item = &'a HostFunctionSpec> {
+        self.manifest.functions.iter().filter(move |spec| {
+            let capability = spec.capability(&self.manifest.plugin);
+            let granted = capabilities.allows(capability);
+            if !granted {
+                debug!("Not linking {}::{}: capability {} not granted", spec.module, spec.name, capability);
+            }
+            granted
+        })
+    }
+}
+
+/// Registry of verified plugins
+pub struct PluginHost {
+    trust: TrustStore,
+    plugins: Vec<LoadedPlugin>,
+}
+
+impl PluginHost {
+    pub fn new(trust: TrustStore) -> Self {
+        Self {
+            trust,
+            plugins: Vec::new(),
+        }
+    }
+    
+    /// Build a host from configuration, scanning `plugin_dir` when auto discovery is on
+    pub fn from_config(config: &PluginConfig) -> Result<Self> {
+        let trust = TrustStore::from_config(config)?;
+        let mut host = Self::new(trust);
+        
+        if config.auto_discovery {
+            host.discover(&config.plugin_dir)?;
+        }
+        
+        Ok(host)
+    }
+    
+    /// Load every plugin directory under `plugin_dir`, skipping ones that fail verification
+    pub fn discover(&mut self, plugin_dir: &Path) -> Result<()> {
+        if !plugin_dir.exists() {
+            return Ok(());
+        }
+        
+        for entry in std::fs::read_dir(plugin_dir)? {
+            let dir = entry?.path();
+            if !dir.join(MANIFEST_FILE).is_file() {
+                continue;
+            }
+            
+            if let Err(e) = self.load(&dir) {
+                warn!("Skipping plugin {}: {}", dir.display(), e);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Verify and load a single plugin directory
+    pub fn load(&mut self, dir: &Path) -> Result<()> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let content = std::fs::read_to_string(&manifest_path)?;
+        let manifest = PluginManifest::parse(&content, &manifest_path)?;
+        let publisher = &manifest.plugin.publisher;
+        
+        if !self.trust.is_trusted(publisher) {
+            return Err(PluginError::UntrustedPublisher(publisher.clone()).into());
+        }
+        
+        let signature = std::fs::read_to_string(dir.join(SIGNATURE_FILE))
+            .map_err(|_| WasmRunnerError::BadSignature(format!("plugin {}", manifest.plugin.name)))?;
+        self.trust.verify(publisher, content.as_bytes(), &signature)?;
+        
+        // The manifest is signed, so checking the library digest covers the library too.
+        // Each file is read once and only the bytes that were checked are used.
+        let library = match &manifest.library {
+            Some(spec) => {
+                let bytes = read_verified(&dir.join(&spec.path), &spec.sha256)?;
+                Some(NativeLibrary::load(&manifest.plugin.name, &bytes)?)
+            }
+            None => None,
+        };
+        
+        let component = match &manifest.component {
+            Some(spec) => Some(read_verified(&dir.join(&spec.path), &spec.sha256)?),
+            None => None,
+        };
+        
+        info!("Loaded plugin {} v{} from {}", manifest.plugin.name, manifest.plugin.version, publisher);
+        self.plugins.push(LoadedPlugin {
+            manifest,
+            dir: dir.to_path_buf(),
+            library,
+            component,
+        });
+        
+        Ok(())
+    }
+    
+    pub fn plugins(&self) -> &[LoadedPlugin] {
+        &self.plugins
+    }
+    
+    /// Look up component plugins by name, failing if any is not installed
+    pub fn components(&self, names: &[String]) -> Result<Vec<&LoadedPlugin>> {
+        names
+            .iter()
+            .map(|name| {
+                self.plugins
+                    .iter()
+                    .find(|p| p.name() == name && p.component.is_some())
+                    .ok_or_else(|| anyhow::anyhow!("Component plugin not installed: {}", name))
+            })
+            .collect()
+    }
+    
+    /// Register host functions whose capability is granted
+    pub fn add_to_linker<T: 'static>(&self, linker: &mut Linker<T>, capabilities: &CapabilitySet) -> Result<()> {
+        for plugin in &self.plugins {
+            let library = match &plugin.library {
+                Some(library) => library,
+                None => continue,
+            };
+            
+            for spec in plugin.granted_functions(capabilities) {
+                let function = library.function(spec.symbol())?;
+                let result_types = spec.results.clone();
+                let ty = FuncType::new(
+                    spec.params.iter().map(|t| t.to_wasmtime()),
+                    spec.results.iter().map(|t| t.to_wasmtime()),
+                );
+                
+                linker.func_new(&spec.module, &spec.name, ty, move |_caller, params, results| {
+                    function.call(params, results, &result_types)
+                })?;
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// Read a file and check it against the sha256 from the signed manifest
+fn read_verified(path: &Path, expected: &str) -> Result<Vec<u8>> {
+    let bytes = std::fs::read(path)?;
+    let actual = hex::encode(Sha256::digest(&bytes));
+    
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(PluginError::DigestMismatch {
+            path: path.display().to_string(),
+            expected: expected.to_string(),
+            actual,
+        }.into());
+    }
+    
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::NETWORK;
+    use ed25519_dalek::{Signer, SigningKey};
+    
+    const COMPONENT: &[u8] = b"\0asm\x0d\0\x01\0";
+    
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+    
+    fn trusting(publisher: &str, key: &SigningKey) -> PluginHost {
+        let mut trust = TrustStore::default();
+        trust.add(publisher, key.verifying_key());
+        PluginHost::new(trust)
+    }
+    
+    fn manifest() -> String {
+        format!(
+            "[plugin]\nname = \"db\"\nversion = \"1.0.0\"\npublisher = \"acme\"\n\n[component]\npath = \"db.wasm\"\nsha256 = \"{}\"\n",
+            hex::encode(Sha256::digest(COMPONENT))
+        )
+    }
+    
+    /// Write a component plugin, signing its manifest with `signer`
+    fn plugin_dir(manifest: &str, signer: Option<&SigningKey>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wasmrunner-plugin-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("db.wasm"), COMPONENT).unwrap();
+        std::fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        if let Some(signer) = signer {
+            let signature = hex::encode(signer.sign(manifest.as_bytes()).to_bytes());
+            std::fs::write(dir.join(SIGNATURE_FILE), signature).unwrap();
+        }
+        dir
+    }
+    
+    #[test]
+    fn signed_plugins_load() {
+        let mut host = trusting("acme", &key(1));
+        host.load(&plugin_dir(&manifest(), Some(&key(1)))).unwrap();
+        
+        assert_eq!(host.plugins()[0].component_bytes(), Some(COMPONENT));
+    }
+    
+    #[test]
+    fn unsigned_and_forged_manifests_are_refused() {
+        let manifest = manifest();
+        
+        // No signature, and one by someone else's key under the trusted publisher's name
+        for signer in [None, Some(key(2))] {
+            let mut host = trusting("acme", &key(1));
+            let err = host.load(&plugin_dir(&manifest, signer.as_ref())).unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(WasmRunnerError::BadSignature(_))));
+            assert!(host.plugins().is_empty());
+        }
+        
+        // Edited after signing
+        let dir = plugin_dir(&manifest, Some(&key(1)));
+        std::fs::write(dir.join(MANIFEST_FILE), manifest.replace("1.0.0", "1.0.1")).unwrap();
+        let err = trusting("acme", &key(1)).load(&dir).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(WasmRunnerError::BadSignature(_))));
+    }
+    
+    #[test]
+    fn untrusted_publishers_are_refused() {
+        let dir = plugin_dir(&manifest(), Some(&key(1)));
+        let err = trusting("other", &key(1)).load(&dir).unwrap_err();
+        
+        assert!(matches!(err.downcast_ref(), Some(PluginError::UntrustedPublisher(_))));
+    }
+    
+    #[test]
+    fn files_not_matching_the_signed_digest_are_refused() {
+        let dir = plugin_dir(&manifest(), Some(&key(1)));
+        std::fs::write(dir.join("db.wasm"), b"\0asm\x0d\0\x01\0\x01").unwrap();
+        
+        let mut host = trusting("acme", &key(1));
+        let err = host.load(&dir).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(PluginError::DigestMismatch { .. })));
+        assert!(host.plugins().is_empty());
+    }
+    
+    #[test]
+    fn only_granted_functions_are_linked() {
+        let manifest = PluginManifest::parse(
+            r#"
+[plugin]
+name = "kv"
+version = "1.0.0"
+publisher = "acme"
+
+[library]
+path = "libkv.so"
+sha256 = "00"
+
+[[functions]]
+module = "wasmrunner:kv"
+name = "get"
+
+[[functions]]
+module = "wasmrunner:kv"
+name = "fetch"
+capability = "network"
+"#,
+            Path::new(MANIFEST_FILE),
+        )
+        .unwrap();
+        let plugin = LoadedPlugin {
+            manifest,
+            dir: PathBuf::new(),
+            library: None,
+            component: None,
+        };
+        let linked = |capabilities: CapabilitySet| -> Vec<String> {
+            plugin.granted_functions(&capabilities).map(|spec| spec.name.clone()).collect()
+        };
+        
+        // Functions without a capability are guarded by the plugin name
+        assert!(linked(CapabilitySet::new()).is_empty());
+        assert_eq!(linked(["kv"].into_iter().collect()), ["get"]);
+        assert_eq!(linked([NETWORK].into_iter().collect()), ["fetch"]);
+        assert_eq!(linked(["kv", NETWORK].into_iter().collect()), ["get", "fetch"]);
+    }
+}
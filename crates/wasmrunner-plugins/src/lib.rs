@@ -0,0 +1,30 @@
+
+//! Host plugin discovery, verification and linking
+//!
+//! Plugins live in `PluginConfig::plugin_dir`, one directory per plugin with a
+//! `plugin.toml` manifest. A plugin is only loaded when its publisher is listed
+//! in `trusted_publishers` and its manifest carries a valid signature from that
+//! publisher's key. Host functions it provides are linked into guests only when
+//! the guest has been granted the capability each function declares.
+//!
+//! Plugins can also ship as WASM components exporting WIT interfaces. These
+//! run in their own sandboxed instance and are linked into an application
+//! component when the application's manifest lists them in `plugins`.
+//!
+//! Either kind of plugin may subscribe to container lifecycle events, which
+//! are delivered on a dedicated thread per plugin through a bounded queue.
+
+pub mod capability;
+pub mod component;
+pub mod error;
+pub mod events;
+pub mod host;
+pub mod manifest;
+pub mod native;
+
+pub use capability::CapabilitySet;
+pub use error::PluginError;
+pub use events::EventHandlers;
+pub use host::PluginHost;
+pub use manifest::PluginManifest;
+pub use wasmrunner_core::trust::TrustStore;
@@ -0,0 +1,169 @@
+
+use crate::error::PluginError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use wasmrunner_core::Result;
+
+/// File name of a plugin manifest
+pub const MANIFEST_FILE: &str = "plugin.toml";
+
+/// File holding the hex-encoded ed25519 signature of `plugin.toml`
+pub const SIGNATURE_FILE: &str = "plugin.toml.sig";
+
+/// Contents of `plugin.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub plugin: PluginInfo,
+    
+    /// Native library implementing the host functions
+    pub library: Option<LibrarySpec>,
+    
+    /// WASM component exporting interfaces to applications
+    pub component: Option<ComponentSpec>,
+    
+    #[serde(default)]
+    pub functions: Vec<HostFunctionSpec>,
+    
+    /// Container lifecycle events the plugin subscribes to
+    pub events: Option<EventsSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub publisher: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibrarySpec {
+    /// Path relative to the plugin directory
+    pub path: String,
+    
+    /// Hex-encoded sha256 of the library, covered by the manifest signature
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentSpec {
+    /// Path relative to the plugin directory
+    pub path: String,
+    
+    /// Hex-encoded sha256 of the component, covered by the manifest signature
+    pub sha256: String,
+    
+    #[serde(default)]
+    pub exports: Vec<InterfaceSpec>,
+}
+
+/// A WIT interface exported by a component plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceSpec {
+    /// Fully qualified interface name, e.g. `wasmrunner:db/store`
+    pub interface: String,
+    
+    /// Functions of the interface that are forwarded to applications
+    pub functions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventsSpec {
+    /// Event names to receive, e.g. `exited` or `oom_killed`; empty means all
+    #[serde(default)]
+    pub kinds: Vec<String>,
+    
+    /// Library symbol, or `interface#function` export of a component plugin,
+    /// receiving each event as JSON
+    pub handler: String,
+    
+    /// Events queued for the plugin before emitters wait for it
+    pub queue_size: Option<usize>,
+}
+
+/// A host function exposed to guests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostFunctionSpec {
+    /// Import module name guests use, e.g. `wasmrunner:kv`
+    pub module: String,
+    pub name: String,
+    
+    /// Exported symbol in the native library, defaults to `name`
+    pub symbol: Option<String>,
+    
+    #[serde(default)]
+    pub params: Vec<ValueType>,
+    
+    #[serde(default)]
+    pub results: Vec<ValueType>,
+    
+    /// Capability a guest needs for this function, defaults to the plugin name
+    pub capability: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl ValueType {
+    pub fn to_wasmtime(self) -> wasmtime::ValType {
+        match self {
+            ValueType::I32 => wasmtime::ValType::I32,
+            ValueType::I64 => wasmtime::ValType::I64,
+            ValueType::F32 => wasmtime::ValType::F32,
+            ValueType::F64 => wasmtime::ValType::F64,
+        }
+    }
+}
+
+impl PluginManifest {
+    pub fn load(plugin_dir: &Path) -> Result<Self> {
+        let path = plugin_dir.join(MANIFEST_FILE);
+        Self::parse(&std::fs::read_to_string(&path)?, &path)
+    }
+    
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        let manifest: PluginManifest = toml::from_str(content).map_err(|e| PluginError::InvalidManifest {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        
+        if !manifest.functions.is_empty() && manifest.library.is_none() {
+            return Err(PluginError::InvalidManifest {
+                path: path.display().to_string(),
+                message: "host functions declared without a library".to_string(),
+            }.into());
+        }
+        
+        if manifest.events.is_some() && manifest.library.is_none() && manifest.component.is_none() {
+            return Err(PluginError::InvalidManifest {
+                path: path.display().to_string(),
+                message: "event handler declared without a library or component".to_string(),
+            }.into());
+        }
+        
+        if manifest.library.is_some() && manifest.component.is_some() {
+            return Err(PluginError::InvalidManifest {
+                path: path.display().to_string(),
+                message: "a plugin provides either a library or a component, not both".to_string(),
+            }.into());
+        }
+        
+        Ok(manifest)
+    }
+}
+
+impl HostFunctionSpec {
+    pub fn symbol(&self) -> &str {
+        self.symbol.as_deref().unwrap_or(&self.name)
+    }
+    
+    pub fn capability<'a>(&'a self, plugin: &'a PluginInfo) -> &'a str {
+        self.capability.as_deref().unwrap_or(&plugin.name)
+    }
+}
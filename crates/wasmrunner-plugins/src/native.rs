@@ -0,0 +1,202 @@
+
+use crate::error::PluginError;
+use crate::manifest::ValueType;
+use libloading::Library;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use wasmrunner_core::Result;
+use wasmtime::Val;
+
+/// ABI every native host function is exported with
+///
+/// Parameters and results are passed as raw 64-bit values: integers are
+/// zero-extended and floats are passed as their bit patterns. A non-zero
+/// return value traps the calling guest.
+pub type RawHostFn = unsafe extern "C" fn(
+    params: *const u64,
+    params_len: usize,
+    results: *mut u64,
+    results_len: usize,
+) -> i32;
+
+/// ABI of a native lifecycle event handler, called with the event as JSON
+pub type RawEventFn = unsafe extern "C" fn(event: *const u8, event_len: usize) -> i32;
+
+/// A loaded plugin library
+pub struct NativeLibrary {
+    name: String,
+    library: Library,
+}
+
+impl NativeLibrary {
+    /// Load a library from the bytes whose digest was checked
+    ///
+    /// The plugin directory may change after verification, so the library is
+    /// loaded from a private copy rather than from its original path.
+    pub fn load(name: &str, bytes: &[u8]) -> Result<Arc<Self>> {
+        let copy = private_copy(bytes)?;
+        
+        // Safety: the copy holds exactly the bytes checked against a signed manifest
+        let library = unsafe { Library::new(&copy) };
+        
+        // The mapping outlives the file; removal fails on Windows, leaving it in the temp dir
+        let _ = std::fs::remove_file(&copy);
+        
+        Ok(Arc::new(Self {
+            name: name.to_string(),
+            library: library?,
+        }))
+    }
+    
+    pub fn function(self: &Arc<Self>, symbol: &str) -> Result<NativeFunction> {
+        // Safety: the symbol is declared with the plugin ABI in the signed manifest
+        let func = unsafe {
+            self.library
+                .get::<RawHostFn>(symbol.as_bytes())
+                .map(|f| *f)
+                .map_err(|_| PluginError::MissingSymbol {
+                    plugin: self.name.clone(),
+                    symbol: symbol.to_string(),
+                })?
+        };
+        
+        Ok(NativeFunction {
+            symbol: symbol.to_string(),
+            func,
+            _library: self.clone(),
+        })
+    }
+}
+
+impl NativeLibrary {
+    pub fn event_handler(self: &Arc<Self>, symbol: &str) -> Result<NativeEventHandler> {
+        // Safety: the symbol is declared as an event handler in the signed manifest
+        let func = unsafe {
+            self.library
+                .get::<RawEventFn>(symbol.as_bytes())
+                .map(|f| *f)
+                .map_err(|_| PluginError::MissingSymbol {
+                    plugin: self.name.clone(),
+                    symbol: symbol.to_string(),
+                })?
+        };
+        
+        Ok(NativeEventHandler {
+            symbol: symbol.to_string(),
+            func,
+            _library: self.clone(),
+        })
+    }
+}
+
+/// Write `bytes` to a new file in the temp dir that only this user can open
+fn private_copy(bytes: &[u8]) -> Result<PathBuf> {
+    static COPIES: AtomicUsize = AtomicUsize::new(0);
+    
+    let path = std::env::temp_dir().join(format!(
+        "wasmrunner-plugin-{}-{}{}",
+        std::process::id(),
+        COPIES.fetch_add(1, Ordering::Relaxed),
+        std::env::consts::DLL_SUFFIX,
+    ));
+    
+    // create_new refuses existing files and symlinks planted at the path
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    
+    let mut file = options.open(&path)?;
+    if let Err(e) = file.write_all(bytes).and_then(|_| file.sync_all()) {
+        let _ = std::fs::remove_file(&path);
+        return Err(e.into());
+    }
+    
+    Ok(path)
+}
+
+/// A lifecycle event handler resolved from a native library
+pub struct NativeEventHandler {
+    symbol: String,
+    func: RawEventFn,
+    _library: Arc<NativeLibrary>,
+}
+
+impl NativeEventHandler {
+    pub fn call(&self, event: &[u8]) -> Result<()> {
+        // Safety: the buffer outlives the call and its length is passed alongside
+        let code = unsafe { (self.func)(event.as_ptr(), event.len()) };
+        
+        if code != 0 {
+            return Err(PluginError::HostFunctionFailed {
+                function: self.symbol.clone(),
+                code,
+            }.into());
+        }
+        
+        Ok(())
+    }
+}
+
+/// A host function resolved from a native library
+pub struct NativeFunction {
+    symbol: String,
+    func: RawHostFn,
+    // Keeps the library mapped for as long as the function is linked
+    _library: Arc<NativeLibrary>,
+}
+
+impl NativeFunction {
+    pub fn call(&self, params: &[Val], results: &mut [Val], result_types: &[ValueType]) -> Result<()> {
+        let raw_params: Vec<u64> = params.iter().map(to_raw).collect();
+        let mut raw_results = vec![0u64; results.len()];
+        
+        // Safety: buffers match the lengths passed alongside them
+        let code = unsafe {
+            (self.func)(
+                raw_params.as_ptr(),
+                raw_params.len(),
+                raw_results.as_mut_ptr(),
+                raw_results.len(),
+            )
+        };
+        
+        if code != 0 {
+            return Err(PluginError::HostFunctionFailed {
+                function: self.symbol.clone(),
+                code,
+            }.into());
+        }
+        
+        for ((result, raw), ty) in results.iter_mut().zip(raw_results).zip(result_types) {
+            *result = from_raw(raw, *ty);
+        }
+        
+        Ok(())
+    }
+}
+
+fn to_raw(val: &Val) -> u64 {
+    match val {
+        Val::I32(v) => *v as u32 as u64,
+        Val::I64(v) => *v as u64,
+        Val::F32(bits) => *bits as u64,
+        Val::F64(bits) => *bits,
+        _ => 0,
+    }
+}
+
+fn from_raw(raw: u64, ty: ValueType) -> Val {
+    match ty {
+        ValueType::I32 => Val::I32(raw as u32 as i32),
+        ValueType::I64 => Val::I64(raw as i64),
+        ValueType::F32 => Val::F32(raw as u32),
+        ValueType::F64 => Val::F64(raw),
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::Result;
+use crate::protocol::ImageDescriptor;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{debug, warn};
+use wasmrunner_core::signing::sha256_hex;
+
+/// Content-addressed cache of pulled files, bounded in size
+///
+/// Files are stored as `blobs/<sha256>` and descriptors as
+/// `descriptors/<wasm sha256>.json`. Reading a file marks it as used; when
+/// the cache grows past its limit the least recently used files go first.
+#[derive(Debug, Clone)]
+pub struct BlobCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+/// What a garbage collection pass removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcStats {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub remaining_bytes: u64,
+}
+
+impl BlobCache {
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(dir.join("blobs"))?;
+        std::fs::create_dir_all(dir.join("descriptors"))?;
+        Ok(Self { dir, max_bytes })
+    }
+    
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    
+    fn blob_path(&self, digest: &str) -> PathBuf {
+        self.dir.join("blobs").join(digest.to_ascii_lowercase())
+    }
+    
+    fn descriptor_path(&self, wasm_digest: &str) -> PathBuf {
+        self.dir.join("descriptors").join(format!("{}.json", wasm_digest.to_ascii_lowercase()))
+    }
+    
+    /// A cached file, if present and intact
+    pub fn get(&self, digest: &str) -> Option<Vec<u8>> {
+        let path = self.blob_path(digest);
+        let data = std::fs::read(&path).ok()?;
+        
+        if !sha256_hex(&data).eq_ignore_ascii_case(digest) {
+            warn!("Removing corrupt cache entry sha256:{}", digest);
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        
+        touch(&path);
+        Some(data)
+    }
+    
+    /// Store a file under its digest, then trim the cache to its limit
+    pub fn put(&self, data: &[u8]) -> Result<String> {
+        let digest = sha256_hex(data);
+        write_atomic(&self.blob_path(&digest), data)?;
+        self.gc()?;
+        Ok(digest)
+    }
+    
+    /// The descriptor last pulled for a module digest
+    pub fn descriptor(&self, wasm_digest: &str) -> Option<ImageDescriptor> {
+        let path = self.descriptor_path(wasm_digest);
+        let descriptor = serde_json::from_slice(&std::fs::read(&path).ok()?).ok()?;
+        touch(&path);
+        Some(descriptor)
+    }
+    
+    pub fn put_descriptor(&self, descriptor: &ImageDescriptor) -> Result<()> {
+        write_atomic(
+            &self.descriptor_path(&descriptor.wasm_sha256),
+            &serde_json::to_vec_pretty(descriptor)?,
+        )
+    }
+    
+    /// Remove least recently used entries until the cache fits its limit
+    pub fn gc(&self) -> Result<GcStats> {
+        self.gc_to(self.max_bytes)
+    }
+    
+    /// Remove least recently used entries until at most `max_bytes` remain
+    pub fn gc_to(&self, max_bytes: u64) -> Result<GcStats> {
+        let mut entries = Vec::new();
+        for subdir in ["blobs", "descriptors"] {
+            for entry in std::fs::read_dir(self.dir.join(subdir))? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                if metadata.is_file() {
+                    let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    entries.push((used, metadata.len(), entry.path()));
+                }
+            }
+        }
+        
+        let mut stats = GcStats {
+            remaining_bytes: entries.iter().map(|(_, size, _)| size).sum(),
+            ..Default::default()
+        };
+        
+        // Oldest first
+        entries.sort_by_key(|(used, _, _)| *used);
+        for (_, size, path) in entries {
+            if stats.remaining_bytes <= max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                debug!("Evicted {}", path.display());
+                stats.removed += 1;
+                stats.freed_bytes += size;
+                stats.remaining_bytes -= size;
+            }
+        }
+        
+        Ok(stats)
+    }
+}
+
+/// Mark a cache entry as just used
+fn touch(path: &Path) {
+    if let Ok(file) = std::fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let partial = path.with_extension(format!("partial-{}", std::process::id()));
+    std::fs::write(&partial, data)?;
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
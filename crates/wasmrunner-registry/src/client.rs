@@ -0,0 +1,193 @@
+use crate::cache::BlobCache;
+use crate::error::{RegistryError, Result};
+use crate::protocol::{check_digest, url_name, validate_name, validate_repository, ImageDescriptor, Repository};
+use reqwest::{Method, RequestBuilder, Response};
+use tracing::debug;
+use wasmrunner_core::signing::{sha256_hex, ImageSignature};
+
+/// Client for a registry's push and pull API
+#[derive(Debug, Clone)]
+pub struct RegistryClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    cache: Option<BlobCache>,
+}
+
+/// An image downloaded from a registry, checked against its descriptor
+#[derive(Debug, Clone)]
+pub struct PulledImage {
+    pub descriptor: ImageDescriptor,
+    pub wasm: Vec<u8>,
+    pub manifest: Option<Vec<u8>>,
+}
+
+impl RegistryClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+            cache: None,
+        }
+    }
+    
+    /// Send `token` as a bearer token with every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+    
+    /// Serve pulled files from `cache` when present and add downloads to it
+    pub fn with_cache(mut self, cache: BlobCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+    
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.http.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+    
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        debug!("{} {}", status, response.url());
+        
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let body = response.text().await.unwrap_or_default();
+            Err(RegistryError::from_response(status, &body))
+        }
+    }
+    
+    /// Check that the registry is reachable and accepts our credentials
+    pub async fn ping(&self) -> Result<()> {
+        self.send(self.request(Method::GET, "/v1/")).await?;
+        Ok(())
+    }
+    
+    pub async fn list(&self) -> Result<Vec<Repository>> {
+        Ok(self.send(self.request(Method::GET, "/v1/images")).await?.json().await?)
+    }
+    
+    pub async fn descriptor(&self, name: &str, tag: &str) -> Result<ImageDescriptor> {
+        validate_repository(name)?;
+        validate_name(tag)?;
+        let path = format!("/v1/images/{}/{}", url_name(name), tag);
+        Ok(self.send(self.request(Method::GET, &path)).await?.json().await?)
+    }
+    
+    /// Download `name:tag`, failing if any file does not match the descriptor
+    ///
+    /// Files already in the cache are not downloaded again.
+    pub async fn pull(&self, name: &str, tag: &str) -> Result<PulledImage> {
+        let descriptor = self.descriptor(name, tag).await?;
+        
+        let wasm = self
+            .fetch(
+                &format!("/v1/images/{}/{}/wasm", url_name(name), tag),
+                &format!("{}:{} module", name, tag),
+                &descriptor.wasm_sha256,
+            )
+            .await?;
+        let manifest = match &descriptor.manifest_sha256 {
+            Some(expected) => Some(
+                self.fetch(
+                    &format!("/v1/images/{}/{}/manifest", url_name(name), tag),
+                    &format!("{}:{} manifest", name, tag),
+                    expected,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+        
+        if let Some(cache) = &self.cache {
+            cache.put_descriptor(&descriptor)?;
+        }
+        Ok(PulledImage { descriptor, wasm, manifest })
+    }
+    
+    /// Load an image by module digest from the cache alone, without contacting the registry
+    pub fn pull_cached(&self, wasm_digest: &str) -> Result<PulledImage> {
+        let not_cached = || {
+            RegistryError::NotFound(format!("sha256:{} is not cached; pull it by tag first", wasm_digest))
+        };
+        let cache = self.cache.as_ref().ok_or_else(not_cached)?;
+        
+        let descriptor = cache.descriptor(wasm_digest).ok_or_else(not_cached)?;
+        let wasm = cache.get(&descriptor.wasm_sha256).ok_or_else(not_cached)?;
+        let manifest = match &descriptor.manifest_sha256 {
+            Some(digest) => Some(cache.get(digest).ok_or_else(not_cached)?),
+            None => None,
+        };
+        
+        Ok(PulledImage { descriptor, wasm, manifest })
+    }
+    
+    /// A file with digest `expected`, from the cache or else from `path`
+    async fn fetch(&self, path: &str, what: &str, expected: &str) -> Result<Vec<u8>> {
+        if let Some(data) = self.cache.as_ref().and_then(|cache| cache.get(expected)) {
+            debug!("Using cached {} (sha256:{})", what, expected);
+            return Ok(data);
+        }
+        
+        let data = self.send(self.request(Method::GET, path)).await?.bytes().await?.to_vec();
+        check_digest(what, expected, &data)?;
+        
+        if let Some(cache) = &self.cache {
+            cache.put(&data)?;
+        }
+        Ok(data)
+    }
+    
+    /// Upload a file for a later commit, returning its sha256
+    pub async fn upload(&self, data: Vec<u8>) -> Result<String> {
+        let digest = sha256_hex(&data);
+        let request = self
+            .request(Method::PUT, &format!("/v1/uploads/{}", digest))
+            .header("Content-Type", "application/octet-stream")
+            .body(data);
+        
+        self.send(request).await?;
+        Ok(digest)
+    }
+    
+    /// Publish `name:tag` from files already uploaded
+    pub async fn commit(&self, descriptor: &ImageDescriptor) -> Result<()> {
+        validate_repository(&descriptor.name)?;
+        validate_name(&descriptor.tag)?;
+        let path = format!("/v1/images/{}/{}", url_name(&descriptor.name), descriptor.tag);
+        self.send(self.request(Method::PUT, &path).json(descriptor)).await?;
+        Ok(())
+    }
+    
+    /// Upload an image's files and commit them as `name:tag`
+    pub async fn push(
+        &self,
+        name: &str,
+        tag: &str,
+        wasm: Vec<u8>,
+        manifest: Option<Vec<u8>>,
+        signature: Option<ImageSignature>,
+    ) -> Result<ImageDescriptor> {
+        let descriptor = ImageDescriptor::describe(name, tag, &wasm, manifest.as_deref(), signature);
+        
+        self.upload(wasm).await?;
+        if let Some(manifest) = manifest {
+            self.upload(manifest).await?;
+        }
+        self.commit(&descriptor).await?;
+        
+        Ok(descriptor)
+    }
+}
@@ -0,0 +1,45 @@
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, RegistryError>;
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    
+    #[error("Not found: {0}")]
+    NotFound(String),
+    
+    #[error("Invalid image name or tag: {0}")]
+    InvalidReference(String),
+    
+    #[error("Digest mismatch for {what}: expected sha256:{expected}, got sha256:{actual}")]
+    DigestMismatch { what: String, expected: String, actual: String },
+    
+    #[error("Registry returned {status}: {message}")]
+    Api { status: u16, message: String },
+    
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl RegistryError {
+    /// Map an unsuccessful response to an error, using the server's `error` message when present
+    pub(crate) fn from_response(status: reqwest::StatusCode, body: &str) -> Self {
+        let message = serde_json::from_str::<crate::protocol::ErrorBody>(body)
+            .map(|body| body.error)
+            .unwrap_or_else(|_| body.trim().to_string());
+        
+        match status.as_u16() {
+            401 | 403 => RegistryError::Unauthorized(message),
+            404 => RegistryError::NotFound(message),
+            status => RegistryError::Api { status, message },
+        }
+    }
+}
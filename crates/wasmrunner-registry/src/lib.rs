@@ -0,0 +1,19 @@
+//! Image registry protocol, client and server
+//!
+//! A registry serves images from a local `ImageStore` over HTTP. Pushes
+//! upload the module and manifest by digest to `/v1/uploads`, then commit an
+//! `ImageDescriptor` naming them; pulls fetch the descriptor and check the
+//! downloaded files against its digests. Clients can keep pulled files in a
+//! `BlobCache` so the same digest is only downloaded once per host.
+
+pub mod cache;
+pub mod client;
+pub mod error;
+pub mod protocol;
+pub mod server;
+
+pub use cache::{BlobCache, GcStats};
+pub use client::{PulledImage, RegistryClient};
+pub use error::{RegistryError, Result};
+pub use protocol::{ImageDescriptor, Repository};
+pub use server::ServerConfig;
@@ -0,0 +1,100 @@
+use crate::error::{RegistryError, Result};
+use serde::{Deserialize, Serialize};
+use wasmrunner_core::signing::{sha256_hex, ImageSignature};
+
+/// Value of `api` in the response to `GET /v1/`
+pub const API_VERSION: &str = "wasmrunner-registry/v1";
+
+/// Longest image name or tag a registry accepts
+const MAX_NAME_LEN: usize = 128;
+
+/// An image as served by `GET /v1/images/{name}/{tag}` and committed by a push
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageDescriptor {
+    pub name: String,
+    pub tag: String,
+    pub wasm_sha256: String,
+    pub wasm_size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ImageSignature>,
+}
+
+impl ImageDescriptor {
+    pub fn describe(
+        name: &str,
+        tag: &str,
+        wasm: &[u8],
+        manifest: Option<&[u8]>,
+        signature: Option<ImageSignature>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            tag: tag.to_string(),
+            wasm_sha256: sha256_hex(wasm),
+            wasm_size: wasm.len() as u64,
+            manifest_sha256: manifest.map(sha256_hex),
+            signature,
+        }
+    }
+}
+
+/// An image name and its tags, as listed by `GET /v1/images`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Repository {
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+/// Body of every unsuccessful response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// Check that an image name is one or more safe path segments separated by `/`
+///
+/// Namespaced names such as `team/app` travel in URLs with the `/` escaped as
+/// `%2F`; see [`url_name`].
+pub fn validate_repository(name: &str) -> Result<()> {
+    name.split('/')
+        .try_for_each(validate_name)
+        .map_err(|_| RegistryError::InvalidReference(name.to_string()))
+}
+
+/// `name` as a single URL path segment
+pub fn url_name(name: &str) -> String {
+    name.replace('/', "%2F")
+}
+
+/// Check that an image name or tag is safe to use as a single path segment
+///
+/// Both must start with a letter or digit and contain only letters, digits,
+/// `.`, `_` and `-`.
+pub fn validate_name(value: &str) -> Result<()> {
+    let mut chars = value.chars();
+    let valid = value.len() <= MAX_NAME_LEN
+        && chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    
+    if valid {
+        Ok(())
+    } else {
+        Err(RegistryError::InvalidReference(value.to_string()))
+    }
+}
+
+/// Check that `data` has the sha256 `expected`
+pub fn check_digest(what: &str, expected: &str, data: &[u8]) -> Result<()> {
+    let actual = sha256_hex(data);
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(RegistryError::DigestMismatch {
+            what: what.to_string(),
+            expected: expected.to_string(),
+            actual,
+        })
+    }
+}
@@ -0,0 +1,318 @@
+use crate::client::RegistryClient;
+use crate::error::RegistryError;
+use crate::protocol::{
+    check_digest, validate_name, validate_repository, ErrorBody, ImageDescriptor, Repository, API_VERSION,
+};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+use wasmrunner_core::error::WasmRunnerError;
+use wasmrunner_core::image::{Image, ImageStore};
+
+/// Directory under the image store root holding uploads not yet committed
+const UPLOADS_DIR: &str = ".uploads";
+
+/// Largest file a push may upload
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 100 * 1024 * 1024;
+
+/// How a registry server authenticates requests
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Bearer tokens accepted for every request
+    pub tokens: Vec<String>,
+    
+    /// Allow pulls without a token; pushes always need one
+    pub anonymous_pull: bool,
+    pub max_upload_bytes: usize,
+    
+    /// Registry to fetch images from when they are not stored locally
+    pub upstream: Option<RegistryClient>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            tokens: Vec::new(),
+            anonymous_pull: false,
+            max_upload_bytes: DEFAULT_MAX_UPLOAD_BYTES,
+            upstream: None,
+        }
+    }
+}
+
+struct ServerState {
+    store: ImageStore,
+    uploads: PathBuf,
+    config: ServerConfig,
+}
+
+/// Routes serving `store` under `config`
+pub fn router(store: ImageStore, config: ServerConfig) -> std::io::Result<Router> {
+    let uploads = store.root().join(UPLOADS_DIR);
+    std::fs::create_dir_all(&uploads)?;
+    
+    let max_upload_bytes = config.max_upload_bytes;
+    let state = Arc::new(ServerState { store, uploads, config });
+    
+    Ok(Router::new()
+        .route("/v1/", get(api_version))
+        .route("/v1/images", get(list_images))
+        .route("/v1/images/:name/:tag", get(describe_image).put(commit_image))
+        .route("/v1/images/:name/:tag/wasm", get(image_wasm))
+        .route("/v1/images/:name/:tag/manifest", get(image_manifest))
+        .route("/v1/uploads/:digest", put(upload))
+        .layer(DefaultBodyLimit::max(max_upload_bytes))
+        .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .with_state(state))
+}
+
+/// Serve `store` on `listener` until the process is stopped
+pub async fn serve(listener: TcpListener, store: ImageStore, config: ServerConfig) -> std::io::Result<()> {
+    if config.tokens.is_empty() {
+        warn!("No registry tokens configured; only anonymous pulls can succeed");
+    }
+    info!("Serving {} on {}", store.root().display(), listener.local_addr()?);
+    
+    axum::serve(listener, router(store, config)?).await
+}
+
+/// Error response carrying an `ErrorBody`
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(ErrorBody { error: self.1 })).into_response()
+    }
+}
+
+impl From<RegistryError> for ApiError {
+    fn from(error: RegistryError) -> Self {
+        let status = match &error {
+            RegistryError::InvalidReference(_) | RegistryError::DigestMismatch { .. } => StatusCode::BAD_REQUEST,
+            RegistryError::NotFound(_) => StatusCode::NOT_FOUND,
+            RegistryError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, error.to_string())
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast_ref::<WasmRunnerError>() {
+            Some(WasmRunnerError::ImageNotFound(image)) => {
+                ApiError(StatusCode::NOT_FOUND, format!("Image not found: {}", image))
+            }
+            _ => ApiError(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(error: std::io::Error) -> Self {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+async fn authenticate(State(state): State<Arc<ServerState>>, request: Request, next: Next) -> Response {
+    let is_pull = matches!(*request.method(), Method::GET | Method::HEAD);
+    if is_pull && state.config.anonymous_pull {
+        return next.run(request).await;
+    }
+    
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    
+    match token {
+        Some(token) if state.config.tokens.iter().any(|known| constant_time_eq(known, token)) => {
+            next.run(request).await
+        }
+        Some(_) => ApiError(StatusCode::UNAUTHORIZED, "Invalid registry token".to_string()).into_response(),
+        None => ApiError(StatusCode::UNAUTHORIZED, "Registry token required".to_string()).into_response(),
+    }
+}
+
+/// Compare tokens without leaking how much of a guess matched
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn api_version() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "api": API_VERSION }))
+}
+
+async fn list_images(State(state): State<Arc<ServerState>>) -> ApiResult<Json<Vec<Repository>>> {
+    let mut repositories: Vec<Repository> = Vec::new();
+    for image in state.store.list()? {
+        match repositories.last_mut() {
+            Some(repository) if repository.name == image.name => repository.tags.push(image.tag),
+            _ => repositories.push(Repository { name: image.name, tags: vec![image.tag] }),
+        }
+    }
+    Ok(Json(repositories))
+}
+
+/// The stored image `name:tag`, first fetching it from the upstream registry if there is one
+///
+/// Fetched images are kept, so a tag is only fetched once; later changes to
+/// it upstream are not seen.
+async fn resolve(state: &ServerState, name: &str, tag: &str) -> ApiResult<Image> {
+    validate_repository(name)?;
+    validate_name(tag)?;
+    
+    let upstream = match (state.store.get(name, tag), &state.config.upstream) {
+        (Ok(image), _) => return Ok(image),
+        (Err(_), Some(upstream)) => upstream,
+        (Err(e), None) => return Err(e.into()),
+    };
+    
+    info!("Fetching {}:{} from {}", name, tag, upstream.base_url());
+    let pulled = upstream.pull(name, tag).await.map_err(|e| match e {
+        RegistryError::NotFound(message) => ApiError(StatusCode::NOT_FOUND, message),
+        e => ApiError(StatusCode::BAD_GATEWAY, format!("Upstream registry: {}", e)),
+    })?;
+    let image = state.store.import(name, tag, &pulled.wasm, pulled.manifest.as_deref())?;
+    if let Some(signature) = &pulled.descriptor.signature {
+        image.save_signature(signature)?;
+    }
+    Ok(image)
+}
+
+async fn describe_image(
+    State(state): State<Arc<ServerState>>,
+    Path((name, tag)): Path<(String, String)>,
+) -> ApiResult<Json<ImageDescriptor>> {
+    let image = resolve(&state, &name, &tag).await?;
+    let descriptor = tokio::task::spawn_blocking(move || -> ApiResult<ImageDescriptor> {
+        let wasm = image.read_wasm()?;
+        let manifest = image.read_manifest_bytes()?;
+        Ok(ImageDescriptor::describe(&name, &tag, &wasm, manifest.as_deref(), image.signature()?))
+    })
+    .await
+    .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+    
+    Ok(Json(descriptor))
+}
+
+async fn image_wasm(
+    State(state): State<Arc<ServerState>>,
+    Path((name, tag)): Path<(String, String)>,
+) -> ApiResult<Response> {
+    let image = resolve(&state, &name, &tag).await?;
+    let wasm = tokio::fs::read(image.wasm_path()).await?;
+    Ok(([(header::CONTENT_TYPE, "application/wasm")], wasm).into_response())
+}
+
+async fn image_manifest(
+    State(state): State<Arc<ServerState>>,
+    Path((name, tag)): Path<(String, String)>,
+) -> ApiResult<Response> {
+    let image = resolve(&state, &name, &tag).await?;
+    match tokio::fs::read(image.manifest_path()).await {
+        Ok(manifest) => Ok(([(header::CONTENT_TYPE, "application/json")], manifest).into_response()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(ApiError(StatusCode::NOT_FOUND, format!("{}:{} has no manifest", name, tag)))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Path of an upload, once `digest` is known to be a sha256 hex string
+fn upload_path(state: &ServerState, digest: &str) -> ApiResult<PathBuf> {
+    if digest.len() != 64 || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ApiError(StatusCode::BAD_REQUEST, format!("Invalid sha256 digest: {}", digest)));
+    }
+    Ok(state.uploads.join(digest.to_ascii_lowercase()))
+}
+
+async fn upload(
+    State(state): State<Arc<ServerState>>,
+    Path(digest): Path<String>,
+    body: Bytes,
+) -> ApiResult<StatusCode> {
+    let path = upload_path(&state, &digest)?;
+    check_digest("upload", &digest, &body)?;
+    
+    // Write beside the final path so a reader never sees a partial upload
+    let partial = path.with_extension(format!("partial-{}", std::process::id()));
+    tokio::fs::write(&partial, &body).await?;
+    tokio::fs::rename(&partial, &path).await?;
+    
+    Ok(StatusCode::CREATED)
+}
+
+async fn commit_image(
+    State(state): State<Arc<ServerState>>,
+    Path((name, tag)): Path<(String, String)>,
+    Json(descriptor): Json<ImageDescriptor>,
+) -> ApiResult<(StatusCode, Json<ImageDescriptor>)> {
+    validate_repository(&name)?;
+    validate_name(&tag)?;
+    if descriptor.name != name || descriptor.tag != tag {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            format!("Descriptor is for {}:{}, not {}:{}", descriptor.name, descriptor.tag, name, tag),
+        ));
+    }
+    
+    let wasm_path = upload_path(&state, &descriptor.wasm_sha256)?;
+    let manifest_path = descriptor
+        .manifest_sha256
+        .as_deref()
+        .map(|digest| upload_path(&state, digest))
+        .transpose()?;
+    
+    let committed = tokio::task::spawn_blocking(move || -> ApiResult<ImageDescriptor> {
+        let read_upload = |path: &PathBuf| {
+            std::fs::read(path).map_err(|_| {
+                ApiError(
+                    StatusCode::BAD_REQUEST,
+                    format!("{} was not uploaded", path.file_name().unwrap_or_default().to_string_lossy()),
+                )
+            })
+        };
+        
+        let wasm = read_upload(&wasm_path)?;
+        let manifest = manifest_path.as_ref().map(read_upload).transpose()?;
+        
+        // Uploads were checked on arrival, but may have been replaced since
+        check_digest("module", &descriptor.wasm_sha256, &wasm)?;
+        if wasm.len() as u64 != descriptor.wasm_size {
+            return Err(ApiError(
+                StatusCode::BAD_REQUEST,
+                format!("Module is {} bytes, descriptor says {}", wasm.len(), descriptor.wasm_size),
+            ));
+        }
+        
+        let image = state.store.import(&name, &tag, &wasm, manifest.as_deref())?;
+        if let Some(signature) = &descriptor.signature {
+            image.save_signature(signature)?;
+        }
+        
+        // Uploads are only needed until the image that uses them is committed
+        let _ = std::fs::remove_file(&wasm_path);
+        if let Some(path) = &manifest_path {
+            let _ = std::fs::remove_file(path);
+        }
+        
+        info!("Committed {}:{} (sha256:{})", name, tag, descriptor.wasm_sha256);
+        Ok(descriptor)
+    })
+    .await
+    .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+    
+    Ok((StatusCode::CREATED, Json(committed)))
+}
@@ -0,0 +1,143 @@
+use wasmrunner_core::image::ImageStore;
+use wasmrunner_registry::server::{self, ServerConfig};
+use wasmrunner_registry::{BlobCache, RegistryClient, RegistryError};
+
+const TOKEN: &str = "registry-token";
+
+/// Minimal valid WASM module header
+const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+fn temp_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("wasmrunner-registry-{}", uuid::Uuid::new_v4()))
+}
+
+async fn start(anonymous_pull: bool) -> (RegistryClient, ImageStore) {
+    start_with(ServerConfig {
+        tokens: vec![TOKEN.to_string()],
+        anonymous_pull,
+        ..Default::default()
+    })
+    .await
+}
+
+async fn start_with(config: ServerConfig) -> (RegistryClient, ImageStore) {
+    let root = temp_dir();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(server::serve(listener, ImageStore::with_root(&root).unwrap(), config));
+    
+    (RegistryClient::new(url), ImageStore::with_root(&root).unwrap())
+}
+
+#[tokio::test]
+async fn push_then_pull_round_trips() {
+    let (client, store) = start(false).await;
+    let client = client.with_token(TOKEN);
+    let manifest = br#"{"name":"hello"}"#.to_vec();
+    
+    let pushed = client
+        .push("hello", "1.0", WASM.to_vec(), Some(manifest.clone()), None)
+        .await
+        .unwrap();
+    assert!(store.resolve("hello:1.0").is_ok());
+    
+    let pulled = client.pull("hello", "1.0").await.unwrap();
+    assert_eq!(pulled.descriptor, pushed);
+    assert_eq!(pulled.wasm, WASM);
+    assert_eq!(pulled.manifest, Some(manifest));
+    
+    let repositories = client.list().await.unwrap();
+    assert_eq!(repositories[0].name, "hello");
+    assert_eq!(repositories[0].tags, vec!["1.0".to_string()]);
+}
+
+#[tokio::test]
+async fn namespaced_names_round_trip() {
+    let (client, store) = start(false).await;
+    let client = client.with_token(TOKEN);
+    
+    client.push("team/hello", "1.0", WASM.to_vec(), None, None).await.unwrap();
+    assert!(store.resolve("team/hello:1.0").is_ok());
+    
+    let pulled = client.pull("team/hello", "1.0").await.unwrap();
+    assert_eq!(pulled.descriptor.name, "team/hello");
+    assert_eq!(client.list().await.unwrap()[0].name, "team/hello");
+    
+    let digest = pulled.descriptor.wasm_sha256;
+    assert_eq!(store.resolve(&format!("team/hello@sha256:{}", digest)).unwrap().tag, "1.0");
+    assert!(store.resolve(&format!("team/hello:1.0@sha256:{}", "0".repeat(64))).is_err());
+}
+
+#[tokio::test]
+async fn requests_without_token_are_rejected() {
+    let (client, _) = start(false).await;
+    
+    assert!(matches!(client.ping().await, Err(RegistryError::Unauthorized(_))));
+    
+    let wrong = client.clone().with_token("guess");
+    let err = wrong.push("hello", "1.0", WASM.to_vec(), None, None).await.unwrap_err();
+    assert!(matches!(err, RegistryError::Unauthorized(_)));
+}
+
+#[tokio::test]
+async fn anonymous_pull_still_requires_token_to_push() {
+    let (client, _) = start(true).await;
+    
+    client.ping().await.unwrap();
+    let err = client.push("hello", "1.0", WASM.to_vec(), None, None).await.unwrap_err();
+    assert!(matches!(err, RegistryError::Unauthorized(_)));
+    
+    let err = client.pull("missing", "latest").await.unwrap_err();
+    assert!(matches!(err, RegistryError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn commit_requires_uploaded_files() {
+    let (client, _) = start(false).await;
+    let client = client.with_token(TOKEN);
+    
+    let descriptor = wasmrunner_registry::ImageDescriptor::describe("hello", "1.0", WASM, None, None);
+    let err = client.commit(&descriptor).await.unwrap_err();
+    
+    assert!(matches!(err, RegistryError::Api { status: 400, .. }));
+}
+
+#[tokio::test]
+async fn mirror_pulls_through_from_upstream() {
+    let (upstream, _) = start(false).await;
+    let upstream = upstream.with_token(TOKEN);
+    upstream.push("hello", "1.0", WASM.to_vec(), None, None).await.unwrap();
+    
+    let (mirror, mirror_store) = start_with(ServerConfig {
+        anonymous_pull: true,
+        upstream: Some(upstream.clone()),
+        ..Default::default()
+    })
+    .await;
+    
+    let pulled = mirror.pull("hello", "1.0").await.unwrap();
+    assert_eq!(pulled.wasm, WASM);
+    assert!(mirror_store.resolve("hello:1.0").is_ok());
+    
+    let err = mirror.pull("missing", "1.0").await.unwrap_err();
+    assert!(matches!(err, RegistryError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn cached_pulls_work_offline_and_gc_evicts() {
+    let (client, _) = start(false).await;
+    let cache = BlobCache::new(temp_dir(), 1024 * 1024).unwrap();
+    let client = client.with_token(TOKEN).with_cache(cache.clone());
+    
+    let pushed = client.push("hello", "1.0", WASM.to_vec(), None, None).await.unwrap();
+    client.pull("hello", "1.0").await.unwrap();
+    
+    let cached = client.pull_cached(&pushed.wasm_sha256).unwrap();
+    assert_eq!(cached.descriptor, pushed);
+    assert_eq!(cached.wasm, WASM);
+    
+    let stats = cache.gc_to(0).unwrap();
+    assert!(stats.removed > 0);
+    assert_eq!(stats.remaining_bytes, 0);
+    assert!(client.pull_cached(&pushed.wasm_sha256).is_err());
+}
@@ -0,0 +1,263 @@
+//! WASI files and directories that count themselves as open handles in
+//! [`GuestStats`], and sockets that also count the bytes through them
+
+use crate::stats::GuestStats;
+use std::any::Any;
+use std::io::{IoSlice, IoSliceMut, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Arc;
+use wasi_common::dir::{OpenResult, ReaddirCursor, ReaddirEntity};
+use wasi_common::file::{Advice, FdFlags, FileType, Filestat, OFlags, RiFlags, RoFlags, SdFlags, SiFlags};
+use wasi_common::{Error, SystemTimeSpec, WasiDir, WasiFile};
+
+/// One open handle, for as long as this lives
+struct Handle(Arc<GuestStats>);
+
+impl Handle {
+    fn open(stats: Arc<GuestStats>) -> Self {
+        stats.handle_opened();
+        Self(stats)
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.0.handle_closed();
+    }
+}
+
+/// A file, pipe or socket the guest holds
+pub(crate) struct CountedFile {
+    inner: Box<dyn WasiFile>,
+    
+    /// Whether reads and writes are network traffic
+    socket: bool,
+    handle: Handle,
+}
+
+impl CountedFile {
+    pub(crate) fn new(inner: Box<dyn WasiFile>, stats: Arc<GuestStats>) -> Box<dyn WasiFile> {
+        Box::new(Self { inner, socket: false, handle: Handle::open(stats) })
+    }
+    
+    pub(crate) fn socket(inner: Box<dyn WasiFile>, stats: Arc<GuestStats>) -> Box<dyn WasiFile> {
+        Box::new(Self { inner, socket: true, handle: Handle::open(stats) })
+    }
+    
+    fn received(&self, bytes: u64) {
+        if self.socket {
+            self.handle.0.received(bytes);
+        }
+    }
+    
+    fn sent(&self, bytes: u64) {
+        if self.socket {
+            self.handle.0.sent(bytes);
+        }
+    }
+}
+
+// Everything is passed through; `as_any` too, so downcasts see the wrapped file
+#[async_trait::async_trait]
+impl WasiFile for CountedFile {
+    fn as_any(&self) -> &dyn Any {
+        self.inner.as_any()
+    }
+    
+    async fn get_filetype(&self) -> Result<FileType, Error> {
+        self.inner.get_filetype().await
+    }
+    
+    #[cfg(unix)]
+    fn pollable(&self) -> Option<std::os::fd::BorrowedFd> {
+        self.inner.pollable()
+    }
+    
+    fn isatty(&self) -> bool {
+        self.inner.isatty()
+    }
+    
+    async fn sock_accept(&self, fdflags: FdFlags) -> Result<Box<dyn WasiFile>, Error> {
+        let stream = self.inner.sock_accept(fdflags).await?;
+        Ok(CountedFile::socket(stream, self.handle.0.clone()))
+    }
+    
+    async fn sock_recv<'a>(&self, ri_data: &mut [IoSliceMut<'a>], ri_flags: RiFlags) -> Result<(u64, RoFlags), Error> {
+        let (bytes, flags) = self.inner.sock_recv(ri_data, ri_flags).await?;
+        self.received(bytes);
+        Ok((bytes, flags))
+    }
+    
+    async fn sock_send<'a>(&self, si_data: &[IoSlice<'a>], si_flags: SiFlags) -> Result<u64, Error> {
+        let bytes = self.inner.sock_send(si_data, si_flags).await?;
+        self.sent(bytes);
+        Ok(bytes)
+    }
+    
+    async fn sock_shutdown(&self, how: SdFlags) -> Result<(), Error> {
+        self.inner.sock_shutdown(how).await
+    }
+    
+    async fn datasync(&self) -> Result<(), Error> {
+        self.inner.datasync().await
+    }
+    
+    async fn sync(&self) -> Result<(), Error> {
+        self.inner.sync().await
+    }
+    
+    async fn get_fdflags(&self) -> Result<FdFlags, Error> {
+        self.inner.get_fdflags().await
+    }
+    
+    async fn set_fdflags(&mut self, flags: FdFlags) -> Result<(), Error> {
+        self.inner.set_fdflags(flags).await
+    }
+    
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        self.inner.get_filestat().await
+    }
+    
+    async fn set_filestat_size(&self, size: u64) -> Result<(), Error> {
+        self.inner.set_filestat_size(size).await
+    }
+    
+    async fn advise(&self, offset: u64, len: u64, advice: Advice) -> Result<(), Error> {
+        self.inner.advise(offset, len, advice).await
+    }
+    
+    async fn set_times(&self, atime: Option<SystemTimeSpec>, mtime: Option<SystemTimeSpec>) -> Result<(), Error> {
+        self.inner.set_times(atime, mtime).await
+    }
+    
+    async fn read_vectored<'a>(&self, bufs: &mut [IoSliceMut<'a>]) -> Result<u64, Error> {
+        let bytes = self.inner.read_vectored(bufs).await?;
+        self.received(bytes);
+        Ok(bytes)
+    }
+    
+    async fn read_vectored_at<'a>(&self, bufs: &mut [IoSliceMut<'a>], offset: u64) -> Result<u64, Error> {
+        self.inner.read_vectored_at(bufs, offset).await
+    }
+    
+    async fn write_vectored<'a>(&self, bufs: &[IoSlice<'a>]) -> Result<u64, Error> {
+        let bytes = self.inner.write_vectored(bufs).await?;
+        self.sent(bytes);
+        Ok(bytes)
+    }
+    
+    async fn write_vectored_at<'a>(&self, bufs: &[IoSlice<'a>], offset: u64) -> Result<u64, Error> {
+        self.inner.write_vectored_at(bufs, offset).await
+    }
+    
+    async fn seek(&self, pos: SeekFrom) -> Result<u64, Error> {
+        self.inner.seek(pos).await
+    }
+    
+    async fn peek(&self, buf: &mut [u8]) -> Result<u64, Error> {
+        self.inner.peek(buf).await
+    }
+    
+    fn num_ready_bytes(&self) -> Result<u64, Error> {
+        self.inner.num_ready_bytes()
+    }
+    
+    async fn readable(&self) -> Result<(), Error> {
+        self.inner.readable().await
+    }
+    
+    async fn writable(&self) -> Result<(), Error> {
+        self.inner.writable().await
+    }
+}
+
+/// A directory the guest holds, whose files and subdirectories are counted too
+pub(crate) struct CountedDir {
+    inner: Box<dyn WasiDir>,
+    handle: Handle,
+}
+
+impl CountedDir {
+    pub(crate) fn new(inner: Box<dyn WasiDir>, stats: Arc<GuestStats>) -> Box<dyn WasiDir> {
+        Box::new(Self { inner, handle: Handle::open(stats) })
+    }
+}
+
+// `as_any` exposes the wrapped directory, which `rename` and `hard_link`
+// downcast their destination to
+#[async_trait::async_trait]
+impl WasiDir for CountedDir {
+    fn as_any(&self) -> &dyn Any {
+        self.inner.as_any()
+    }
+    
+    async fn open_file(
+        &self,
+        symlink_follow: bool,
+        path: &str,
+        oflags: OFlags,
+        read: bool,
+        write: bool,
+        fdflags: FdFlags,
+    ) -> Result<OpenResult, Error> {
+        let stats = self.handle.0.clone();
+        
+        Ok(match self.inner.open_file(symlink_follow, path, oflags, read, write, fdflags).await? {
+            OpenResult::File(file) => OpenResult::File(CountedFile::new(file, stats)),
+            OpenResult::Dir(dir) => OpenResult::Dir(CountedDir::new(dir, stats)),
+        })
+    }
+    
+    async fn create_dir(&self, path: &str) -> Result<(), Error> {
+        self.inner.create_dir(path).await
+    }
+    
+    async fn readdir(
+        &self,
+        cursor: ReaddirCursor,
+    ) -> Result<Box<dyn Iterator<Item = Result<ReaddirEntity, Error>> + Send>, Error> {
+        self.inner.readdir(cursor).await
+    }
+    
+    async fn symlink(&self, old_path: &str, new_path: &str) -> Result<(), Error> {
+        self.inner.symlink(old_path, new_path).await
+    }
+    
+    async fn remove_dir(&self, path: &str) -> Result<(), Error> {
+        self.inner.remove_dir(path).await
+    }
+    
+    async fn unlink_file(&self, path: &str) -> Result<(), Error> {
+        self.inner.unlink_file(path).await
+    }
+    
+    async fn read_link(&self, path: &str) -> Result<PathBuf, Error> {
+        self.inner.read_link(path).await
+    }
+    
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        self.inner.get_filestat().await
+    }
+    
+    async fn get_path_filestat(&self, path: &str, follow_symlinks: bool) -> Result<Filestat, Error> {
+        self.inner.get_path_filestat(path, follow_symlinks).await
+    }
+    
+    async fn rename(&self, path: &str, dest_dir: &dyn WasiDir, dest_path: &str) -> Result<(), Error> {
+        self.inner.rename(path, dest_dir, dest_path).await
+    }
+    
+    async fn hard_link(&self, path: &str, target_dir: &dyn WasiDir, target_path: &str) -> Result<(), Error> {
+        self.inner.hard_link(path, target_dir, target_path).await
+    }
+    
+    async fn set_times(
+        &self,
+        path: &str,
+        atime: Option<SystemTimeSpec>,
+        mtime: Option<SystemTimeSpec>,
+        follow_symlinks: bool,
+    ) -> Result<(), Error> {
+        self.inner.set_times(path, atime, mtime, follow_symlinks).await
+    }
+}
@@ -6,9 +6,18 @@ pub mod wasmtime_runtime;
 pub mod wasmer_runtime;
 pub mod loader;
 pub mod validator;
+pub mod stdio;
+pub mod stats;
+mod handles;
 
 pub use manager::RuntimeManager;
+pub use stats::GuestStats;
+pub use stdio::StdioConfig;
 
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use wasmrunner_core::Result;
 
 /// Trait for WASM runtime implementations
@@ -18,13 +27,65 @@ pub trait WasmRuntime {
     
     /// Get runtime name
     fn name(&self) -> &str;
+    
+    /// Keep compiled modules in `dir`, reusing them when the same module is loaded again
+    fn set_compile_cache(&mut self, dir: PathBuf);
+    
+    /// Count the instructions guests of modules loaded after this execute, at a cost in speed
+    fn set_fuel_metering(&mut self, enabled: bool) -> Result<()>;
 }
 
 /// Trait for WASM module instances
 pub trait WasmModule {
-    /// Execute the module with given arguments
-    fn execute(&mut self, args: Vec<String>) -> Result<i32>;
+    /// Execute the module with given arguments and standard I/O
+    fn execute(&mut self, args: Vec<String>, stdio: StdioConfig) -> Result<i32>;
     
     /// Get module exports
     fn exports(&self) -> Vec<String>;
+    
+    /// Limit the linear memory instances of this module may grow to
+    fn set_memory_limit(&mut self, bytes: u64);
+    
+    /// Trap instances of this module still running `timeout` after they were created
+    fn set_timeout(&mut self, timeout: Duration);
+    
+    /// Environment variables, directories and sockets given to the guest on `execute`
+    fn set_environment(&mut self, environment: GuestEnvironment);
+    
+    /// Resource usage of this module's instances, updated while they run
+    fn stats(&self) -> Arc<GuestStats>;
+    
+    /// Whether loading reused compiled code from the compile cache
+    fn compiled_from_cache(&self) -> bool;
+    
+    /// Get the signature of an exported function
+    fn signature(&mut self, export: &str) -> Result<FuncSignature>;
+    
+    /// Call an exported function, parsing arguments from text and formatting results
+    fn call(&mut self, export: &str, args: &[String]) -> Result<Vec<String>>;
+}
+
+/// What a guest is given besides its arguments and standard I/O
+#[derive(Debug, Default)]
+pub struct GuestEnvironment {
+    pub variables: Vec<(String, String)>,
+    
+    /// Host directories preopened at guest paths, from file descriptor 3 on
+    pub volumes: Vec<(PathBuf, String)>,
+    
+    /// Listening sockets preopened after the volumes
+    pub listeners: Vec<TcpListener>,
+}
+
+/// Parameter and result types of an exported function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuncSignature {
+    pub params: Vec<String>,
+    pub results: Vec<String>,
+}
+
+impl std::fmt::Display for FuncSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}) -> ({})", self.params.join(", "), self.results.join(", "))
+    }
 }
@@ -2,6 +2,8 @@
 use crate::{WasmRuntime, wasmtime_runtime::WasmtimeRuntime};
 use wasmrunner_core::Result;
 use std::collections::HashMap;
+use std::sync::Arc;
+use wasmrunner_plugins::{CapabilitySet, PluginHost};
 
 pub struct RuntimeManager {
     runtimes: HashMap<String, Box<dyn WasmRuntime>>,
@@ -22,6 +24,21 @@ impl RuntimeManager {
         })
     }
     
+    /// Create a manager whose runtimes link plugin host functions and the
+    /// `imports` component plugins
+    pub fn with_plugins(
+        host: Arc<PluginHost>,
+        capabilities: CapabilitySet,
+        imports: Vec<String>,
+    ) -> Result<Self> {
+        let mut manager = Self::new()?;
+        manager.runtimes.insert(
+            "wasmtime".to_string(),
+            Box::new(WasmtimeRuntime::with_plugins(host, capabilities, imports)?),
+        );
+        Ok(manager)
+    }
+    
     pub fn get_runtime(&mut self, name: Option<&str>) -> Result<&mut dyn WasmRuntime> {
         let runtime_name = name.unwrap_or(&self.default_runtime);
         
@@ -0,0 +1,121 @@
+//! Resource usage of guest instances, measured inside the runtime
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use wasmrunner_core::container::ResourceUsage;
+
+/// Counters a module's instances update while they run
+///
+/// Several containers can share one process, so usage is counted per
+/// instance by the runtime rather than read from the OS. A module shares one
+/// set of counters across executions; each execution starts them afresh.
+#[derive(Debug, Default)]
+pub struct GuestStats {
+    memory_bytes: AtomicU64,
+    fuel_consumed: AtomicU64,
+    fuel_metered: AtomicBool,
+    open_handles: AtomicU64,
+    net_rx_bytes: AtomicU64,
+    net_tx_bytes: AtomicU64,
+    cpu_time_us: AtomicU64,
+    started: Mutex<Option<Instant>>,
+    
+    /// CPU time of the thread running the instance when it was created
+    cpu_started: Mutex<Option<Duration>>,
+}
+
+impl GuestStats {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+    
+    /// Start counting for a new instance, which runs on the calling thread
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.memory_bytes,
+            &self.fuel_consumed,
+            &self.open_handles,
+            &self.net_rx_bytes,
+            &self.net_tx_bytes,
+            &self.cpu_time_us,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        self.fuel_metered.store(false, Ordering::Relaxed);
+        *self.started.lock().unwrap() = Some(Instant::now());
+        *self.cpu_started.lock().unwrap() = thread_cpu_time();
+    }
+    
+    /// Record the CPU time used so far; called on the thread running the instance
+    pub(crate) fn record_cpu_time(&self) {
+        let started = *self.cpu_started.lock().unwrap();
+        if let (Some(started), Some(now)) = (started, thread_cpu_time()) {
+            let used = now.saturating_sub(started).as_micros() as u64;
+            self.cpu_time_us.store(used, Ordering::Relaxed);
+        }
+    }
+    
+    /// Linear memory grew from `current` to `desired` bytes
+    pub(crate) fn memory_grown(&self, current: usize, desired: usize) {
+        self.memory_bytes.fetch_add((desired - current) as u64, Ordering::Relaxed);
+    }
+    
+    pub(crate) fn set_fuel_consumed(&self, fuel: u64) {
+        self.fuel_consumed.store(fuel, Ordering::Relaxed);
+        self.fuel_metered.store(true, Ordering::Relaxed);
+    }
+    
+    pub(crate) fn handle_opened(&self) {
+        self.open_handles.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    pub(crate) fn handle_closed(&self) {
+        self.open_handles.fetch_sub(1, Ordering::Relaxed);
+    }
+    
+    pub(crate) fn received(&self, bytes: u64) {
+        self.net_rx_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+    
+    pub(crate) fn sent(&self, bytes: u64) {
+        self.net_tx_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+    
+    /// Current usage of the running or most recent instance
+    pub fn usage(&self) -> ResourceUsage {
+        let started = *self.started.lock().unwrap();
+        
+        ResourceUsage {
+            memory_bytes: self.memory_bytes.load(Ordering::Relaxed),
+            fuel_consumed: self
+                .fuel_metered
+                .load(Ordering::Relaxed)
+                .then(|| self.fuel_consumed.load(Ordering::Relaxed)),
+            cpu_time_ms: self
+                .cpu_started
+                .lock()
+                .unwrap()
+                .map(|_| self.cpu_time_us.load(Ordering::Relaxed) / 1000),
+            uptime_secs: started.map_or(0, |started| started.elapsed().as_secs()),
+            open_handles: self.open_handles.load(Ordering::Relaxed),
+            net_rx_bytes: self.net_rx_bytes.load(Ordering::Relaxed),
+            net_tx_bytes: self.net_tx_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// CPU time used by the calling thread
+#[cfg(unix)]
+fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) } != 0 {
+        return None;
+    }
+    Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+#[cfg(not(unix))]
+fn thread_cpu_time() -> Option<Duration> {
+    None
+}
@@ -0,0 +1,168 @@
+
+//! Guest standard I/O wiring
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+use wasmrunner_core::Result;
+
+/// Source of guest stdin
+pub enum StdinMode {
+    /// Guest reads EOF immediately
+    Null,
+    /// Guest reads host stdin until it is closed
+    Inherit,
+    /// Guest reads from the given pipe
+    Pipe(Box<dyn Read + Send + Sync>),
+}
+
+/// Destination of guest stdout or stderr
+pub enum OutputMode {
+    Inherit,
+    Pipe(Box<dyn Write + Send + Sync>),
+}
+
+/// Standard I/O configuration for a single execution
+pub struct StdioConfig {
+    pub stdin: StdinMode,
+    pub stdout: OutputMode,
+    pub stderr: OutputMode,
+}
+
+impl Default for StdioConfig {
+    fn default() -> Self {
+        Self {
+            stdin: StdinMode::Null,
+            stdout: OutputMode::Inherit,
+            stderr: OutputMode::Inherit,
+        }
+    }
+}
+
+impl StdioConfig {
+    /// Inherit host stdio, forwarding stdin only when `interactive`
+    pub fn foreground(interactive: bool) -> Self {
+        Self {
+            stdin: if interactive { StdinMode::Inherit } else { StdinMode::Null },
+            ..Self::default()
+        }
+    }
+}
+
+/// Output sink shared by a detached guest's log file and attached clients
+#[derive(Clone)]
+struct Broadcast {
+    log: Arc<Mutex<File>>,
+    clients: Arc<Mutex<Vec<UnixStream>>>,
+}
+
+impl Write for Broadcast {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.log.lock().unwrap().write_all(buf)?;
+        
+        // Clients that went away are dropped rather than failing the guest
+        self.clients
+            .lock()
+            .unwrap()
+            .retain_mut(|client| client.write_all(buf).is_ok());
+        
+        Ok(buf.len())
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.log.lock().unwrap().flush()
+    }
+}
+
+/// Attach endpoint of a detached container
+///
+/// Output is appended to the log file and copied to every attached client.
+/// When the container is interactive, client input is forwarded to guest
+/// stdin, and a client closing its input closes guest stdin.
+pub struct AttachPoint {
+    output: Broadcast,
+    
+    /// Guest end of the stdin pipe, for interactive containers
+    guest_stdin: Option<UnixStream>,
+}
+
+impl AttachPoint {
+    pub fn bind(socket_path: &Path, log_path: &Path, interactive: bool) -> Result<(Self, StdioConfig)> {
+        // A stale socket from a previous run would make bind fail
+        let _ = std::fs::remove_file(socket_path);
+        let listener = UnixListener::bind(socket_path)?;
+        
+        let log = OpenOptions::new().create(true).append(true).open(log_path)?;
+        let output = Broadcast {
+            log: Arc::new(Mutex::new(log)),
+            clients: Arc::new(Mutex::new(Vec::new())),
+        };
+        
+        let (guest_stdin, host_stdin) = UnixStream::pair()?;
+        let clients = output.clients.clone();
+        
+        std::thread::spawn(move || {
+            for client in listener.incoming() {
+                let client = match client {
+                    Ok(client) => client,
+                    Err(e) => {
+                        warn!("Failed to accept attach connection: {}", e);
+                        continue;
+                    }
+                };
+                debug!("Client attached");
+                
+                if let Ok(writer) = client.try_clone() {
+                    clients.lock().unwrap().push(writer);
+                }
+                
+                if interactive {
+                    let host_stdin = match host_stdin.try_clone() {
+                        Ok(stdin) => stdin,
+                        Err(_) => continue,
+                    };
+                    std::thread::spawn(move || forward_input(client, host_stdin));
+                }
+            }
+        });
+        
+        let attach_point = Self {
+            output,
+            guest_stdin: interactive.then_some(guest_stdin),
+        };
+        let stdio = attach_point.stdio()?;
+        Ok((attach_point, stdio))
+    }
+    
+    /// Standard I/O for another run of the guest, e.g. after a restart
+    pub fn stdio(&self) -> Result<StdioConfig> {
+        Ok(StdioConfig {
+            stdin: match &self.guest_stdin {
+                Some(stdin) => StdinMode::Pipe(Box::new(stdin.try_clone()?)),
+                None => StdinMode::Null,
+            },
+            stdout: OutputMode::Pipe(Box::new(self.output.clone())),
+            stderr: OutputMode::Pipe(Box::new(self.output.clone())),
+        })
+    }
+    
+    /// Disconnect attached clients once the guest has exited
+    pub fn close(&self) {
+        for client in self.output.clients.lock().unwrap().drain(..) {
+            let _ = client.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+fn forward_input(mut client: UnixStream, mut host_stdin: UnixStream) {
+    if let Err(e) = std::io::copy(&mut client, &mut host_stdin) {
+        debug!("Attach input closed: {}", e);
+    }
+    
+    // Propagate EOF so the guest sees the end of its input
+    let _ = host_stdin.shutdown(Shutdown::Write);
+}
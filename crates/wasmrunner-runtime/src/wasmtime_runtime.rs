@@ -1,70 +1,403 @@
 
-use crate::{WasmRuntime, WasmModule};
-use wasmrunner_core::Result;
-use wasmtime::{Engine, Store, Module, Instance, Linker};
+use crate::{FuncSignature, GuestEnvironment, WasmRuntime, WasmModule};
+use crate::handles::{CountedDir, CountedFile};
+use crate::stats::GuestStats;
+use crate::stdio::{OutputMode, StdinMode, StdioConfig};
+use wasmrunner_core::{Result, WasmRunnerError};
+use wasmtime::{
+    Engine, Store, Module, Instance, Linker, Val, ValType, ExternType, ResourceLimiter, UpdateDeadline,
+};
+use wasmtime::component::{self, Component};
+use wasmtime_wasi::sync::{ambient_authority, Dir, TcpListener};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder};
+use wasi_common::file::FileAccessMode;
+use wasi_common::pipe::{ReadPipe, WritePipe};
+use wasi_common::{I32Exit, WasiFile};
+use wasmrunner_plugins::{component::link_components, CapabilitySet, PluginHost};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{debug, info_span};
+
+/// How often running guests check their deadline and report the CPU time and fuel they have used
+const EPOCH_TICK: Duration = Duration::from_millis(100);
 
 pub struct WasmtimeRuntime {
     engine: Engine,
+    fuel_metering: bool,
+    plugins: Option<Plugins>,
+    compile_cache: Option<PathBuf>,
+}
+
+/// Plugin host functions, the capabilities granted to guests and the
+/// component plugins guests import
+#[derive(Clone)]
+struct Plugins {
+    host: Arc<PluginHost>,
+    capabilities: CapabilitySet,
+    imports: Vec<String>,
 }
 
 impl WasmtimeRuntime {
     pub fn new() -> Result<Self> {
-        let engine = Engine::default();
-        Ok(Self { engine })
+        Ok(Self {
+            engine: new_engine(false)?,
+            fuel_metering: false,
+            plugins: None,
+            compile_cache: None,
+        })
+    }
+    
+    /// Link plugin host functions into every module this runtime loads, and
+    /// the `imports` component plugins into every component
+    pub fn with_plugins(
+        host: Arc<PluginHost>,
+        capabilities: CapabilitySet,
+        imports: Vec<String>,
+    ) -> Result<Self> {
+        let mut runtime = Self::new()?;
+        runtime.plugins = Some(Plugins { host, capabilities, imports });
+        Ok(runtime)
     }
 }
 
 impl WasmRuntime for WasmtimeRuntime {
     fn load_module(&mut self, wasm_bytes: &[u8]) -> Result<Box<dyn WasmModule>> {
-        let module = Module::new(&self.engine, wasm_bytes)?;
+        if is_component(wasm_bytes) {
+            let component = Component::new(&self.engine, wasm_bytes)?;
+            
+            return Ok(Box::new(WasmtimeComponent {
+                engine: self.engine.clone(),
+                fuel_metering: self.fuel_metering,
+                component,
+                plugins: self.plugins.clone(),
+                memory_limit: None,
+                timeout: None,
+                stats: GuestStats::new(),
+            }));
+        }
+        
+        let (module, from_cache) = match &self.compile_cache {
+            Some(dir) => compile_cached(&self.engine, dir, wasm_bytes)?,
+            None => (Module::new(&self.engine, wasm_bytes)?, false),
+        };
         
         Ok(Box::new(WasmtimeModule {
             engine: self.engine.clone(),
+            fuel_metering: self.fuel_metering,
             module,
+            from_cache,
+            plugins: self.plugins.clone(),
+            memory_limit: None,
+            timeout: None,
+            environment: GuestEnvironment::default(),
+            stats: GuestStats::new(),
         }))
     }
     
     fn name(&self) -> &str {
         "wasmtime"
     }
+    
+    fn set_compile_cache(&mut self, dir: PathBuf) {
+        self.compile_cache = Some(dir);
+    }
+    
+    fn set_fuel_metering(&mut self, enabled: bool) -> Result<()> {
+        if enabled != self.fuel_metering {
+            self.engine = new_engine(enabled)?;
+            self.fuel_metering = enabled;
+        }
+        Ok(())
+    }
+}
+
+/// An engine whose guests check their deadline on every epoch tick, and
+/// count the fuel they consume if `fuel_metering` is set
+fn new_engine(fuel_metering: bool) -> Result<Engine> {
+    let mut config = wasmtime::Config::new();
+    config.wasm_component_model(true);
+    
+    // Fuel instruments every block of guest code, so it is only paid for when asked
+    config.consume_fuel(fuel_metering);
+    config.epoch_interruption(true);
+    
+    Ok(Engine::new(&config)?)
+}
+
+/// Load a module compiled earlier into `dir`, or compile it and store it there
+fn compile_cached(engine: &Engine, dir: &Path, wasm_bytes: &[u8]) -> Result<(Module, bool)> {
+    // Compiled code only suits the wasmtime version and settings that produced it
+    let mut engine_hash = DefaultHasher::new();
+    engine.precompile_compatibility_hash().hash(&mut engine_hash);
+    let path = dir.join(format!(
+        "{}-{:016x}.cwasm",
+        hex::encode(Sha256::digest(wasm_bytes)),
+        engine_hash.finish()
+    ));
+    
+    if path.is_file() {
+        // Safety: the cache is private to this user and holds only what `store_compiled` wrote
+        match unsafe { Module::deserialize_file(engine, &path) } {
+            Ok(module) => return Ok((module, true)),
+            Err(e) => debug!("Ignoring unusable compiled module {}: {}", path.display(), e),
+        }
+    }
+    
+    let module = Module::new(engine, wasm_bytes)?;
+    
+    // Failing to store it only costs the next load a compile
+    if let Err(e) = store_compiled(&module, dir, &path) {
+        debug!("Failed to cache compiled module {}: {}", path.display(), e);
+    }
+    Ok((module, false))
+}
+
+fn store_compiled(module: &Module, dir: &Path, path: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    
+    // Written aside and renamed, as other processes may load the same module meanwhile
+    let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&partial, module.serialize()?)?;
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Store data for core module instances
+struct HostState {
+    wasi: WasiCtx,
+    limiter: MemoryLimiter,
+}
+
+/// Enforces the container memory limit on linear memory growth, and
+/// records the size memories grow to
+#[derive(Default)]
+struct MemoryLimiter {
+    max_bytes: Option<u64>,
+    stats: Arc<GuestStats>,
+}
+
+impl ResourceLimiter for MemoryLimiter {
+    fn memory_growing(&mut self, current: usize, desired: usize, _maximum: Option<usize>) -> anyhow::Result<bool> {
+        match self.max_bytes {
+            // Trap rather than fail the grow so the container is reported as OOM killed
+            Some(limit_bytes) if desired as u64 > limit_bytes => {
+                Err(WasmRunnerError::OutOfMemory { limit_bytes }.into())
+            }
+            _ => {
+                self.stats.memory_grown(current, desired);
+                Ok(true)
+            }
+        }
+    }
+    
+    fn table_growing(&mut self, _current: u32, _desired: u32, _maximum: Option<u32>) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+}
+
+/// Check a new store's deadline and record its CPU time in `stats` on every
+/// epoch tick, trapping once `timeout` has passed; with `fuel_metering`, also
+/// give it all the fuel it can use and report consumption on every tick
+fn meter<T>(
+    store: &mut Store<T>,
+    stats: Arc<GuestStats>,
+    fuel_metering: bool,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let deadline = timeout.map(|timeout| (Instant::now() + timeout, timeout));
+    if fuel_metering {
+        store.set_fuel(u64::MAX)?;
+        stats.set_fuel_consumed(0);
+    }
+    store.set_epoch_deadline(1);
+    store.epoch_deadline_callback(move |store| {
+        stats.record_cpu_time();
+        if fuel_metering {
+            stats.set_fuel_consumed(u64::MAX - store.get_fuel()?);
+        }
+        if let Some((deadline, timeout)) = deadline {
+            if Instant::now() >= deadline {
+                return Err(WasmRunnerError::TimedOut { timeout_ms: timeout.as_millis() as u64 }.into());
+            }
+        }
+        Ok(UpdateDeadline::Continue(1))
+    });
+    Ok(())
+}
+
+/// Advances an engine's epoch every [`EPOCH_TICK`] until dropped
+struct EpochTicker {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    fn start(engine: &Engine) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::spawn({
+            let engine = engine.clone();
+            let stop = stop.clone();
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(EPOCH_TICK);
+                    engine.increment_epoch();
+                }
+            }
+        });
+        
+        Self { stop, thread: Some(thread) }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Map an execution error to an exit code, or to a typed trap or OOM error
+fn exit_status(error: anyhow::Error) -> Result<i32> {
+    if let Some(exit) = error.downcast_ref::<I32Exit>() {
+        return Ok(exit.0);
+    }
+    
+    if error.downcast_ref::<WasmRunnerError>().is_some() {
+        return Err(error);
+    }
+    
+    if let Some(trap) = error.downcast_ref::<wasmtime::Trap>() {
+        return Err(WasmRunnerError::Trap(trap.to_string()).into());
+    }
+    
+    Err(error)
+}
+
+/// Components carry layer `1` in the header where core modules carry `0`
+fn is_component(wasm_bytes: &[u8]) -> bool {
+    wasm_bytes.len() >= 8 && &wasm_bytes[..4] == b"\0asm" && wasm_bytes[6..8] == [1, 0]
 }
 
 pub struct WasmtimeModule {
     engine: Engine,
+    fuel_metering: bool,
     module: Module,
+    from_cache: bool,
+    plugins: Option<Plugins>,
+    memory_limit: Option<u64>,
+    timeout: Option<Duration>,
+    environment: GuestEnvironment,
+    stats: Arc<GuestStats>,
 }
 
-impl WasmModule for WasmtimeModule {
-    fn execute(&mut self, args: Vec<String>) -> Result<i32> {
-        let mut store = Store::new(&self.engine, ());
+impl WasmtimeModule {
+    fn instantiate(&self, args: &[String], stdio: StdioConfig) -> Result<(Store<HostState>, Instance)> {
         let mut linker = Linker::new(&self.engine);
         
         // Add WASI support
-        wasmtime_wasi::add_to_linker(&mut linker, |s| s)?;
+        wasmtime_wasi::add_to_linker(&mut linker, |s: &mut HostState| &mut s.wasi)?;
+        
+        // Add plugin host functions the guest is allowed to use
+        if let Some(plugins) = &self.plugins {
+            plugins.host.add_to_linker(&mut linker, &plugins.capabilities)?;
+        }
+        
+        // Every handle given to the guest is counted while it stays open
+        let stats = &self.stats;
+        stats.reset();
+        
+        // Configure WASI context with args and stdio
+        let mut builder = WasiCtxBuilder::new();
+        builder.args(args)?;
+        builder.envs(&self.environment.variables)?;
+        
+        let stdin: Box<dyn WasiFile> = match stdio.stdin {
+            StdinMode::Null => Box::new(ReadPipe::from(Vec::new())),
+            StdinMode::Inherit => Box::new(wasmtime_wasi::sync::stdio::stdin()),
+            StdinMode::Pipe(reader) => Box::new(ReadPipe::new(reader)),
+        };
+        let stdout: Box<dyn WasiFile> = match stdio.stdout {
+            OutputMode::Inherit => Box::new(wasmtime_wasi::sync::stdio::stdout()),
+            OutputMode::Pipe(writer) => Box::new(WritePipe::new(writer)),
+        };
+        let stderr: Box<dyn WasiFile> = match stdio.stderr {
+            OutputMode::Inherit => Box::new(wasmtime_wasi::sync::stdio::stderr()),
+            OutputMode::Pipe(writer) => Box::new(WritePipe::new(writer)),
+        };
+        builder.stdin(CountedFile::new(stdin, stats.clone()));
+        builder.stdout(CountedFile::new(stdout, stats.clone()));
+        builder.stderr(CountedFile::new(stderr, stats.clone()));
+        let wasi = builder.build();
+        
+        // Preopened directories take file descriptors 3 onwards, then sockets follow
+        for (host, guest) in &self.environment.volumes {
+            let dir = Dir::open_ambient_dir(host, ambient_authority())
+                .map_err(|e| anyhow::anyhow!("Cannot open volume {}: {}", host.display(), e))?;
+            let dir = Box::new(wasmtime_wasi::sync::dir::Dir::from_cap_std(dir));
+            wasi.push_preopened_dir(CountedDir::new(dir, stats.clone()), guest)?;
+        }
+        let first_socket = 3 + self.environment.volumes.len() as u32;
+        for (i, listener) in self.environment.listeners.iter().enumerate() {
+            let listener = TcpListener::from_std(listener.try_clone()?);
+            let socket = Box::new(wasmtime_wasi::sync::net::TcpListener::from_cap_std(listener));
+            wasi.insert_file(
+                first_socket + i as u32,
+                CountedFile::socket(socket, stats.clone()),
+                FileAccessMode::READ | FileAccessMode::WRITE,
+            );
+        }
         
-        // Configure WASI context with args
-        let wasi = wasmtime_wasi::WasiCtxBuilder::new()
-            .args(&args)?
-            .inherit_stdio()
-            .build();
-        store.data_mut() = wasi;
+        let state = HostState {
+            wasi,
+            limiter: MemoryLimiter { max_bytes: self.memory_limit, stats: stats.clone() },
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|s| &mut s.limiter);
+        meter(&mut store, stats.clone(), self.fuel_metering, self.timeout)?;
         
-        // Instantiate and run
         let instance = linker.instantiate(&mut store, &self.module)?;
+        Ok((store, instance))
+    }
+    
+    fn func_type(&self, export: &str) -> Result<wasmtime::FuncType> {
+        match self.module.get_export(export) {
+            Some(ExternType::Func(ty)) => Ok(ty),
+            _ => Err(WasmRunnerError::ExportNotFound(export.to_string()).into()),
+        }
+    }
+}
+
+impl WasmModule for WasmtimeModule {
+    fn execute(&mut self, args: Vec<String>, stdio: StdioConfig) -> Result<i32> {
+        let (mut store, instance) = info_span!("instantiate").in_scope(|| self.instantiate(&args, stdio))?;
+        let _ticker = EpochTicker::start(&self.engine);
+        let _span = info_span!("execute").entered();
         
         // Call _start function if it exists
-        if let Ok(start_func) = instance.get_typed_func::<(), ()>(&mut store, "_start") {
-            start_func.call(&mut store, ())?;
-            Ok(0)
+        let result = if let Ok(start_func) = instance.get_typed_func::<(), ()>(&mut store, "_start") {
+            start_func.call(&mut store, ()).map(|_| 0).or_else(exit_status)
         } else {
             // Try main function
             if let Ok(main_func) = instance.get_typed_func::<(), i32>(&mut store, "main") {
-                let result = main_func.call(&mut store, ())?;
-                Ok(result)
+                main_func.call(&mut store, ()).or_else(exit_status)
             } else {
                 Ok(0)
             }
+        };
+        
+        if let Ok(fuel) = store.get_fuel() {
+            self.stats.set_fuel_consumed(u64::MAX - fuel);
         }
+        self.stats.record_cpu_time();
+        result
     }
     
     fn exports(&self) -> Vec<String> {
@@ -73,4 +406,300 @@ impl WasmModule for WasmtimeModule {
             .map(|export| export.name().to_string())
             .collect()
     }
+    
+    fn set_memory_limit(&mut self, bytes: u64) {
+        self.memory_limit = Some(bytes);
+    }
+    
+    fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+    
+    fn set_environment(&mut self, environment: GuestEnvironment) {
+        self.environment = environment;
+    }
+    
+    fn stats(&self) -> Arc<GuestStats> {
+        self.stats.clone()
+    }
+    
+    fn compiled_from_cache(&self) -> bool {
+        self.from_cache
+    }
+    
+    fn signature(&mut self, export: &str) -> Result<FuncSignature> {
+        let ty = self.func_type(export)?;
+        
+        Ok(FuncSignature {
+            params: ty.params().map(|t| t.to_string()).collect(),
+            results: ty.results().map(|t| t.to_string()).collect(),
+        })
+    }
+    
+    fn call(&mut self, export: &str, args: &[String]) -> Result<Vec<String>> {
+        let ty = self.func_type(export)?;
+        
+        if ty.params().len() != args.len() {
+            return Err(WasmRunnerError::InvalidArgument {
+                export: export.to_string(),
+                message: format!("expected {} arguments, got {}", ty.params().len(), args.len()),
+            }.into());
+        }
+        
+        let params = ty.params()
+            .zip(args)
+            .map(|(param, arg)| parse_val(export, &param, arg))
+            .collect::<Result<Vec<_>>>()?;
+        let mut results = vec![Val::I32(0); ty.results().len()];
+        
+        // Epochs must advance for the call to report fuel and meet its deadline
+        let _ticker = EpochTicker::start(&self.engine);
+        let (mut store, instance) = self.instantiate(&[export.to_string()], StdioConfig::default())?;
+        
+        // Reactor modules set up libc and their heap in `_initialize`, which must run before any export
+        if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+            initialize.call(&mut store, ()).map_err(|e| match exit_status(e) {
+                Ok(exit_code) => anyhow::anyhow!("_initialize exited with code {}", exit_code),
+                Err(e) => e,
+            })?;
+        }
+        
+        let func = instance
+            .get_func(&mut store, export)
+            .ok_or_else(|| WasmRunnerError::ExportNotFound(export.to_string()))?;
+        let called = func.call(&mut store, &params, &mut results);
+        self.stats.record_cpu_time();
+        if let Err(e) = called {
+            // An export exiting the process has no results to report
+            let exit_code = exit_status(e)?;
+            return Err(anyhow::anyhow!("{} exited with code {}", export, exit_code));
+        }
+        
+        Ok(results.iter().map(format_val).collect())
+    }
+}
+
+fn parse_val(export: &str, ty: &ValType, arg: &str) -> Result<Val> {
+    let invalid = |message: String| WasmRunnerError::InvalidArgument {
+        export: export.to_string(),
+        message,
+    };
+    
+    // Integers accept both signed and unsigned notation
+    let val = match ty {
+        ValType::I32 => arg.parse::<i32>().ok()
+            .or_else(|| arg.parse::<u32>().ok().map(|v| v as i32))
+            .map(Val::I32),
+        ValType::I64 => arg.parse::<i64>().ok()
+            .or_else(|| arg.parse::<u64>().ok().map(|v| v as i64))
+            .map(Val::I64),
+        ValType::F32 => arg.parse::<f32>().ok().map(|v| Val::F32(v.to_bits())),
+        ValType::F64 => arg.parse::<f64>().ok().map(|v| Val::F64(v.to_bits())),
+        other => return Err(invalid(format!("unsupported parameter type {}", other)).into()),
+    };
+    
+    val.ok_or_else(|| invalid(format!("expected {}, got '{}'", ty, arg)).into())
+}
+
+fn format_val(val: &Val) -> String {
+    match val {
+        Val::I32(v) => v.to_string(),
+        Val::I64(v) => v.to_string(),
+        Val::F32(bits) => f32::from_bits(*bits).to_string(),
+        Val::F64(bits) => f64::from_bits(*bits).to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// A component-model component, linked only against the component plugins it imports
+pub struct WasmtimeComponent {
+    engine: Engine,
+    fuel_metering: bool,
+    component: Component,
+    plugins: Option<Plugins>,
+    memory_limit: Option<u64>,
+    timeout: Option<Duration>,
+    stats: Arc<GuestStats>,
+}
+
+impl WasmtimeComponent {
+    fn instantiate(&self, export: &str) -> Result<(Store<MemoryLimiter>, component::Func)> {
+        self.stats.reset();
+        
+        let mut linker = component::Linker::new(&self.engine);
+        let limiter = MemoryLimiter { max_bytes: self.memory_limit, stats: self.stats.clone() };
+        let mut store = Store::new(&self.engine, limiter);
+        store.limiter(|limiter| limiter);
+        meter(&mut store, self.stats.clone(), self.fuel_metering, self.timeout)?;
+        
+        if let Some(plugins) = &self.plugins {
+            let components = plugins.host.components(&plugins.imports)?;
+            link_components(&self.engine, &mut store, &mut linker, &self.component, &components)?;
+        }
+        
+        let instance = linker.instantiate(&mut store, &self.component)?;
+        let func = instance
+            .get_func(&mut store, export)
+            .ok_or_else(|| WasmRunnerError::ExportNotFound(export.to_string()))?;
+        
+        Ok((store, func))
+    }
+}
+
+impl WasmModule for WasmtimeComponent {
+    fn execute(&mut self, _args: Vec<String>, _stdio: StdioConfig) -> Result<i32> {
+        Err(anyhow::anyhow!("Components have no entry point; invoke an export with `wasmrunner call`"))
+    }
+    
+    fn exports(&self) -> Vec<String> {
+        // Export names are only known once a function is looked up on an instance
+        Vec::new()
+    }
+    
+    fn set_memory_limit(&mut self, bytes: u64) {
+        self.memory_limit = Some(bytes);
+    }
+    
+    fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+    
+    fn set_environment(&mut self, _environment: GuestEnvironment) {
+        // Components are only called through exports, which see no WASI context
+    }
+    
+    fn stats(&self) -> Arc<GuestStats> {
+        self.stats.clone()
+    }
+    
+    fn compiled_from_cache(&self) -> bool {
+        // Components are compiled afresh on every load
+        false
+    }
+    
+    fn signature(&mut self, export: &str) -> Result<FuncSignature> {
+        let (store, func) = self.instantiate(export)?;
+        
+        Ok(FuncSignature {
+            params: func.params(&store).iter().map(describe_type).collect(),
+            results: func.results(&store).iter().map(describe_type).collect(),
+        })
+    }
+    
+    fn call(&mut self, export: &str, args: &[String]) -> Result<Vec<String>> {
+        let _ticker = EpochTicker::start(&self.engine);
+        let (mut store, func) = self.instantiate(export)?;
+        let param_types = func.params(&store);
+        
+        if param_types.len() != args.len() {
+            return Err(WasmRunnerError::InvalidArgument {
+                export: export.to_string(),
+                message: format!("expected {} arguments, got {}", param_types.len(), args.len()),
+            }.into());
+        }
+        
+        let params = param_types
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| parse_component_val(export, param, arg))
+            .collect::<Result<Vec<_>>>()?;
+        let mut results = vec![component::Val::Bool(false); func.results(&store).len()];
+        
+        let called = func.call(&mut store, &params, &mut results);
+        self.stats.record_cpu_time();
+        called?;
+        func.post_return(&mut store)?;
+        
+        Ok(results.iter().map(format_component_val).collect())
+    }
+}
+
+fn describe_type(ty: &component::Type) -> String {
+    use component::Type;
+    
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::S8 => "s8".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::S16 => "s16".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::S32 => "s32".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::S64 => "s64".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::Float32 => "float32".to_string(),
+        Type::Float64 => "float64".to_string(),
+        Type::Char => "char".to_string(),
+        Type::String => "string".to_string(),
+        Type::List(list) => format!("list<{}>", describe_type(&list.ty())),
+        Type::Option(option) => format!("option<{}>", describe_type(&option.ty())),
+        Type::Enum(e) => format!("enum {{ {} }}", e.names().collect::<Vec<_>>().join(", ")),
+        other => format!("{:?}", other),
+    }
+}
+
+fn parse_component_val(export: &str, ty: &component::Type, arg: &str) -> Result<component::Val> {
+    use component::{Type, Val};
+    
+    let invalid = |message: String| WasmRunnerError::InvalidArgument {
+        export: export.to_string(),
+        message,
+    };
+    
+    let val = match ty {
+        Type::Bool => arg.parse().ok().map(Val::Bool),
+        Type::S8 => arg.parse().ok().map(Val::S8),
+        Type::U8 => arg.parse().ok().map(Val::U8),
+        Type::S16 => arg.parse().ok().map(Val::S16),
+        Type::U16 => arg.parse().ok().map(Val::U16),
+        Type::S32 => arg.parse().ok().map(Val::S32),
+        Type::U32 => arg.parse().ok().map(Val::U32),
+        Type::S64 => arg.parse().ok().map(Val::S64),
+        Type::U64 => arg.parse().ok().map(Val::U64),
+        Type::Float32 => arg.parse().ok().map(Val::Float32),
+        Type::Float64 => arg.parse().ok().map(Val::Float64),
+        Type::Char => {
+            let mut chars = arg.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(Val::Char(c)),
+                _ => None,
+            }
+        }
+        Type::String => Some(Val::String(arg.into())),
+        Type::Enum(e) => e.new_val(arg).ok(),
+        Type::Option(option) => {
+            if arg == "none" {
+                option.new_val(None).ok()
+            } else {
+                let inner = parse_component_val(export, &option.ty(), arg)?;
+                option.new_val(Some(inner)).ok()
+            }
+        }
+        other => {
+            return Err(invalid(format!("unsupported parameter type {}", describe_type(other))).into())
+        }
+    };
+    
+    val.ok_or_else(|| invalid(format!("expected {}, got '{}'", describe_type(ty), arg)).into())
+}
+
+fn format_component_val(val: &component::Val) -> String {
+    use component::Val;
+    
+    match val {
+        Val::Bool(v) => v.to_string(),
+        Val::S8(v) => v.to_string(),
+        Val::U8(v) => v.to_string(),
+        Val::S16(v) => v.to_string(),
+        Val::U16(v) => v.to_string(),
+        Val::S32(v) => v.to_string(),
+        Val::U32(v) => v.to_string(),
+        Val::S64(v) => v.to_string(),
+        Val::U64(v) => v.to_string(),
+        Val::Float32(v) => v.to_string(),
+        Val::Float64(v) => v.to_string(),
+        Val::Char(c) => c.to_string(),
+        Val::String(s) => s.to_string(),
+        other => format!("{:?}", other),
+    }
 }
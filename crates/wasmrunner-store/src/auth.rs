@@ -0,0 +1,151 @@
+
+use crate::client::{eq, StoreClient};
+use crate::error::{Result, StoreError};
+use crate::models::{ApiToken, CreatedApiToken};
+use crate::pagination::PageRequest;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// A signed-in user as reported by the auth API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthUser {
+    pub id: String,
+    pub email: Option<String>,
+}
+
+/// Tokens returned by a successful sign-in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    
+    /// Lifetime of the access token in seconds
+    pub expires_in: Option<u64>,
+    pub user: AuthUser,
+}
+
+impl StoreClient {
+    /// Exchange an email and password for a session
+    pub async fn sign_in(&self, email: &str, password: &str) -> Result<Session> {
+        let request = self
+            .request(Method::POST, "/auth/v1/token")
+            .query(&[("grant_type", "password")])
+            .json(&json!({ "email": email, "password": password }));
+        
+        Ok(self.send(request).await?.json().await?)
+    }
+    
+    /// Exchange a refresh token for a new session
+    pub async fn refresh_session(&self, refresh_token: &str) -> Result<Session> {
+        let request = self
+            .request(Method::POST, "/auth/v1/token")
+            .query(&[("grant_type", "refresh_token")])
+            .json(&json!({ "refresh_token": refresh_token }));
+        
+        Ok(self.send(request).await?.json().await?)
+    }
+    
+    /// Revoke the client's session on the server
+    pub async fn sign_out(&self) -> Result<()> {
+        self.require_token()?;
+        self.send(self.request(Method::POST, "/auth/v1/logout")).await?;
+        Ok(())
+    }
+    
+    /// Create an account; the user must confirm their email before signing in
+    pub async fn sign_up(&self, email: &str, password: &str, username: &str) -> Result<AuthUser> {
+        let request = self
+            .request(Method::POST, "/auth/v1/signup")
+            .json(&json!({
+                "email": email,
+                "password": password,
+                "data": { "username": username }
+            }));
+        
+        // With autoconfirm enabled the user comes wrapped in a session
+        let mut body: serde_json::Value = self.send(request).await?.json().await?;
+        if let Some(user) = body.get_mut("user") {
+            body = user.take();
+        }
+        
+        serde_json::from_value(body).map_err(|e| StoreError::InvalidResponse(e.to_string()))
+    }
+    
+    /// The user owning the client's access token
+    pub async fn current_user(&self) -> Result<AuthUser> {
+        self.require_token()?;
+        let request = self.request(Method::GET, "/auth/v1/user");
+        
+        Ok(self.send(request).await?.json().await?)
+    }
+}
+
+/// Scopes an API token can be granted
+pub const API_TOKEN_SCOPES: &[&str] = &["publish", "favorites", "reviews"];
+
+/// Columns of `api_tokens` safe to read back
+const API_TOKEN_COLUMNS: &str = "id,name,scopes,created_at,expires_at,last_used_at,revoked_at";
+
+impl StoreClient {
+    /// Trade an API token for a short-lived session limited to its scopes
+    pub async fn exchange_api_token(&self, token: &str) -> Result<Session> {
+        let request = self
+            .request(Method::POST, "/functions/v1/exchange-token")
+            .json(&json!({ "token": token }));
+        
+        Ok(self.send(request).await?.json().await?)
+    }
+    
+    /// Create an API token for the signed-in user
+    pub async fn create_api_token(
+        &self,
+        name: &str,
+        scopes: &[String],
+        expires_in_days: Option<u32>,
+    ) -> Result<CreatedApiToken> {
+        self.require_token()?;
+        let args = json!({
+            "token_name": name,
+            "token_scopes": scopes,
+            "expires_in_days": expires_in_days,
+        });
+        
+        self.rpc::<_, CreatedApiToken>("create_api_token", &args, PageRequest::new(0, 1))
+            .await?
+            .items
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::InvalidResponse("create_api_token returned no token".to_string()))
+    }
+    
+    /// The signed-in user's API tokens, newest first
+    pub async fn list_api_tokens(&self) -> Result<Vec<ApiToken>> {
+        self.require_token()?;
+        let page = self
+            .select(
+                "api_tokens",
+                &[("select", API_TOKEN_COLUMNS.to_string()), ("order", "created_at.desc".to_string())],
+                PageRequest::new(0, 1000),
+            )
+            .await?;
+        
+        Ok(page.items)
+    }
+    
+    pub async fn revoke_api_token(&self, id: &str) -> Result<()> {
+        self.require_token()?;
+        let revoked: Vec<ApiToken> = self
+            .update(
+                "api_tokens",
+                &[("id", eq(id)), ("select", API_TOKEN_COLUMNS.to_string())],
+                &json!({ "revoked_at": chrono::Utc::now() }),
+            )
+            .await?;
+        
+        if revoked.is_empty() {
+            return Err(StoreError::NotFound(format!("API token {}", id)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,513 @@
+
+use crate::error::{Result, StoreError};
+use crate::models::{
+    App, AppQuery, AppUpdate, AppVersion, DownloadStat, Favorite, FavoriteApp, NewApp, NewAppVersion, NewDownload,
+    NewReview, Profile, Review, ReviewUpdate, StatsQuery,
+};
+use crate::pagination::{parse_total, Page, PageRequest};
+use crate::storage::is_storage_url;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::debug;
+use wasmrunner_core::config::SupabaseConfig;
+
+/// PostgREST filter, e.g. `("slug", "eq.hello")`
+pub type Filter = (&'static str, String);
+
+/// Rows fetched per request for download stats, which are read in full
+const STATS_PAGE_SIZE: u64 = 1000;
+
+/// Client for the app store's auth and REST APIs
+#[derive(Debug, Clone)]
+pub struct StoreClient {
+    http: reqwest::Client,
+    base_url: String,
+    anon_key: String,
+    access_token: Option<String>,
+}
+
+impl StoreClient {
+    pub fn new(base_url: impl Into<String>, anon_key: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            anon_key: anon_key.into(),
+            access_token: None,
+        }
+    }
+    
+    pub fn from_config(config: &SupabaseConfig) -> Self {
+        Self::new(&config.url, &config.anon_key)
+    }
+    
+    /// Act as a signed-in user instead of anonymously
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(token.into());
+        self
+    }
+    
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    
+    pub fn access_token(&self) -> Option<&str> {
+        self.access_token.as_deref()
+    }
+    
+    pub(crate) fn require_token(&self) -> Result<&str> {
+        self.access_token().ok_or(StoreError::NotLoggedIn)
+    }
+    
+    pub(crate) fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.authorize(self.http.request(method, format!("{}{}", self.base_url, path)))
+    }
+    
+    /// Request to an absolute URL handed out by the server
+    ///
+    /// App rows may point anywhere, so credentials are only sent to this
+    /// server's storage API; other hosts get a plain request.
+    pub(crate) fn request_url(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = self.http.request(method, url);
+        if is_storage_url(&self.base_url, url) {
+            self.authorize(request)
+        } else {
+            debug!("Not sending credentials to {}", url);
+            request
+        }
+    }
+    
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        // Anonymous requests authenticate with the anon key itself
+        let bearer = self.access_token.as_deref().unwrap_or(&self.anon_key);
+        
+        request.header("apikey", &self.anon_key).bearer_auth(bearer)
+    }
+    
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        
+        if status.is_success() {
+            return Ok(response);
+        }
+        
+        let body = response.text().await.unwrap_or_default();
+        debug!("App store request failed with {}: {}", status, body);
+        Err(StoreError::from_response(status, &body))
+    }
+    
+    // Generic table access
+    
+    /// Select a page of rows from `table`
+    pub async fn select<T: DeserializeOwned>(
+        &self,
+        table: &str,
+        filters: &[Filter],
+        page: PageRequest,
+    ) -> Result<Page<T>> {
+        let request = self
+            .request(Method::GET, &format!("/rest/v1/{}", table))
+            .header("Prefer", "count=exact")
+            .query(filters)
+            .query(&[("limit", page.limit), ("offset", page.offset)]);
+        
+        self.page(request, page).await
+    }
+    
+    /// Call a set-returning database function, paging over its rows
+    pub async fn rpc<B: Serialize, T: DeserializeOwned>(
+        &self,
+        function: &str,
+        args: &B,
+        page: PageRequest,
+    ) -> Result<Page<T>> {
+        let request = self
+            .request(Method::POST, &format!("/rest/v1/rpc/{}", function))
+            .header("Prefer", "count=exact")
+            .query(&[("limit", page.limit), ("offset", page.offset)])
+            .json(args);
+        
+        self.page(request, page).await
+    }
+    
+    async fn page<T: DeserializeOwned>(&self, request: RequestBuilder, page: PageRequest) -> Result<Page<T>> {
+        let response = self.send(request).await?;
+        let total = response
+            .headers()
+            .get("content-range")
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_total);
+        
+        Ok(Page {
+            items: response.json().await?,
+            offset: page.offset,
+            total,
+        })
+    }
+    
+    /// Select the first row matching `filters`
+    pub async fn select_one<T: DeserializeOwned>(&self, table: &str, filters: &[Filter]) -> Result<Option<T>> {
+        let request = self
+            .request(Method::GET, &format!("/rest/v1/{}", table))
+            .query(filters)
+            .query(&[("limit", 1)]);
+        
+        let rows: Vec<T> = self.send(request).await?.json().await?;
+        Ok(rows.into_iter().next())
+    }
+    
+    /// Insert a row, returning it as stored
+    pub async fn insert<B: Serialize, T: DeserializeOwned>(&self, table: &str, row: &B) -> Result<T> {
+        let request = self
+            .request(Method::POST, &format!("/rest/v1/{}", table))
+            .header("Prefer", "return=representation")
+            .json(row);
+        
+        let rows: Vec<T> = self.send(request).await?.json().await?;
+        rows.into_iter()
+            .next()
+            .ok_or_else(|| StoreError::InvalidResponse(format!("insert into {} returned no rows", table)))
+    }
+    
+    /// Insert a row without reading it back, for tables the caller cannot select from
+    pub async fn insert_only<B: Serialize>(&self, table: &str, row: &B) -> Result<()> {
+        let request = self
+            .request(Method::POST, &format!("/rest/v1/{}", table))
+            .header("Prefer", "return=minimal")
+            .json(row);
+        
+        self.send(request).await?;
+        Ok(())
+    }
+    
+    /// Update rows matching `filters`, returning them as stored
+    pub async fn update<B: Serialize, T: DeserializeOwned>(
+        &self,
+        table: &str,
+        filters: &[Filter],
+        changes: &B,
+    ) -> Result<Vec<T>> {
+        let request = self
+            .request(Method::PATCH, &format!("/rest/v1/{}", table))
+            .header("Prefer", "return=representation")
+            .query(filters)
+            .json(changes);
+        
+        Ok(self.send(request).await?.json().await?)
+    }
+    
+    /// Delete rows matching `filters`
+    pub async fn delete(&self, table: &str, filters: &[Filter]) -> Result<()> {
+        let request = self
+            .request(Method::DELETE, &format!("/rest/v1/{}", table))
+            .query(filters);
+        
+        self.send(request).await?;
+        Ok(())
+    }
+    
+    /// Delete rows matching `filters`, returning the deleted rows
+    pub async fn delete_returning<T: DeserializeOwned>(&self, table: &str, filters: &[Filter]) -> Result<Vec<T>> {
+        let request = self
+            .request(Method::DELETE, &format!("/rest/v1/{}", table))
+            .header("Prefer", "return=representation")
+            .query(filters);
+        
+        Ok(self.send(request).await?.json().await?)
+    }
+    
+    // Profiles
+    
+    pub async fn profile(&self, user_id: &str) -> Result<Profile> {
+        self.select_one("profiles", &[("id", eq(user_id))])
+            .await?
+            .ok_or_else(|| StoreError::NotFound(format!("profile {}", user_id)))
+    }
+    
+    // Apps
+    
+    pub async fn list_apps(&self, query: &AppQuery, page: PageRequest) -> Result<Page<App>> {
+        let mut filters = vec![("order", "created_at.desc".to_string())];
+        
+        if !query.include_unpublished {
+            filters.push(("is_published", "eq.true".to_string()));
+        }
+        if query.verified_only {
+            filters.push(("is_verified", "eq.true".to_string()));
+        }
+        if let Some(category) = &query.category {
+            filters.push(("category", eq(category)));
+        }
+        if let Some(author_id) = &query.author_id {
+            filters.push(("author_id", eq(author_id)));
+        }
+        
+        self.select("apps", &filters, page).await
+    }
+    
+    /// Published apps matching `term`, best matches first
+    ///
+    /// Ranking is done by the `search_apps` function over the `idx_apps_search`
+    /// full-text index; only `category` and `verified_only` apply as filters.
+    pub async fn search_apps(&self, term: &str, query: &AppQuery, page: PageRequest) -> Result<Page<App>> {
+        let args = serde_json::json!({
+            "query": term,
+            "category_filter": query.category,
+            "verified_only": query.verified_only,
+        });
+        
+        self.rpc("search_apps", &args, page).await
+    }
+    
+    pub async fn app_by_slug(&self, slug: &str) -> Result<App> {
+        self.select_one("apps", &[("slug", eq(slug))])
+            .await?
+            .ok_or_else(|| StoreError::NotFound(format!("app {}", slug)))
+    }
+    
+    pub async fn create_app(&self, app: &NewApp) -> Result<App> {
+        self.require_token()?;
+        self.insert("apps", app).await
+    }
+    
+    pub async fn update_app(&self, app_id: &str, changes: &AppUpdate) -> Result<App> {
+        self.require_token()?;
+        self.update("apps", &[("id", eq(app_id))], changes)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::NotFound(format!("app {}", app_id)))
+    }
+    
+    /// Set or, with `None`, clear the deprecation notice shown for every version of an app
+    pub async fn deprecate_app(&self, app_id: &str, message: Option<&str>) -> Result<App> {
+        self.require_token()?;
+        self.update("apps", &[("id", eq(app_id))], &serde_json::json!({ "deprecation_message": message }))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::NotFound(format!("app {}", app_id)))
+    }
+    
+    // Versions
+    
+    pub async fn list_versions(&self, app_id: &str, page: PageRequest) -> Result<Page<AppVersion>> {
+        self.select(
+            "app_versions",
+            &[("app_id", eq(app_id)), ("order", "created_at.desc".to_string())],
+            page,
+        )
+        .await
+    }
+    
+    /// Every version of an app, following pagination
+    pub async fn all_versions(&self, app_id: &str) -> Result<Vec<AppVersion>> {
+        let mut versions = Vec::new();
+        let mut request = Some(PageRequest::default());
+        
+        while let Some(page_request) = request {
+            let page = self.list_versions(app_id, page_request).await?;
+            request = page.next_request(page_request.limit);
+            versions.extend(page.items);
+        }
+        
+        Ok(versions)
+    }
+    
+    pub async fn app_version(&self, app_id: &str, version: &str) -> Result<Option<AppVersion>> {
+        self.select_one("app_versions", &[("app_id", eq(app_id)), ("version", eq(version))])
+            .await
+    }
+    
+    /// The version currently marked `is_latest`, if any
+    pub async fn latest_version(&self, app_id: &str) -> Result<Option<AppVersion>> {
+        self.select_one("app_versions", &[("app_id", eq(app_id)), ("is_latest", "eq.true".to_string())])
+            .await
+    }
+    
+    pub async fn create_version(&self, version: &NewAppVersion) -> Result<AppVersion> {
+        self.require_token()?;
+        self.insert("app_versions", version).await
+    }
+    
+    /// Create an app and its first version in one transaction, so neither
+    /// is left behind if the other fails; `version.app_id` is ignored
+    pub async fn create_app_with_version(&self, app: &NewApp, version: &NewAppVersion) -> Result<AppVersion> {
+        self.require_token()?;
+        let args = serde_json::json!({ "app": app, "first_version": version });
+        
+        self.rpc::<_, AppVersion>("create_app_with_version", &args, PageRequest::new(0, 1))
+            .await?
+            .items
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::InvalidResponse("create_app_with_version returned no version".to_string()))
+    }
+    
+    /// Yank a version so installs skip it unless it is pinned exactly
+    pub async fn yank_version(&self, version_id: &str, reason: Option<&str>) -> Result<AppVersion> {
+        self.update_version(version_id, &serde_json::json!({ "yanked_at": Utc::now(), "yank_reason": reason }))
+            .await
+    }
+    
+    pub async fn unyank_version(&self, version_id: &str) -> Result<AppVersion> {
+        self.update_version(version_id, &serde_json::json!({ "yanked_at": null, "yank_reason": null }))
+            .await
+    }
+    
+    /// Set or, with `None`, clear a version's deprecation notice
+    pub async fn deprecate_version(&self, version_id: &str, message: Option<&str>) -> Result<AppVersion> {
+        self.update_version(version_id, &serde_json::json!({ "deprecation_message": message }))
+            .await
+    }
+    
+    async fn update_version(&self, version_id: &str, changes: &serde_json::Value) -> Result<AppVersion> {
+        self.require_token()?;
+        self.update("app_versions", &[("id", eq(version_id))], changes)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::NotFound(format!("version {}", version_id)))
+    }
+    
+    /// Clear `is_latest` on every version of an app except `keep_id`
+    pub async fn demote_versions(&self, app_id: &str, keep_id: &str) -> Result<()> {
+        self.require_token()?;
+        self.update::<_, AppVersion>(
+            "app_versions",
+            &[
+                ("app_id", eq(app_id)),
+                ("id", format!("neq.{}", keep_id)),
+                ("is_latest", "eq.true".to_string()),
+            ],
+            &serde_json::json!({ "is_latest": false }),
+        )
+        .await?;
+        Ok(())
+    }
+    
+    // Favorites
+    
+    pub async fn list_favorites(&self, user_id: &str, page: PageRequest) -> Result<Page<Favorite>> {
+        self.select(
+            "user_favorites",
+            &[("user_id", eq(user_id)), ("order", "created_at.desc".to_string())],
+            page,
+        )
+        .await
+    }
+    
+    /// A user's favorites with the current state of each app
+    pub async fn list_favorite_apps(&self, user_id: &str, page: PageRequest) -> Result<Page<FavoriteApp>> {
+        self.select(
+            "user_favorites",
+            &[
+                ("select", "created_at,app:apps(*)".to_string()),
+                ("user_id", eq(user_id)),
+                ("order", "created_at.desc".to_string()),
+            ],
+            page,
+        )
+        .await
+    }
+    
+    pub async fn add_favorite(&self, user_id: &str, app_id: &str) -> Result<Favorite> {
+        self.require_token()?;
+        self.insert("user_favorites", &serde_json::json!({ "user_id": user_id, "app_id": app_id }))
+            .await
+    }
+    
+    /// Remove a favorite, failing with `NotFound` if the app was not a favorite
+    pub async fn remove_favorite(&self, user_id: &str, app_id: &str) -> Result<()> {
+        self.require_token()?;
+        let removed: Vec<Favorite> = self
+            .delete_returning("user_favorites", &[("user_id", eq(user_id)), ("app_id", eq(app_id))])
+            .await?;
+        
+        if removed.is_empty() {
+            return Err(StoreError::NotFound(format!("favorite {}", app_id)));
+        }
+        Ok(())
+    }
+    
+    // Reviews
+    
+    pub async fn list_reviews(&self, app_id: &str, page: PageRequest) -> Result<Page<Review>> {
+        self.select(
+            "reviews",
+            &[("app_id", eq(app_id)), ("order", "created_at.desc".to_string())],
+            page,
+        )
+        .await
+    }
+    
+    /// A user's review of an app; each user has at most one per app
+    pub async fn user_review(&self, app_id: &str, user_id: &str) -> Result<Option<Review>> {
+        self.select_one("reviews", &[("app_id", eq(app_id)), ("user_id", eq(user_id))])
+            .await
+    }
+    
+    pub async fn create_review(&self, review: &NewReview) -> Result<Review> {
+        self.require_token()?;
+        self.insert("reviews", review).await
+    }
+    
+    pub async fn update_review(&self, review_id: &str, changes: &ReviewUpdate) -> Result<Review> {
+        self.require_token()?;
+        self.update("reviews", &[("id", eq(review_id))], changes)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::NotFound(format!("review {}", review_id)))
+    }
+    
+    pub async fn delete_review(&self, review_id: &str) -> Result<()> {
+        self.require_token()?;
+        let removed: Vec<Review> = self.delete_returning("reviews", &[("id", eq(review_id))]).await?;
+        
+        if removed.is_empty() {
+            return Err(StoreError::NotFound(format!("review {}", review_id)));
+        }
+        Ok(())
+    }
+    
+    // Downloads
+    
+    /// Record a download; only app authors may read the rows back
+    pub async fn record_download(&self, download: &NewDownload) -> Result<()> {
+        self.insert_only("downloads", download).await
+    }
+    
+    /// Download counts for an app the caller authors, following pagination
+    pub async fn download_stats(&self, app_id: &str, query: &StatsQuery) -> Result<Vec<DownloadStat>> {
+        self.require_token()?;
+        let args = serde_json::json!({
+            "target_app": app_id,
+            "since": query.since.map(start_of_day),
+            "until": query.until.and_then(|day| day.succ_opt()).map(start_of_day),
+            "bucket": query.interval,
+        });
+        
+        let mut stats = Vec::new();
+        let mut request = Some(PageRequest::new(0, STATS_PAGE_SIZE));
+        while let Some(page_request) = request {
+            let page = self.rpc("download_stats", &args, page_request).await?;
+            request = page.next_request(page_request.limit);
+            stats.extend(page.items);
+        }
+        
+        Ok(stats)
+    }
+}
+
+/// Midnight UTC at the start of `day`
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// PostgREST equality filter value
+pub fn eq(value: &str) -> String {
+    format!("eq.{}", value)
+}
@@ -0,0 +1,121 @@
+
+use crate::auth::Session;
+use crate::error::{Result, StoreError};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::warn;
+
+/// Refresh access tokens this long before they expire
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// Session saved by `wasmrunner login`
+///
+/// The file holds live tokens, so it is written readable only by its owner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credentials {
+    pub access_token: String,
+    pub email: String,
+    pub supabase_url: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+impl Credentials {
+    pub fn from_session(session: Session, email: &str, supabase_url: &str) -> Self {
+        let expires_at = session
+            .expires_in
+            .map(|secs| Utc::now() + Duration::seconds(secs as i64));
+        
+        Self {
+            access_token: session.access_token,
+            email: session.user.email.unwrap_or_else(|| email.to_string()),
+            supabase_url: supabase_url.to_string(),
+            refresh_token: session.refresh_token,
+            expires_at,
+            user_id: Some(session.user.id),
+        }
+    }
+    
+    /// Whether the access token has expired or is about to
+    pub fn needs_refresh(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at - Duration::seconds(REFRESH_MARGIN_SECS) <= Utc::now(),
+            None => false,
+        }
+    }
+    
+    /// Load saved credentials, failing with `NotLoggedIn` when there are none
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(StoreError::NotLoggedIn);
+        }
+        
+        restrict_permissions(path)?;
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+    
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        
+        // Write beside the target and rename, so the file is never briefly world-readable
+        let staging = path.with_extension("tmp");
+        write_private(&staging, &serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&staging, path)?;
+        Ok(())
+    }
+    
+    /// Forget saved credentials; succeeds when there are none
+    pub fn remove(path: &Path) -> Result<()> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    
+    let _ = std::fs::remove_file(path);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Tighten credentials written by older versions with default permissions
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        warn!("{} was readable by other users; restricting it to its owner", path.display());
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,59 @@
+
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, StoreError>;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("App store is not configured; set [supabase] in the config file or SUPABASE_URL and SUPABASE_ANON_KEY")]
+    NotConfigured,
+    
+    #[error("Not logged in. Run `wasmrunner login` first.")]
+    NotLoggedIn,
+    
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    
+    #[error("Not found: {0}")]
+    NotFound(String),
+    
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    
+    #[error("App store returned {status}: {message}")]
+    Api { status: u16, message: String },
+    
+    #[error("Invalid response from app store: {0}")]
+    InvalidResponse(String),
+    
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    
+    #[error("Failed to access credentials: {0}")]
+    Io(#[from] std::io::Error),
+    
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl StoreError {
+    /// Map an unsuccessful response to an error, using the message from
+    /// PostgREST (`message`) or GoTrue (`error_description`, `msg`) when present
+    pub(crate) fn from_response(status: reqwest::StatusCode, body: &str) -> Self {
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| {
+                ["message", "error_description", "msg", "error"]
+                    .iter()
+                    .find_map(|key| value.get(key).and_then(|v| v.as_str()).map(str::to_string))
+            })
+            .unwrap_or_else(|| body.trim().to_string());
+        
+        match status.as_u16() {
+            401 | 403 => StoreError::Unauthorized(message),
+            404 => StoreError::NotFound(message),
+            409 => StoreError::Conflict(message),
+            status => StoreError::Api { status, message },
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Typed client for the WasmRunner app store
+//!
+//! The store is a Supabase project: authentication goes through GoTrue and the
+//! tables from `migrations/001_initial_schema.sql` are served by PostgREST.
+
+pub mod auth;
+pub mod client;
+pub mod credentials;
+pub mod error;
+pub mod models;
+pub mod moderation;
+pub mod pagination;
+pub mod storage;
+
+pub use auth::{AuthUser, Session, API_TOKEN_SCOPES};
+pub use client::StoreClient;
+pub use credentials::Credentials;
+pub use error::{Result, StoreError};
+pub use pagination::{Page, PageRequest};
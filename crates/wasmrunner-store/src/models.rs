@@ -0,0 +1,354 @@
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use wasmrunner_core::signing::ImageSignature;
+
+/// Row of `profiles`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub email: String,
+    pub username: Option<String>,
+    pub full_name: Option<String>,
+    pub avatar_url: Option<String>,
+    #[serde(default = "default_role")]
+    pub role: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Row of `apps`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct App {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    pub long_description: Option<String>,
+    pub version: String,
+    pub author_id: String,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub manifest_url: String,
+    pub wasm_url: String,
+    #[serde(default)]
+    pub size_bytes: i64,
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub runtime_requirements: serde_json::Value,
+    pub homepage_url: Option<String>,
+    pub repository_url: Option<String>,
+    pub license: Option<String>,
+    #[serde(default)]
+    pub download_count: i32,
+    #[serde(default)]
+    pub star_count: i32,
+    pub rating_average: Option<f64>,
+    #[serde(default)]
+    pub rating_count: i32,
+    #[serde(default)]
+    pub deprecation_message: Option<String>,
+    #[serde(default)]
+    pub is_published: bool,
+    #[serde(default)]
+    pub is_verified: bool,
+    #[serde(default)]
+    pub is_featured: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Insert into `apps`; unset columns take their database defaults
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewApp {
+    pub name: String,
+    pub slug: String,
+    pub version: String,
+    pub author_id: String,
+    pub manifest_url: String,
+    pub wasm_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub size_bytes: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    pub is_published: bool,
+}
+
+/// Columns of `apps` changed when a new latest version is published
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AppUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wasm_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Row of `app_versions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppVersion {
+    pub id: String,
+    pub app_id: String,
+    pub version: String,
+    pub changelog: Option<String>,
+    pub manifest_url: String,
+    pub wasm_url: String,
+    #[serde(default)]
+    pub size_bytes: i64,
+    pub sha256: Option<String>,
+    pub signature: Option<ImageSignature>,
+    #[serde(default)]
+    pub is_prerelease: bool,
+    #[serde(default)]
+    pub is_latest: bool,
+    #[serde(default)]
+    pub yanked_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub yank_reason: Option<String>,
+    #[serde(default)]
+    pub deprecation_message: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl AppVersion {
+    pub fn is_yanked(&self) -> bool {
+        self.yanked_at.is_some()
+    }
+}
+
+/// Insert into `app_versions`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewAppVersion {
+    pub app_id: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<String>,
+    pub manifest_url: String,
+    pub wasm_url: String,
+    pub size_bytes: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ImageSignature>,
+    pub is_prerelease: bool,
+    pub is_latest: bool,
+}
+
+/// Row of `user_favorites`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Favorite {
+    pub id: String,
+    pub user_id: String,
+    pub app_id: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A favorite joined with the app it stars
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteApp {
+    pub created_at: Option<DateTime<Utc>>,
+    pub app: App,
+}
+
+/// Row of `reviews`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    pub id: String,
+    pub app_id: String,
+    pub user_id: String,
+    pub rating: i32,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Insert into `reviews`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewReview {
+    pub app_id: String,
+    pub user_id: String,
+    pub rating: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// Patch for `reviews`; `None` leaves a column unchanged
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReviewUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// Row of `downloads`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Download {
+    pub id: String,
+    pub app_id: String,
+    pub version: String,
+    pub user_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub platform: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Insert into `downloads`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewDownload {
+    pub app_id: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+}
+
+/// Downloads in one period for one version and platform, from `download_stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadStat {
+    pub period: NaiveDate,
+    pub version: String,
+    pub platform: String,
+    pub downloads: i64,
+}
+
+/// Length of the periods download stats are grouped into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsInterval {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+/// Date range and grouping for `download_stats`; bounds are in UTC
+#[derive(Debug, Clone, Default)]
+pub struct StatsQuery {
+    /// First day included
+    pub since: Option<NaiveDate>,
+    
+    /// Last day included
+    pub until: Option<NaiveDate>,
+    pub interval: StatsInterval,
+}
+
+/// Change a moderator can make with the `moderate` function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    Verify,
+    Unverify,
+    Feature,
+    Unfeature,
+    Unpublish,
+    Publish,
+    Yank,
+    Unyank,
+}
+
+impl ModerationAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Verify => "verify",
+            Self::Unverify => "unverify",
+            Self::Feature => "feature",
+            Self::Unfeature => "unfeature",
+            Self::Unpublish => "unpublish",
+            Self::Publish => "publish",
+            Self::Yank => "yank",
+            Self::Unyank => "unyank",
+        }
+    }
+}
+
+/// Row of `moderation_log`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationEntry {
+    pub id: String,
+    pub actor_id: String,
+    pub action: ModerationAction,
+    pub app_id: Option<String>,
+    pub version: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    
+    /// Embedded when listing the log
+    #[serde(default)]
+    pub app: Option<AppRef>,
+    #[serde(default)]
+    pub actor: Option<ProfileRef>,
+}
+
+/// Embedded `apps` columns identifying an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRef {
+    pub slug: String,
+}
+
+/// Embedded `profiles` columns identifying a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileRef {
+    pub username: Option<String>,
+    pub email: String,
+}
+
+/// Row of `api_tokens`, without the token hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// A newly created API token; `token` is only ever returned here
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub info: ApiToken,
+    pub token: String,
+}
+
+/// Filters for listing apps
+#[derive(Debug, Clone, Default)]
+pub struct AppQuery {
+    pub category: Option<String>,
+    pub verified_only: bool,
+    pub author_id: Option<String>,
+    
+    /// Include apps that have not been published
+    pub include_unpublished: bool,
+}
+
+fn default_role() -> String {
+    "user".to_string()
+}
@@ -0,0 +1,51 @@
+use crate::client::{eq, Filter, StoreClient};
+use crate::error::{Result, StoreError};
+use crate::models::{ModerationAction, ModerationEntry};
+use crate::pagination::{Page, PageRequest};
+use serde_json::json;
+
+/// Columns selected when listing the log, with the app slug and moderator embedded
+const LOG_COLUMNS: &str = "*,app:apps(slug),actor:profiles(username,email)";
+
+impl StoreClient {
+    /// Apply a moderation action to an app, or for yanks to one of its versions
+    ///
+    /// The server checks the caller's role and records the action in the
+    /// moderation log; the returned entry is that record.
+    pub async fn moderate(
+        &self,
+        app_id: &str,
+        action: ModerationAction,
+        version: Option<&str>,
+        reason: Option<&str>,
+    ) -> Result<ModerationEntry> {
+        self.require_token()?;
+        let args = json!({
+            "target_app": app_id,
+            "moderation_action": action,
+            "target_version": version,
+            "moderation_reason": reason,
+        });
+        
+        self.rpc::<_, ModerationEntry>("moderate", &args, PageRequest::new(0, 1))
+            .await?
+            .items
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::InvalidResponse("moderate returned no log entry".to_string()))
+    }
+    
+    /// Moderation log entries, newest first, optionally for one app
+    pub async fn moderation_log(&self, app_id: Option<&str>, page: PageRequest) -> Result<Page<ModerationEntry>> {
+        self.require_token()?;
+        let mut filters: Vec<Filter> = vec![
+            ("select", LOG_COLUMNS.to_string()),
+            ("order", "created_at.desc".to_string()),
+        ];
+        if let Some(app_id) = app_id {
+            filters.push(("app_id", eq(app_id)));
+        }
+        
+        self.select("moderation_log", &filters, page).await
+    }
+}
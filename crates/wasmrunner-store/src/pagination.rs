@@ -0,0 +1,62 @@
+
+use serde::Serialize;
+
+pub const DEFAULT_PAGE_SIZE: u64 = 25;
+
+/// A window of rows to fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub offset: u64,
+    pub limit: u64,
+}
+
+impl PageRequest {
+    pub fn new(offset: u64, limit: u64) -> Self {
+        Self { offset, limit }
+    }
+    
+    /// The window following this one
+    pub fn next(&self) -> Self {
+        Self {
+            offset: self.offset + self.limit,
+            limit: self.limit,
+        }
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self::new(0, DEFAULT_PAGE_SIZE)
+    }
+}
+
+/// Rows returned for a `PageRequest`
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub offset: u64,
+    
+    /// Total matching rows, when the server counted them
+    pub total: Option<u64>,
+}
+
+impl<T> Page<T> {
+    pub fn has_more(&self) -> bool {
+        match self.total {
+            Some(total) => self.offset + (self.items.len() as u64) < total,
+            None => false,
+        }
+    }
+    
+    pub fn next_request(&self, limit: u64) -> Option<PageRequest> {
+        self.has_more()
+            .then(|| PageRequest::new(self.offset + self.items.len() as u64, limit))
+    }
+}
+
+/// Total from a PostgREST `Content-Range` header such as `0-24/137` or `*/0`
+pub(crate) fn parse_total(content_range: &str) -> Option<u64> {
+    content_range
+        .split_once('/')
+        .and_then(|(_, total)| total.parse().ok())
+}
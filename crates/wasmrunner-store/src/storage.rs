@@ -0,0 +1,180 @@
+
+use crate::client::StoreClient;
+use crate::error::{Result, StoreError};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use reqwest::{Method, Response, Url};
+use tracing::{debug, warn};
+
+/// Bucket holding WASM binaries, readable by signed-in users
+pub const WASM_BUCKET: &str = "wasm-files";
+
+/// Public bucket holding app manifests
+pub const MANIFEST_BUCKET: &str = "manifests";
+
+/// Chunk size required by Supabase's resumable upload endpoint
+pub const CHUNK_SIZE: usize = 6 * 1024 * 1024;
+
+/// Attempts per chunk before giving up on an upload
+const MAX_ATTEMPTS: u32 = 3;
+
+const TUS_VERSION: &str = "1.0.0";
+
+/// Path prefix of downloadable objects
+const OBJECT_PATH: &str = "/storage/v1/object/";
+
+/// Path prefix of the upload URLs the resumable endpoint hands out
+const UPLOAD_PATH: &str = "/storage/v1/upload/resumable";
+
+impl StoreClient {
+    /// URL of an object for authenticated downloads
+    pub fn object_url(&self, bucket: &str, object: &str) -> String {
+        format!("{}/storage/v1/object/{}/{}", self.base_url(), bucket, object)
+    }
+    
+    /// URL of an object in a public bucket
+    pub fn public_object_url(&self, bucket: &str, object: &str) -> String {
+        format!("{}/storage/v1/object/public/{}/{}", self.base_url(), bucket, object)
+    }
+    
+    /// Download an object, reporting `(received, total)` bytes as it arrives
+    ///
+    /// Objects outside public buckets need a signed-in user; without one this
+    /// fails with `NotLoggedIn` rather than with the server's refusal.
+    pub async fn download(&self, url: &str, mut progress: impl FnMut(u64, Option<u64>)) -> Result<Vec<u8>> {
+        if is_private_object(self.base_url(), url) {
+            self.require_token()?;
+        }
+        
+        let mut response = self.send(self.request_url(Method::GET, url)).await?;
+        let total = response.content_length();
+        let mut data = Vec::with_capacity(total.unwrap_or(0) as usize);
+        
+        progress(0, total);
+        while let Some(chunk) = response.chunk().await? {
+            data.extend_from_slice(&chunk);
+            progress(data.len() as u64, total);
+        }
+        
+        Ok(data)
+    }
+    
+    /// Upload `data` to `bucket/object` in chunks, replacing any existing object
+    ///
+    /// Uses the TUS resumable protocol: a chunk that fails is retried from the
+    /// offset the server acknowledged. `progress` receives the bytes uploaded
+    /// so far after every chunk.
+    pub async fn upload(
+        &self,
+        bucket: &str,
+        object: &str,
+        content_type: &str,
+        data: &[u8],
+        mut progress: impl FnMut(u64),
+    ) -> Result<()> {
+        self.require_token()?;
+        
+        let metadata = [("bucketName", bucket), ("objectName", object), ("contentType", content_type)]
+            .iter()
+            .map(|(key, value)| format!("{} {}", key, BASE64.encode(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        
+        let request = self
+            .request(Method::POST, "/storage/v1/upload/resumable")
+            .header("Tus-Resumable", TUS_VERSION)
+            .header("Upload-Length", data.len())
+            .header("Upload-Metadata", metadata)
+            .header("x-upsert", "true");
+        
+        let response = self.send(request).await?;
+        let location = header(&response, "location")?;
+        let location = if location.starts_with('/') {
+            format!("{}{}", self.base_url(), location)
+        } else {
+            location
+        };
+        debug!("Uploading {} bytes to {}/{} via {}", data.len(), bucket, object, location);
+        
+        let mut offset = 0;
+        let mut attempts = 0;
+        progress(0);
+        
+        while offset < data.len() {
+            let end = (offset + CHUNK_SIZE).min(data.len());
+            
+            match self.upload_chunk(&location, offset, &data[offset..end]).await {
+                Ok(acknowledged) => {
+                    offset = acknowledged;
+                    attempts = 0;
+                    progress(offset as u64);
+                }
+                Err(e) if attempts + 1 < MAX_ATTEMPTS => {
+                    attempts += 1;
+                    warn!("Upload chunk at offset {} failed, resuming: {}", offset, e);
+                    offset = self.upload_offset(&location).await?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        
+        Ok(())
+    }
+    
+    async fn upload_chunk(&self, location: &str, offset: usize, chunk: &[u8]) -> Result<usize> {
+        let request = self
+            .request_url(Method::PATCH, location)
+            .header("Tus-Resumable", TUS_VERSION)
+            .header("Upload-Offset", offset)
+            .header("Content-Type", "application/offset+octet-stream")
+            .body(chunk.to_vec());
+        
+        let response = self.send(request).await?;
+        parse_offset(&response)
+    }
+    
+    /// Offset the server has stored for an interrupted upload
+    async fn upload_offset(&self, location: &str) -> Result<usize> {
+        let request = self
+            .request_url(Method::HEAD, location)
+            .header("Tus-Resumable", TUS_VERSION);
+        
+        let response = self.send(request).await?;
+        parse_offset(&response)
+    }
+}
+
+/// The path of `url` when it has the same origin as `base_url`
+fn own_path(base_url: &str, url: &str) -> Option<String> {
+    let base = Url::parse(base_url).ok()?;
+    let url = Url::parse(url).ok()?;
+    (url.origin() == base.origin()).then(|| url.path().to_string())
+}
+
+/// Whether `url` is an object or upload on the storage API of the server at `base_url`
+pub(crate) fn is_storage_url(base_url: &str, url: &str) -> bool {
+    own_path(base_url, url).is_some_and(|path| path.starts_with(OBJECT_PATH) || path.starts_with(UPLOAD_PATH))
+}
+
+/// Whether `url` is an object that `object_url` would hand out, as opposed to a public one
+fn is_private_object(base_url: &str, url: &str) -> bool {
+    own_path(base_url, url)
+        .as_deref()
+        .and_then(|path| path.strip_prefix(OBJECT_PATH))
+        .is_some_and(|object| !object.starts_with("public/"))
+}
+
+fn header(response: &Response, name: &str) -> Result<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .ok_or_else(|| StoreError::InvalidResponse(format!("missing {} header", name)))
+}
+
+fn parse_offset(response: &Response) -> Result<usize> {
+    header(response, "upload-offset")?
+        .parse()
+        .map_err(|_| StoreError::InvalidResponse("invalid Upload-Offset header".to_string()))
+}
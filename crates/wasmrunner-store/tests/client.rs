@@ -0,0 +1,568 @@
+
+use serde_json::json;
+use wasmrunner_store::models::{
+    AppQuery, ModerationAction, NewApp, NewAppVersion, ReviewUpdate, StatsInterval, StatsQuery,
+};
+use wasmrunner_store::{PageRequest, StoreClient, StoreError};
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ANON_KEY: &str = "anon-key";
+
+fn app_row(slug: &str) -> serde_json::Value {
+    json!({
+        "id": format!("id-{}", slug),
+        "name": slug,
+        "slug": slug,
+        "description": null,
+        "long_description": null,
+        "version": "1.0.0",
+        "author_id": "user-1",
+        "category": "utility",
+        "tags": ["cli"],
+        "manifest_url": "https://example.com/manifest.json",
+        "wasm_url": "https://example.com/app.wasm",
+        "size_bytes": 1024,
+        "runtime_requirements": {},
+        "homepage_url": null,
+        "repository_url": null,
+        "license": "MIT",
+        "download_count": 3,
+        "star_count": 1,
+        "rating_average": 4.5,
+        "rating_count": 2,
+        "is_published": true,
+        "is_verified": false,
+        "is_featured": false,
+        "created_at": "2024-01-01T12:00:00.123456+00:00",
+        "updated_at": "2024-01-01T12:00:00+00:00"
+    })
+}
+
+#[tokio::test]
+async fn sign_in_returns_session() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auth/v1/token"))
+        .and(query_param("grant_type", "password"))
+        .and(header("apikey", ANON_KEY))
+        .and(body_json(json!({ "email": "dev@example.com", "password": "secret" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "token-1",
+            "refresh_token": "refresh-1",
+            "expires_in": 3600,
+            "token_type": "bearer",
+            "user": { "id": "user-1", "email": "dev@example.com" }
+        })))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let session = client.sign_in("dev@example.com", "secret").await.unwrap();
+    
+    assert_eq!(session.access_token, "token-1");
+    assert_eq!(session.refresh_token.as_deref(), Some("refresh-1"));
+    assert_eq!(session.user.id, "user-1");
+}
+
+#[tokio::test]
+async fn auth_errors_are_typed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auth/v1/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": "invalid_grant",
+            "error_description": "Invalid login credentials"
+        })))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let err = client.sign_in("dev@example.com", "wrong").await.unwrap_err();
+    
+    match err {
+        StoreError::Api { status, message } => {
+            assert_eq!(status, 400);
+            assert_eq!(message, "Invalid login credentials");
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn list_apps_paginates_with_filters() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/v1/apps"))
+        .and(query_param("is_published", "eq.true"))
+        .and(query_param("category", "eq.utility"))
+        .and(query_param("limit", "2"))
+        .and(query_param("offset", "2"))
+        .and(header("Prefer", "count=exact"))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header("Content-Range", "2-3/5")
+                .set_body_json(json!([app_row("fmt"), app_row("grep")])),
+        )
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let query = AppQuery {
+        category: Some("utility".to_string()),
+        ..Default::default()
+    };
+    let page = client.list_apps(&query, PageRequest::new(2, 2)).await.unwrap();
+    
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[1].slug, "grep");
+    assert_eq!(page.total, Some(5));
+    assert!(page.has_more());
+    assert_eq!(page.next_request(2), Some(PageRequest::new(4, 2)));
+}
+
+#[tokio::test]
+async fn missing_app_is_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/v1/apps"))
+        .and(query_param("slug", "eq.missing"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let err = client.app_by_slug("missing").await.unwrap_err();
+    
+    assert!(matches!(err, StoreError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn create_app_sends_token_and_returns_row() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v1/apps"))
+        .and(header("Authorization", "Bearer token-1"))
+        .and(header("Prefer", "return=representation"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!([app_row("fmt")])))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let app = client
+        .create_app(&NewApp {
+            name: "fmt".to_string(),
+            slug: "fmt".to_string(),
+            version: "1.0.0".to_string(),
+            author_id: "user-1".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    
+    assert_eq!(app.id, "id-fmt");
+    assert_eq!(app.size_bytes, 1024);
+}
+
+#[tokio::test]
+async fn writes_require_login() {
+    let client = StoreClient::new("http://127.0.0.1:9", ANON_KEY);
+    let err = client.add_favorite("user-1", "app-1").await.unwrap_err();
+    
+    assert!(matches!(err, StoreError::NotLoggedIn));
+}
+
+#[tokio::test]
+async fn anonymous_download_of_private_object_requires_login() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"\0asm".to_vec()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let wasm_url = client.object_url("wasm-files", "user-1/fmt/1.0.0.wasm");
+    let err = client.download(&wasm_url, |_, _| {}).await.unwrap_err();
+    assert!(matches!(err, StoreError::NotLoggedIn));
+    
+    // Public objects stay readable without a session
+    let manifest_url = client.public_object_url("manifests", "user-1/fmt/1.0.0.json");
+    let data = client.download(&manifest_url, |_, _| {}).await.unwrap();
+    assert_eq!(data, b"\0asm");
+}
+
+#[tokio::test]
+async fn downloads_from_other_hosts_carry_no_credentials() {
+    let store = MockServer::start().await;
+    let foreign = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/fmt.wasm"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"\0asm".to_vec()))
+        .expect(1)
+        .mount(&foreign)
+        .await;
+    
+    let client = StoreClient::new(store.uri(), ANON_KEY).with_access_token("token-1");
+    let data = client.download(&format!("{}/fmt.wasm", foreign.uri()), |_, _| {}).await.unwrap();
+    assert_eq!(data, b"\0asm");
+    
+    let requests = foreign.received_requests().await.unwrap();
+    let sent = |name: &str| requests[0].headers.iter().any(|(header, _)| header.as_str().eq_ignore_ascii_case(name));
+    assert!(!sent("authorization"));
+    assert!(!sent("apikey"));
+}
+
+#[tokio::test]
+async fn duplicate_favorite_is_conflict() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v1/user_favorites"))
+        .respond_with(ResponseTemplate::new(409).set_body_json(json!({
+            "code": "23505",
+            "message": "duplicate key value violates unique constraint"
+        })))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let err = client.add_favorite("user-1", "app-1").await.unwrap_err();
+    
+    assert!(matches!(err, StoreError::Conflict(message) if message.contains("duplicate key")));
+}
+
+#[tokio::test]
+async fn demote_versions_keeps_new_latest() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(path("/rest/v1/app_versions"))
+        .and(query_param("app_id", "eq.app-1"))
+        .and(query_param("id", "neq.version-2"))
+        .and(query_param("is_latest", "eq.true"))
+        .and(body_json(json!({ "is_latest": false })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    client.demote_versions("app-1", "version-2").await.unwrap();
+}
+
+#[tokio::test]
+async fn create_app_with_version_uses_one_rpc() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v1/rpc/create_app_with_version"))
+        .and(header("Authorization", "Bearer token-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "id": "version-1",
+            "app_id": "id-fmt",
+            "version": "1.0.0",
+            "changelog": null,
+            "manifest_url": "https://example.com/manifest.json",
+            "wasm_url": "https://example.com/app.wasm",
+            "size_bytes": 1024,
+            "sha256": null,
+            "signature": null,
+            "is_prerelease": false,
+            "is_latest": true,
+            "created_at": "2024-01-01T00:00:00+00:00"
+        }])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let app = NewApp {
+        name: "fmt".to_string(),
+        slug: "fmt".to_string(),
+        version: "1.0.0".to_string(),
+        author_id: "user-1".to_string(),
+        ..Default::default()
+    };
+    let version = NewAppVersion {
+        version: "1.0.0".to_string(),
+        is_latest: true,
+        ..Default::default()
+    };
+    let created = client.create_app_with_version(&app, &version).await.unwrap();
+    
+    assert_eq!(created.app_id, "id-fmt");
+    assert!(created.is_latest);
+}
+
+#[tokio::test]
+async fn search_apps_calls_ranked_rpc() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v1/rpc/search_apps"))
+        .and(query_param("limit", "20"))
+        .and(query_param("offset", "0"))
+        .and(body_json(json!({
+            "query": "json formatter",
+            "category_filter": null,
+            "verified_only": true
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Range", "0-0/1")
+                .set_body_json(json!([app_row("fmt")])),
+        )
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let query = AppQuery {
+        verified_only: true,
+        ..Default::default()
+    };
+    let page = client
+        .search_apps("json formatter", &query, PageRequest::new(0, 20))
+        .await
+        .unwrap();
+    
+    assert_eq!(page.items[0].slug, "fmt");
+    assert_eq!(page.total, Some(1));
+    assert!(!page.has_more());
+}
+
+#[tokio::test]
+async fn refresh_session_exchanges_refresh_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auth/v1/token"))
+        .and(query_param("grant_type", "refresh_token"))
+        .and(body_json(json!({ "refresh_token": "refresh-1" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "token-2",
+            "refresh_token": "refresh-2",
+            "expires_in": 3600,
+            "user": { "id": "user-1", "email": "dev@example.com" }
+        })))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let session = client.refresh_session("refresh-1").await.unwrap();
+    
+    assert_eq!(session.access_token, "token-2");
+    assert_eq!(session.refresh_token.as_deref(), Some("refresh-2"));
+}
+
+#[tokio::test]
+async fn exchange_api_token_returns_scoped_session() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/functions/v1/exchange-token"))
+        .and(body_json(json!({ "token": "wrt_abc" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "jwt-1",
+            "token_type": "bearer",
+            "expires_in": 900,
+            "refresh_token": null,
+            "user": { "id": "user-1", "email": "ci@example.com" }
+        })))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY);
+    let session = client.exchange_api_token("wrt_abc").await.unwrap();
+    
+    assert_eq!(session.access_token, "jwt-1");
+    assert!(session.refresh_token.is_none());
+}
+
+#[tokio::test]
+async fn favorite_apps_embed_app_rows() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/v1/user_favorites"))
+        .and(query_param("select", "created_at,app:apps(*)"))
+        .and(query_param("user_id", "eq.user-1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Range", "0-0/1")
+                .set_body_json(json!([{
+                    "created_at": "2024-02-01T00:00:00+00:00",
+                    "app": app_row("fmt")
+                }])),
+        )
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let page = client
+        .list_favorite_apps("user-1", PageRequest::default())
+        .await
+        .unwrap();
+    
+    assert_eq!(page.items[0].app.slug, "fmt");
+    assert_eq!(page.items[0].app.star_count, 1);
+}
+
+#[tokio::test]
+async fn removing_missing_favorite_is_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .and(path("/rest/v1/user_favorites"))
+        .and(header("Prefer", "return=representation"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let err = client.remove_favorite("user-1", "app-1").await.unwrap_err();
+    
+    assert!(matches!(err, StoreError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn update_review_patches_only_given_fields() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(path("/rest/v1/reviews"))
+        .and(query_param("id", "eq.review-1"))
+        .and(body_json(json!({ "rating": 4 })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "id": "review-1",
+            "app_id": "app-1",
+            "user_id": "user-1",
+            "rating": 4,
+            "title": "Solid",
+            "content": null,
+            "created_at": "2024-02-01T00:00:00+00:00",
+            "updated_at": "2024-02-02T00:00:00+00:00"
+        }])))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let changes = ReviewUpdate { rating: Some(4), ..Default::default() };
+    let review = client.update_review("review-1", &changes).await.unwrap();
+    
+    assert_eq!(review.rating, 4);
+    assert_eq!(review.title.as_deref(), Some("Solid"));
+}
+
+#[tokio::test]
+async fn download_stats_sends_inclusive_date_range() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v1/rpc/download_stats"))
+        .and(body_json(json!({
+            "target_app": "app-1",
+            "since": "2024-03-01T00:00:00Z",
+            "until": "2024-04-01T00:00:00Z",
+            "bucket": "week"
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Range", "0-1/2")
+                .set_body_json(json!([
+                    { "period": "2024-03-04", "version": "1.0.0", "platform": "linux-x86_64", "downloads": 7 },
+                    { "period": "2024-03-11", "version": "1.1.0", "platform": "macos-aarch64", "downloads": 3 }
+                ])),
+        )
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let query = StatsQuery {
+        since: chrono::NaiveDate::from_ymd_opt(2024, 3, 1),
+        until: chrono::NaiveDate::from_ymd_opt(2024, 3, 31),
+        interval: StatsInterval::Week,
+    };
+    let stats = client.download_stats("app-1", &query).await.unwrap();
+    
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].downloads, 7);
+    assert_eq!(stats[1].platform, "macos-aarch64");
+}
+
+#[tokio::test]
+async fn moderate_returns_log_entry() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v1/rpc/moderate"))
+        .and(body_json(json!({
+            "target_app": "app-1",
+            "moderation_action": "yank",
+            "target_version": "1.0.0",
+            "moderation_reason": "malware"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "id": "log-1",
+            "actor_id": "mod-1",
+            "action": "yank",
+            "app_id": "app-1",
+            "version": "1.0.0",
+            "reason": "malware",
+            "created_at": "2024-05-01T00:00:00+00:00"
+        }])))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let entry = client
+        .moderate("app-1", ModerationAction::Yank, Some("1.0.0"), Some("malware"))
+        .await
+        .unwrap();
+    
+    assert_eq!(entry.action, ModerationAction::Yank);
+    assert_eq!(entry.version.as_deref(), Some("1.0.0"));
+    assert!(entry.app.is_none());
+}
+
+#[tokio::test]
+async fn moderation_without_role_is_unauthorized() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/v1/rpc/moderate"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "code": "42501",
+            "message": "Moderation requires the moderator or admin role"
+        })))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let err = client
+        .moderate("app-1", ModerationAction::Verify, None, None)
+        .await
+        .unwrap_err();
+    
+    assert!(matches!(err, StoreError::Unauthorized(_)));
+}
+
+#[tokio::test]
+async fn unyank_clears_yank_columns() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(path("/rest/v1/app_versions"))
+        .and(query_param("id", "eq.version-1"))
+        .and(body_json(json!({ "yanked_at": null, "yank_reason": null })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "id": "version-1",
+            "app_id": "app-1",
+            "version": "1.0.0",
+            "changelog": null,
+            "manifest_url": "https://example.com/manifest.json",
+            "wasm_url": "https://example.com/app.wasm",
+            "size_bytes": 1024,
+            "sha256": null,
+            "signature": null,
+            "is_prerelease": false,
+            "is_latest": true,
+            "yanked_at": null,
+            "yank_reason": null,
+            "created_at": "2024-01-01T00:00:00+00:00"
+        }])))
+        .mount(&server)
+        .await;
+    
+    let client = StoreClient::new(server.uri(), ANON_KEY).with_access_token("token-1");
+    let release = client.unyank_version("version-1").await.unwrap();
+    
+    assert!(!release.is_yanked());
+}
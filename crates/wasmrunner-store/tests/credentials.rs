@@ -0,0 +1,61 @@
+
+use wasmrunner_store::{AuthUser, Credentials, Session, StoreError};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("wasmrunner-store-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("auth.json")
+}
+
+fn session(expires_in: u64) -> Session {
+    Session {
+        access_token: "token-1".to_string(),
+        refresh_token: Some("refresh-1".to_string()),
+        expires_in: Some(expires_in),
+        user: AuthUser {
+            id: "user-1".to_string(),
+            email: Some("dev@example.com".to_string()),
+        },
+    }
+}
+
+#[test]
+fn saved_credentials_round_trip() {
+    let path = temp_path("round-trip");
+    let credentials = Credentials::from_session(session(3600), "dev@example.com", "http://localhost");
+    credentials.save(&path).unwrap();
+    
+    let loaded = Credentials::load(&path).unwrap();
+    assert_eq!(loaded.access_token, "token-1");
+    assert_eq!(loaded.refresh_token.as_deref(), Some("refresh-1"));
+    assert_eq!(loaded.user_id.as_deref(), Some("user-1"));
+    assert!(!loaded.needs_refresh());
+    
+    Credentials::remove(&path).unwrap();
+    assert!(matches!(Credentials::load(&path), Err(StoreError::NotLoggedIn)));
+}
+
+#[test]
+fn expiring_session_needs_refresh() {
+    let credentials = Credentials::from_session(session(30), "dev@example.com", "http://localhost");
+    assert!(credentials.needs_refresh());
+}
+
+#[cfg(unix)]
+#[test]
+fn credentials_are_private() {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let path = temp_path("private");
+    std::fs::write(&path, r#"{"access_token":"t","email":"e","supabase_url":"u"}"#).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    
+    // Loading tightens files written by older versions
+    Credentials::load(&path).unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    
+    Credentials::from_session(session(3600), "dev@example.com", "http://localhost")
+        .save(&path)
+        .unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+}
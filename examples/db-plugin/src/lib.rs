@@ -0,0 +1,34 @@
+
+//! Key-value store plugin exported as a WASM component
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+wit_bindgen::generate!({
+    world: "db-plugin",
+    exports: {
+        "wasmrunner:db/store": Store,
+    },
+});
+
+use exports::wasmrunner::db::store::Guest;
+
+thread_local! {
+    static DATA: RefCell<BTreeMap<String, String>> = RefCell::new(BTreeMap::new());
+}
+
+struct Store;
+
+impl Guest for Store {
+    fn get(key: String) -> Option<String> {
+        DATA.with(|data| data.borrow().get(&key).cloned())
+    }
+    
+    fn set(key: String, value: String) {
+        DATA.with(|data| data.borrow_mut().insert(key, value));
+    }
+    
+    fn delete(key: String) -> bool {
+        DATA.with(|data| data.borrow_mut().remove(&key).is_some())
+    }
+}
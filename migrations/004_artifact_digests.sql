@@ -0,0 +1,30 @@
+-- Artifact digests and storage for uploaded apps
+
+ALTER TABLE public.apps ADD COLUMN sha256 TEXT;
+ALTER TABLE public.app_versions ADD COLUMN sha256 TEXT;
+
+-- Storage buckets used by `wasmrunner publish`
+INSERT INTO storage.buckets (id, name, public, file_size_limit, allowed_mime_types)
+VALUES
+    ('wasm-files', 'wasm-files', false, 52428800, ARRAY['application/wasm']),
+    ('manifests', 'manifests', true, 1048576, ARRAY['application/json'])
+ON CONFLICT (id) DO NOTHING;
+
+-- Objects live under the uploader's user ID: <user_id>/<slug>/<version>.<ext>
+CREATE POLICY "WASM files accessible to authenticated users"
+    ON storage.objects FOR SELECT
+    USING (bucket_id = 'wasm-files' AND auth.role() = 'authenticated');
+
+CREATE POLICY "Users can upload own artifacts"
+    ON storage.objects FOR INSERT
+    WITH CHECK (
+        bucket_id IN ('wasm-files', 'manifests')
+        AND auth.uid()::text = (storage.foldername(name))[1]
+    );
+
+CREATE POLICY "Users can replace own artifacts"
+    ON storage.objects FOR UPDATE
+    USING (
+        bucket_id IN ('wasm-files', 'manifests')
+        AND auth.uid()::text = (storage.foldername(name))[1]
+    );
@@ -0,0 +1,23 @@
+-- Ranked full-text search over published apps
+
+-- The tsvector expression must match idx_apps_search for the index to be used
+CREATE OR REPLACE FUNCTION public.search_apps(
+    query TEXT,
+    category_filter TEXT DEFAULT NULL,
+    verified_only BOOLEAN DEFAULT false
+)
+RETURNS SETOF public.apps
+LANGUAGE sql STABLE
+AS $$
+    SELECT a.*
+    FROM public.apps a,
+         websearch_to_tsquery('english', query) q
+    WHERE a.is_published
+      AND to_tsvector('english', a.name || ' ' || COALESCE(a.description, '')) @@ q
+      AND (category_filter IS NULL OR a.category = category_filter)
+      AND (NOT verified_only OR a.is_verified)
+    ORDER BY ts_rank(to_tsvector('english', a.name || ' ' || COALESCE(a.description, '')), q) DESC,
+             a.download_count DESC
+$$;
+
+GRANT EXECUTE ON FUNCTION public.search_apps(TEXT, TEXT, BOOLEAN) TO anon, authenticated;
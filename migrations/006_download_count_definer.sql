@@ -0,0 +1,17 @@
+-- Count downloads of apps the downloader does not own
+--
+-- The trigger runs as the inserting user, and only authors may update their
+-- apps, so download_count never moved for anyone else's downloads.
+
+CREATE OR REPLACE FUNCTION update_download_count()
+RETURNS trigger
+SECURITY DEFINER
+SET search_path = public
+AS $$
+BEGIN
+    UPDATE public.apps
+    SET download_count = download_count + 1
+    WHERE id = NEW.app_id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
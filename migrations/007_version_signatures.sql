@@ -0,0 +1,6 @@
+-- Detached ed25519 signatures for published versions
+--
+-- Holds the publisher name, the signed wasm/manifest digests and the hex
+-- signature, as written by `wasmrunner publish`.
+
+ALTER TABLE public.app_versions ADD COLUMN signature JSONB;
@@ -0,0 +1,147 @@
+-- Scoped API tokens for non-interactive clients such as CI pipelines
+--
+-- A token is shown once when created; only its sha256 is stored. The
+-- exchange-token edge function trades a token for a short-lived JWT carrying
+-- the token's scopes in a `token_scopes` claim, which the restrictive
+-- policies below check. Sessions from a password login carry no such claim
+-- and keep full access.
+
+CREATE EXTENSION IF NOT EXISTS pgcrypto WITH SCHEMA extensions;
+
+CREATE TABLE public.api_tokens (
+    id UUID DEFAULT gen_random_uuid() PRIMARY KEY,
+    user_id UUID REFERENCES public.profiles(id) ON DELETE CASCADE NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT UNIQUE NOT NULL,
+    scopes TEXT[] NOT NULL CHECK (scopes <@ ARRAY['publish', 'favorites', 'reviews']),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    
+    UNIQUE(user_id, name)
+);
+
+CREATE INDEX idx_api_tokens_user ON public.api_tokens(user_id);
+
+-- Whether the caller may act with `scope`
+CREATE OR REPLACE FUNCTION public.has_scope(scope TEXT)
+RETURNS BOOLEAN
+LANGUAGE sql STABLE
+AS $$
+    SELECT COALESCE((auth.jwt() -> 'token_scopes') ? scope, true)
+$$;
+
+-- Whether the caller signed in with a password rather than an API token
+CREATE OR REPLACE FUNCTION public.is_user_session()
+RETURNS BOOLEAN
+LANGUAGE sql STABLE
+AS $$
+    SELECT auth.jwt() -> 'token_scopes' IS NULL
+$$;
+
+ALTER TABLE public.api_tokens ENABLE ROW LEVEL SECURITY;
+
+-- Tokens cannot be used to manage tokens
+CREATE POLICY "Users can view their own API tokens"
+    ON public.api_tokens FOR SELECT
+    USING (auth.uid() = user_id AND public.is_user_session());
+
+CREATE POLICY "Users can revoke their own API tokens"
+    ON public.api_tokens FOR UPDATE
+    USING (auth.uid() = user_id AND public.is_user_session());
+
+-- Returns the plaintext token; it cannot be recovered later
+CREATE OR REPLACE FUNCTION public.create_api_token(
+    token_name TEXT,
+    token_scopes TEXT[],
+    expires_in_days INTEGER DEFAULT NULL
+)
+RETURNS TABLE (
+    id UUID,
+    name TEXT,
+    scopes TEXT[],
+    created_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    token TEXT
+)
+LANGUAGE plpgsql
+SECURITY DEFINER
+SET search_path = public, extensions
+AS $$
+DECLARE
+    plaintext TEXT := 'wrt_' || encode(gen_random_bytes(32), 'hex');
+BEGIN
+    IF auth.uid() IS NULL OR NOT public.is_user_session() THEN
+        RAISE EXCEPTION 'API tokens can only be created from a user session'
+            USING ERRCODE = '42501';
+    END IF;
+    
+    RETURN QUERY
+    INSERT INTO public.api_tokens AS t (user_id, name, token_hash, scopes, expires_at)
+    VALUES (
+        auth.uid(),
+        token_name,
+        encode(digest(plaintext, 'sha256'), 'hex'),
+        token_scopes,
+        CASE WHEN expires_in_days IS NULL THEN NULL
+             ELSE NOW() + make_interval(days => expires_in_days) END
+    )
+    RETURNING t.id, t.name, t.scopes, t.created_at, t.expires_at, plaintext;
+END;
+$$;
+
+GRANT EXECUTE ON FUNCTION public.create_api_token(TEXT, TEXT[], INTEGER) TO authenticated;
+
+-- Scope checks, combined with the existing policies
+CREATE POLICY "Updating profiles requires a user session"
+    ON public.profiles AS RESTRICTIVE FOR UPDATE
+    USING (public.is_user_session());
+
+CREATE POLICY "Publishing apps requires publish scope"
+    ON public.apps AS RESTRICTIVE FOR INSERT
+    WITH CHECK (public.has_scope('publish'));
+
+CREATE POLICY "Updating apps requires publish scope"
+    ON public.apps AS RESTRICTIVE FOR UPDATE
+    USING (public.has_scope('publish'));
+
+CREATE POLICY "Adding versions requires publish scope"
+    ON public.app_versions AS RESTRICTIVE FOR INSERT
+    WITH CHECK (public.has_scope('publish'));
+
+CREATE POLICY "Updating versions requires publish scope"
+    ON public.app_versions AS RESTRICTIVE FOR UPDATE
+    USING (public.has_scope('publish'));
+
+CREATE POLICY "Deleting versions requires publish scope"
+    ON public.app_versions AS RESTRICTIVE FOR DELETE
+    USING (public.has_scope('publish'));
+
+CREATE POLICY "Uploading artifacts requires publish scope"
+    ON storage.objects AS RESTRICTIVE FOR INSERT
+    WITH CHECK (bucket_id NOT IN ('wasm-files', 'manifests') OR public.has_scope('publish'));
+
+CREATE POLICY "Replacing artifacts requires publish scope"
+    ON storage.objects AS RESTRICTIVE FOR UPDATE
+    USING (bucket_id NOT IN ('wasm-files', 'manifests') OR public.has_scope('publish'));
+
+CREATE POLICY "Adding favorites requires favorites scope"
+    ON public.user_favorites AS RESTRICTIVE FOR INSERT
+    WITH CHECK (public.has_scope('favorites'));
+
+CREATE POLICY "Removing favorites requires favorites scope"
+    ON public.user_favorites AS RESTRICTIVE FOR DELETE
+    USING (public.has_scope('favorites'));
+
+CREATE POLICY "Writing reviews requires reviews scope"
+    ON public.reviews AS RESTRICTIVE FOR INSERT
+    WITH CHECK (public.has_scope('reviews'));
+
+CREATE POLICY "Editing reviews requires reviews scope"
+    ON public.reviews AS RESTRICTIVE FOR UPDATE
+    USING (public.has_scope('reviews'));
+
+CREATE POLICY "Deleting reviews requires reviews scope"
+    ON public.reviews AS RESTRICTIVE FOR DELETE
+    USING (public.has_scope('reviews'));
@@ -0,0 +1,27 @@
+-- Keep apps.star_count in step with user_favorites
+--
+-- Runs as the definer because users may favorite apps they cannot update.
+
+CREATE OR REPLACE FUNCTION update_star_count()
+RETURNS trigger
+SECURITY DEFINER
+SET search_path = public
+AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        UPDATE public.apps SET star_count = star_count + 1 WHERE id = NEW.app_id;
+        RETURN NEW;
+    ELSE
+        UPDATE public.apps SET star_count = GREATEST(star_count - 1, 0) WHERE id = OLD.app_id;
+        RETURN OLD;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER on_favorite_changed
+    AFTER INSERT OR DELETE ON public.user_favorites
+    FOR EACH ROW EXECUTE PROCEDURE update_star_count();
+
+-- Correct counts recorded before the trigger existed
+UPDATE public.apps a
+SET star_count = (SELECT COUNT(*) FROM public.user_favorites f WHERE f.app_id = a.id);
@@ -0,0 +1,44 @@
+-- Cache each app's rating on the apps row and stamp edited reviews
+--
+-- Runs as the definer because reviewers cannot update the apps they review.
+
+ALTER TABLE public.apps
+    ADD COLUMN rating_average NUMERIC(3, 2),
+    ADD COLUMN rating_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE OR REPLACE FUNCTION update_app_rating()
+RETURNS trigger
+SECURITY DEFINER
+SET search_path = public
+AS $$
+DECLARE
+    target UUID := COALESCE(NEW.app_id, OLD.app_id);
+BEGIN
+    UPDATE public.apps
+    SET rating_average = (SELECT ROUND(AVG(rating), 2) FROM public.reviews WHERE app_id = target),
+        rating_count = (SELECT COUNT(*) FROM public.reviews WHERE app_id = target)
+    WHERE id = target;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER on_review_changed
+    AFTER INSERT OR UPDATE OF rating OR DELETE ON public.reviews
+    FOR EACH ROW EXECUTE PROCEDURE update_app_rating();
+
+CREATE OR REPLACE FUNCTION touch_review()
+RETURNS trigger AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER on_review_edited
+    BEFORE UPDATE ON public.reviews
+    FOR EACH ROW EXECUTE PROCEDURE touch_review();
+
+-- Ratings recorded before the trigger existed
+UPDATE public.apps a
+SET rating_average = (SELECT ROUND(AVG(rating), 2) FROM public.reviews r WHERE r.app_id = a.id),
+    rating_count = (SELECT COUNT(*) FROM public.reviews r WHERE r.app_id = a.id);
@@ -0,0 +1,36 @@
+-- Download counts for an app grouped by period, version and platform
+--
+-- Runs as the invoker so the downloads RLS policy limits results to the
+-- app's author; anyone else gets no rows.
+
+CREATE OR REPLACE FUNCTION public.download_stats(
+    target_app UUID,
+    since TIMESTAMPTZ DEFAULT NULL,
+    until TIMESTAMPTZ DEFAULT NULL,
+    bucket TEXT DEFAULT 'day'
+)
+RETURNS TABLE (period DATE, version TEXT, platform TEXT, downloads BIGINT)
+LANGUAGE plpgsql STABLE
+AS $$
+BEGIN
+    IF bucket NOT IN ('day', 'week', 'month') THEN
+        RAISE EXCEPTION 'bucket must be day, week or month' USING ERRCODE = '22023';
+    END IF;
+    
+    RETURN QUERY
+    SELECT date_trunc(bucket, d.created_at)::DATE,
+           d.version,
+           COALESCE(d.platform, 'unknown'),
+           COUNT(*)
+    FROM public.downloads d
+    WHERE d.app_id = target_app
+      AND (since IS NULL OR d.created_at >= since)
+      AND (until IS NULL OR d.created_at < until)
+    GROUP BY 1, 2, 3
+    ORDER BY 1, 2, 3;
+END;
+$$;
+
+GRANT EXECUTE ON FUNCTION public.download_stats(UUID, TIMESTAMPTZ, TIMESTAMPTZ, TEXT) TO authenticated;
+
+CREATE INDEX idx_downloads_app_created ON public.downloads(app_id, created_at);
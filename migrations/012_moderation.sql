@@ -0,0 +1,185 @@
+-- Moderation of apps and versions by moderators and admins
+--
+-- Moderators act through public.moderate, which checks the caller's role and
+-- records every change in moderation_log in the same transaction. Authors
+-- cannot verify or feature their own apps, nor republish an app a moderator
+-- has unpublished.
+
+ALTER TABLE public.app_versions
+    ADD COLUMN yanked_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN yank_reason TEXT;
+
+CREATE TABLE public.moderation_log (
+    id UUID DEFAULT gen_random_uuid() PRIMARY KEY,
+    actor_id UUID REFERENCES public.profiles(id) NOT NULL,
+    action TEXT NOT NULL CHECK (action IN (
+        'verify', 'unverify', 'feature', 'unfeature', 'unpublish', 'publish', 'yank', 'unyank'
+    )),
+    app_id UUID REFERENCES public.apps(id) ON DELETE SET NULL,
+    version TEXT,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_moderation_log_app ON public.moderation_log(app_id, created_at);
+
+-- Whether the caller is a moderator or admin signed in with a password
+CREATE OR REPLACE FUNCTION public.is_moderator()
+RETURNS BOOLEAN
+LANGUAGE sql STABLE
+AS $$
+    SELECT public.is_user_session() AND EXISTS (
+        SELECT 1 FROM public.profiles
+        WHERE id = auth.uid() AND role IN ('moderator', 'admin')
+    )
+$$;
+
+-- Users may update their own profile, but roles are only granted by admins
+-- and the service role
+CREATE OR REPLACE FUNCTION guard_profile_role()
+RETURNS trigger
+SECURITY DEFINER
+SET search_path = public
+AS $$
+BEGIN
+    -- The service role and migrations run without a user
+    IF NEW.role IS NOT DISTINCT FROM OLD.role OR auth.uid() IS NULL THEN
+        RETURN NEW;
+    END IF;
+    
+    IF public.is_user_session() AND EXISTS (
+        SELECT 1 FROM public.profiles
+        WHERE id = auth.uid() AND role = 'admin'
+    ) THEN
+        RETURN NEW;
+    END IF;
+    
+    RAISE EXCEPTION 'Only admins can change roles' USING ERRCODE = '42501';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER on_profile_role_change
+    BEFORE UPDATE ON public.profiles
+    FOR EACH ROW EXECUTE PROCEDURE guard_profile_role();
+
+ALTER TABLE public.moderation_log ENABLE ROW LEVEL SECURITY;
+
+-- Entries are only written by public.moderate
+CREATE POLICY "Moderators can view the moderation log"
+    ON public.moderation_log FOR SELECT
+    USING (public.is_moderator());
+
+CREATE POLICY "Moderators can view all apps"
+    ON public.apps FOR SELECT
+    USING (public.is_moderator());
+
+CREATE POLICY "Moderators can view all app versions"
+    ON public.app_versions FOR SELECT
+    USING (public.is_moderator());
+
+CREATE OR REPLACE FUNCTION guard_moderated_columns()
+RETURNS trigger
+SECURITY DEFINER
+SET search_path = public
+AS $$
+BEGIN
+    -- The service role and migrations run without a user
+    IF auth.uid() IS NULL OR public.is_moderator() THEN
+        RETURN NEW;
+    END IF;
+    
+    IF TG_OP = 'INSERT' THEN
+        IF NEW.is_verified OR NEW.is_featured THEN
+            RAISE EXCEPTION 'Only moderators can verify or feature apps' USING ERRCODE = '42501';
+        END IF;
+        RETURN NEW;
+    END IF;
+    
+    IF NEW.is_verified IS DISTINCT FROM OLD.is_verified
+        OR NEW.is_featured IS DISTINCT FROM OLD.is_featured THEN
+        RAISE EXCEPTION 'Only moderators can verify or feature apps' USING ERRCODE = '42501';
+    END IF;
+    
+    IF NEW.is_published AND NOT OLD.is_published AND (
+        SELECT m.action FROM public.moderation_log m
+        WHERE m.app_id = NEW.id AND m.action IN ('unpublish', 'publish')
+        ORDER BY m.created_at DESC
+        LIMIT 1
+    ) = 'unpublish' THEN
+        RAISE EXCEPTION 'This app was unpublished by a moderator' USING ERRCODE = '42501';
+    END IF;
+    
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER on_app_moderated_columns
+    BEFORE INSERT OR UPDATE ON public.apps
+    FOR EACH ROW EXECUTE PROCEDURE guard_moderated_columns();
+
+-- Apply a moderation action and log it; `target_version` only applies to
+-- yank and unyank, which affect every version of the app when it is NULL
+CREATE OR REPLACE FUNCTION public.moderate(
+    target_app UUID,
+    moderation_action TEXT,
+    target_version TEXT DEFAULT NULL,
+    moderation_reason TEXT DEFAULT NULL
+)
+RETURNS SETOF public.moderation_log
+LANGUAGE plpgsql
+SECURITY DEFINER
+SET search_path = public
+AS $$
+DECLARE
+    changed INTEGER;
+BEGIN
+    IF NOT public.is_moderator() THEN
+        RAISE EXCEPTION 'Moderation requires the moderator or admin role' USING ERRCODE = '42501';
+    END IF;
+    
+    IF target_version IS NOT NULL AND moderation_action NOT IN ('yank', 'unyank') THEN
+        RAISE EXCEPTION 'Only yank and unyank apply to a single version' USING ERRCODE = '22023';
+    END IF;
+    
+    CASE moderation_action
+        WHEN 'verify' THEN
+            UPDATE public.apps SET is_verified = true WHERE id = target_app;
+        WHEN 'unverify' THEN
+            UPDATE public.apps SET is_verified = false WHERE id = target_app;
+        WHEN 'feature' THEN
+            UPDATE public.apps SET is_featured = true WHERE id = target_app;
+        WHEN 'unfeature' THEN
+            UPDATE public.apps SET is_featured = false WHERE id = target_app;
+        WHEN 'unpublish' THEN
+            UPDATE public.apps SET is_published = false, is_featured = false WHERE id = target_app;
+        WHEN 'publish' THEN
+            UPDATE public.apps SET is_published = true WHERE id = target_app;
+        WHEN 'yank' THEN
+            UPDATE public.app_versions
+            SET yanked_at = NOW(), yank_reason = moderation_reason
+            WHERE app_id = target_app
+              AND (target_version IS NULL OR version = target_version)
+              AND yanked_at IS NULL;
+        WHEN 'unyank' THEN
+            UPDATE public.app_versions
+            SET yanked_at = NULL, yank_reason = NULL
+            WHERE app_id = target_app
+              AND (target_version IS NULL OR version = target_version)
+              AND yanked_at IS NOT NULL;
+        ELSE
+            RAISE EXCEPTION 'Unknown moderation action %', moderation_action USING ERRCODE = '22023';
+    END CASE;
+    
+    GET DIAGNOSTICS changed = ROW_COUNT;
+    IF changed = 0 THEN
+        RAISE EXCEPTION 'Nothing to %', moderation_action USING ERRCODE = 'P0002';
+    END IF;
+    
+    RETURN QUERY
+    INSERT INTO public.moderation_log (actor_id, action, app_id, version, reason)
+    VALUES (auth.uid(), moderation_action, target_app, target_version, moderation_reason)
+    RETURNING *;
+END;
+$$;
+
+GRANT EXECUTE ON FUNCTION public.moderate(UUID, TEXT, TEXT, TEXT) TO authenticated;
@@ -0,0 +1,56 @@
+-- Deprecation notices, and keeping moderator yanks out of authors' hands
+--
+-- Authors yank their own versions by setting app_versions.yanked_at (added in
+-- 012_moderation.sql). A version a moderator yanked can only be restored by a
+-- moderator, whether by unyanking it or by deleting and publishing it again.
+
+ALTER TABLE public.apps ADD COLUMN deprecation_message TEXT;
+ALTER TABLE public.app_versions ADD COLUMN deprecation_message TEXT;
+
+-- Whether the latest moderator yank or unyank of this version, or of every
+-- version of the app, was a yank
+CREATE OR REPLACE FUNCTION moderator_yanked(target_app UUID, target_version TEXT)
+RETURNS BOOLEAN
+LANGUAGE sql STABLE
+AS $$
+    SELECT COALESCE((
+        SELECT m.action = 'yank' FROM public.moderation_log m
+        WHERE m.app_id = target_app
+          AND m.action IN ('yank', 'unyank')
+          AND (m.version IS NULL OR m.version = target_version)
+        ORDER BY m.created_at DESC
+        LIMIT 1
+    ), false)
+$$;
+
+CREATE OR REPLACE FUNCTION guard_moderator_yank()
+RETURNS trigger
+SECURITY DEFINER
+SET search_path = public
+AS $$
+BEGIN
+    IF auth.uid() IS NULL OR public.is_moderator() THEN
+        RETURN COALESCE(NEW, OLD);
+    END IF;
+    
+    -- Deleting the version and publishing it again would lift the yank too;
+    -- while the row stays, the version cannot be inserted again
+    IF TG_OP = 'DELETE' THEN
+        IF OLD.yanked_at IS NOT NULL AND moderator_yanked(OLD.app_id, OLD.version) THEN
+            RAISE EXCEPTION 'This version was yanked by a moderator' USING ERRCODE = '42501';
+        END IF;
+        RETURN OLD;
+    END IF;
+    
+    IF OLD.yanked_at IS NOT NULL AND NEW.yanked_at IS NULL
+        AND moderator_yanked(NEW.app_id, NEW.version) THEN
+        RAISE EXCEPTION 'This version was yanked by a moderator' USING ERRCODE = '42501';
+    END IF;
+    
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER on_version_unyanked
+    BEFORE DELETE OR UPDATE OF yanked_at ON public.app_versions
+    FOR EACH ROW EXECUTE PROCEDURE guard_moderator_yank();
@@ -0,0 +1,58 @@
+-- Create an app together with its first version
+--
+-- `wasmrunner publish` used to insert the app and then the version, so a
+-- failed version insert left an app with no versions behind. Both rows are
+-- now written in one transaction. The function runs as the caller, so the
+-- row level security and scope policies on both tables still apply.
+
+CREATE OR REPLACE FUNCTION public.create_app_with_version(app JSONB, first_version JSONB)
+RETURNS SETOF public.app_versions
+LANGUAGE plpgsql
+SET search_path = public
+AS $$
+DECLARE
+    new_app_id UUID;
+BEGIN
+    INSERT INTO public.apps (
+        name, slug, version, author_id, manifest_url, wasm_url, description, category, tags,
+        size_bytes, sha256, is_published
+    )
+    VALUES (
+        app ->> 'name',
+        app ->> 'slug',
+        app ->> 'version',
+        (app ->> 'author_id')::UUID,
+        app ->> 'manifest_url',
+        app ->> 'wasm_url',
+        app ->> 'description',
+        COALESCE(app ->> 'category', 'utility'),
+        ARRAY(SELECT jsonb_array_elements_text(COALESCE(app -> 'tags', '[]'::JSONB))),
+        COALESCE((app ->> 'size_bytes')::BIGINT, 0),
+        app ->> 'sha256',
+        COALESCE((app ->> 'is_published')::BOOLEAN, false)
+    )
+    RETURNING id INTO new_app_id;
+    
+    -- Any app_id in `first_version` is ignored in favour of the new app's
+    RETURN QUERY
+    INSERT INTO public.app_versions (
+        app_id, version, changelog, manifest_url, wasm_url, size_bytes, sha256, signature,
+        is_prerelease, is_latest
+    )
+    VALUES (
+        new_app_id,
+        first_version ->> 'version',
+        first_version ->> 'changelog',
+        first_version ->> 'manifest_url',
+        first_version ->> 'wasm_url',
+        COALESCE((first_version ->> 'size_bytes')::BIGINT, 0),
+        first_version ->> 'sha256',
+        first_version -> 'signature',
+        COALESCE((first_version ->> 'is_prerelease')::BOOLEAN, false),
+        COALESCE((first_version ->> 'is_latest')::BOOLEAN, false)
+    )
+    RETURNING *;
+END;
+$$;
+
+GRANT EXECUTE ON FUNCTION public.create_app_with_version(JSONB, JSONB) TO authenticated;
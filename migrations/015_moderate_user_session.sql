@@ -0,0 +1,74 @@
+-- Keep API tokens out of moderation
+--
+-- public.moderate is SECURITY DEFINER, so the scope policies from
+-- 008_api_tokens.sql never see its writes. A moderator's token of any scope
+-- must not be able to verify, feature, unpublish or yank apps; only a
+-- password session may moderate.
+
+CREATE OR REPLACE FUNCTION public.moderate(
+    target_app UUID,
+    moderation_action TEXT,
+    target_version TEXT DEFAULT NULL,
+    moderation_reason TEXT DEFAULT NULL
+)
+RETURNS SETOF public.moderation_log
+LANGUAGE plpgsql
+SECURITY DEFINER
+SET search_path = public
+AS $$
+DECLARE
+    changed INTEGER;
+BEGIN
+    IF auth.uid() IS NULL OR NOT public.is_user_session() THEN
+        RAISE EXCEPTION 'Moderation requires signing in with a password; API tokens cannot moderate'
+            USING ERRCODE = '42501';
+    END IF;
+    
+    IF NOT public.is_moderator() THEN
+        RAISE EXCEPTION 'Moderation requires the moderator or admin role' USING ERRCODE = '42501';
+    END IF;
+    
+    IF target_version IS NOT NULL AND moderation_action NOT IN ('yank', 'unyank') THEN
+        RAISE EXCEPTION 'Only yank and unyank apply to a single version' USING ERRCODE = '22023';
+    END IF;
+    
+    CASE moderation_action
+        WHEN 'verify' THEN
+            UPDATE public.apps SET is_verified = true WHERE id = target_app;
+        WHEN 'unverify' THEN
+            UPDATE public.apps SET is_verified = false WHERE id = target_app;
+        WHEN 'feature' THEN
+            UPDATE public.apps SET is_featured = true WHERE id = target_app;
+        WHEN 'unfeature' THEN
+            UPDATE public.apps SET is_featured = false WHERE id = target_app;
+        WHEN 'unpublish' THEN
+            UPDATE public.apps SET is_published = false, is_featured = false WHERE id = target_app;
+        WHEN 'publish' THEN
+            UPDATE public.apps SET is_published = true WHERE id = target_app;
+        WHEN 'yank' THEN
+            UPDATE public.app_versions
+            SET yanked_at = NOW(), yank_reason = moderation_reason
+            WHERE app_id = target_app
+              AND (target_version IS NULL OR version = target_version)
+              AND yanked_at IS NULL;
+        WHEN 'unyank' THEN
+            UPDATE public.app_versions
+            SET yanked_at = NULL, yank_reason = NULL
+            WHERE app_id = target_app
+              AND (target_version IS NULL OR version = target_version)
+              AND yanked_at IS NOT NULL;
+        ELSE
+            RAISE EXCEPTION 'Unknown moderation action %', moderation_action USING ERRCODE = '22023';
+    END CASE;
+    
+    GET DIAGNOSTICS changed = ROW_COUNT;
+    IF changed = 0 THEN
+        RAISE EXCEPTION 'Nothing to %', moderation_action USING ERRCODE = 'P0002';
+    END IF;
+    
+    RETURN QUERY
+    INSERT INTO public.moderation_log (actor_id, action, app_id, version, reason)
+    VALUES (auth.uid(), moderation_action, target_app, target_version, moderation_reason)
+    RETURNING *;
+END;
+$$;
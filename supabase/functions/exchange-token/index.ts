@@ -0,0 +1,80 @@
+// Exchanges a WasmRunner API token for a short-lived JWT carrying its scopes.
+//
+// Requires the JWT_SECRET secret to hold the project's JWT secret:
+//   supabase secrets set JWT_SECRET=...
+
+import { serve } from 'https://deno.land/std@0.168.0/http/server.ts'
+import { createClient } from 'https://esm.sh/@supabase/supabase-js@2'
+import { create, getNumericDate } from 'https://deno.land/x/djwt@v2.8/mod.ts'
+
+const TOKEN_LIFETIME_SECS = 15 * 60
+
+const supabase = createClient(
+  Deno.env.get('SUPABASE_URL')!,
+  Deno.env.get('SUPABASE_SERVICE_ROLE_KEY')!,
+)
+
+async function sha256Hex(text: string): Promise<string> {
+  const digest = await crypto.subtle.digest('SHA-256', new TextEncoder().encode(text))
+  return Array.from(new Uint8Array(digest))
+    .map((b) => b.toString(16).padStart(2, '0'))
+    .join('')
+}
+
+function error(status: number, message: string): Response {
+  return new Response(JSON.stringify({ error: 'invalid_token', error_description: message }), {
+    status,
+    headers: { 'Content-Type': 'application/json' },
+  })
+}
+
+serve(async (req) => {
+  const { token } = await req.json().catch(() => ({}))
+  if (typeof token !== 'string' || !token.startsWith('wrt_')) {
+    return error(400, 'Missing or malformed API token')
+  }
+
+  const { data: row } = await supabase
+    .from('api_tokens')
+    .select('id, user_id, scopes, expires_at, revoked_at, profiles(email)')
+    .eq('token_hash', await sha256Hex(token))
+    .maybeSingle()
+
+  if (!row || row.revoked_at || (row.expires_at && new Date(row.expires_at) <= new Date())) {
+    return error(401, 'API token is invalid, expired or revoked')
+  }
+
+  await supabase.from('api_tokens').update({ last_used_at: new Date().toISOString() }).eq('id', row.id)
+
+  const key = await crypto.subtle.importKey(
+    'raw',
+    new TextEncoder().encode(Deno.env.get('JWT_SECRET')!),
+    { name: 'HMAC', hash: 'SHA-256' },
+    false,
+    ['sign'],
+  )
+  const email = row.profiles?.email ?? null
+  const accessToken = await create(
+    { alg: 'HS256', typ: 'JWT' },
+    {
+      sub: row.user_id,
+      email,
+      role: 'authenticated',
+      aud: 'authenticated',
+      exp: getNumericDate(TOKEN_LIFETIME_SECS),
+      token_scopes: row.scopes,
+    },
+    key,
+  )
+
+  return new Response(
+    JSON.stringify({
+      access_token: accessToken,
+      token_type: 'bearer',
+      expires_in: TOKEN_LIFETIME_SECS,
+      refresh_token: null,
+      user: { id: row.user_id, email },
+    }),
+    { headers: { 'Content-Type': 'application/json' } },
+  )
+})
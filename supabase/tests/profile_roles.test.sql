@@ -0,0 +1,49 @@
+-- Roles on profiles can only be changed by admins and the service role
+
+BEGIN;
+CREATE EXTENSION IF NOT EXISTS pgtap WITH SCHEMA extensions;
+SELECT plan(4);
+
+-- handle_new_user creates a profile for each
+INSERT INTO auth.users (id, email) VALUES
+    ('00000000-0000-0000-0000-000000000001', 'user@example.com'),
+    ('00000000-0000-0000-0000-000000000002', 'admin@example.com');
+
+-- Without a user, as the service role does
+SELECT lives_ok(
+    $$ UPDATE public.profiles SET role = 'admin' WHERE id = '00000000-0000-0000-0000-000000000002' $$,
+    'The service role can grant roles'
+);
+
+SET LOCAL ROLE authenticated;
+SELECT set_config('request.jwt.claims', '{"sub": "00000000-0000-0000-0000-000000000001", "role": "authenticated"}', true);
+
+SELECT throws_ok(
+    $$ UPDATE public.profiles SET role = 'admin' WHERE id = '00000000-0000-0000-0000-000000000001' $$,
+    '42501',
+    'Only admins can change roles',
+    'Users cannot change their own role'
+);
+
+SELECT lives_ok(
+    $$ UPDATE public.profiles SET full_name = 'A User' WHERE id = '00000000-0000-0000-0000-000000000001' $$,
+    'Users can still update the rest of their profile'
+);
+
+-- A token session's update matches no rows
+SELECT set_config(
+    'request.jwt.claims',
+    '{"sub": "00000000-0000-0000-0000-000000000001", "role": "authenticated", "token_scopes": ["publish"]}',
+    true
+);
+UPDATE public.profiles SET full_name = 'A Token' WHERE id = '00000000-0000-0000-0000-000000000001';
+
+RESET ROLE;
+SELECT is(
+    (SELECT full_name FROM public.profiles WHERE id = '00000000-0000-0000-0000-000000000001'),
+    'A User',
+    'API tokens cannot update profiles'
+);
+
+SELECT * FROM finish();
+ROLLBACK;